@@ -42,16 +42,16 @@ fn main() {
     if tmp.len() < 1 {
         panic!("USAGE: ./3d [music_file]");
     }
-    let fmod = match rfmod::Sys::new() {
+    let builder = match rfmod::Sys::builder() {
         Ok(f) => f,
         Err(e) => {
-            panic!("Sys::new() : {:?}", e);
+            panic!("Sys::builder() : {:?}", e);
         }
     };
 
-    match fmod.init_with_parameters(10i32, rfmod::InitFlag(rfmod::INIT_NORMAL)) {
-        rfmod::Status::Ok => {}
-        e => {
+    let fmod = match builder.init_with_parameters(10i32, rfmod::INIT_NORMAL) {
+        Ok(f) => f,
+        Err(e) => {
             panic!("FmodSys.init failed : {:?}", e);
         }
     };
@@ -61,18 +61,18 @@ fn main() {
     println!("=========================================");
 
     let arg1 = tmp.get(0).unwrap();
-    let sound = match fmod.create_sound((*arg1).as_ref(), Some(rfmod::Mode(rfmod::_3D | rfmod::SOFTWARE)), None) {
+    let sound = match fmod.create_sound((*arg1).as_ref(), Some(rfmod::_3D | rfmod::SOFTWARE), None) {
         Ok(s) => s,
         Err(e) => panic!("create sound error: {:?}", e)
     };
-    sound.set_3D_min_max_distance(4f32, 10000f32);
-    sound.set_mode(rfmod::Mode(rfmod::LOOP_NORMAL));
+    sound.set_3D_min_max_distance(4f32, 10000f32).unwrap();
+    sound.set_mode(rfmod::LOOP_NORMAL).unwrap();
 
     let chan = match sound.play() {
         Ok(c) => c,
         Err(e) => panic!("sound.play error: {:?}", e)
     };
-    chan.set_3D_attributes(&rfmod::Vector{x: -10f32, y: 0f32, z: 0f32}, &Default::default());
+    chan.set_3D_attributes(&rfmod::Vector{x: -10f32, y: 0f32, z: 0f32}, &Default::default()).unwrap();
 
     let mut last_pos = rfmod::Vector::new();
     let mut listener_pos = rfmod::Vector::new();
@@ -98,12 +98,12 @@ fn main() {
         t += 30f32 * (1f32 / interface_update_time);
 
         last_pos = listener_pos;
-        fmod.set_3D_listener_attributes(0, &listener_pos, &vel, &forward, &up);
+        fmod.set_3D_listener_attributes(0, &listener_pos, &vel, &forward, &up).unwrap();
 
         let mut tmp = "|.......................<1>......................<2>....................|\r".to_owned();
         unsafe { (tmp.as_mut_vec().as_mut() as &mut [u8])[(listener_pos.x as isize + 35isize) as usize] = 'L' as u8; }
         print!("{}", tmp);
-        fmod.update();
+        fmod.update().unwrap();
         sleep(Duration::from_millis(interface_update_time as u64 - 1));
     }
 }
\ No newline at end of file
@@ -29,7 +29,6 @@
 
 extern crate rfmod;
 
-use std::default::Default;
 use std::io::{BufRead, Write};
 
 #[allow(unused_must_use)]
@@ -55,22 +54,42 @@ fn get_key() -> char {
     }
 }
 
-fn my_DSP_callback(dsp_state: &rfmod::DspState, inbuffer: &mut [f32], outbuffer: &mut [f32], length: u32, in_channels: i32,
-    out_channels: i32) -> rfmod::Status {
-    for it in 0usize..(inbuffer.len() - 1usize) {
-        outbuffer[it] = inbuffer[it] * 0.2f32;
+struct Attenuator {
+    volume: f32,
+}
+
+impl rfmod::DspProcessor for Attenuator {
+    fn process(&mut self, input: &[f32], output: &mut [f32], length: u32, in_channels: i32,
+               out_channels: i32) -> rfmod::Status {
+        for count in 0..length {
+            for count2 in 0..out_channels {
+                /*
+                    This DSP filter just lowers the volume!
+                    Input is modified, and sent to output.
+                */
+                output[((count as i32 * out_channels) + count2) as usize] =
+                    input[((count as i32 * in_channels) + count2) as usize] * self.volume;
+            }
+        }
+        rfmod::Status::Ok
     }
-    for count in 0..length {
-        for count2 in 0..out_channels {
-            /* 
-                This DSP filter just halves the volume!
-                Input is modified, and sent to output.
-            */
-            outbuffer[((count as i32 * out_channels) + count2) as usize] = inbuffer[((count as i32 * in_channels) + count2) as usize] * 0.2f32;
+
+    fn set_parameter(&mut self, index: i32, value: f32) -> rfmod::Status {
+        match index {
+            0 => {
+                self.volume = value;
+                rfmod::Status::Ok
+            }
+            _ => rfmod::Status::InvalidParam
         }
-    } 
+    }
 
-    rfmod::Status::Ok
+    fn get_parameter(&mut self, index: i32) -> Result<(f32, String), rfmod::Status> {
+        match index {
+            0 => Ok((self.volume, format!("{:.0}", self.volume * 100f32))),
+            _ => Err(rfmod::Status::InvalidParam)
+        }
+    }
 }
 
 fn main() {
@@ -85,16 +104,16 @@ fn main() {
     if tmp.len() < 1 {
         panic!("USAGE: ./dsp_custom [music_file]");
     }
-    let fmod = match rfmod::Sys::new() {
+    let builder = match rfmod::Sys::builder() {
         Ok(f) => f,
         Err(e) => {
-            panic!("Sys::new() : {:?}", e);
+            panic!("Sys::builder() : {:?}", e);
         }
     };
 
-    match fmod.init() {
-        rfmod::Status::Ok => {}
-        e => {
+    let fmod = match builder.init() {
+        Ok(f) => f,
+        Err(e) => {
             panic!("FmodSys.init failed : {:?}", e);
         }
     };
@@ -102,7 +121,7 @@ fn main() {
     let arg1 = tmp.get(0).unwrap();
 
     let sound = match fmod.create_sound((*arg1).as_ref(),
-        Some(rfmod::Mode(rfmod::SOFTWARE | rfmod::LOOP_NORMAL)), None) {
+        Some(rfmod::SOFTWARE | rfmod::LOOP_NORMAL), None) {
         Ok(s) => s,
         Err(err) => {
             panic!("FmodSys.create_sound failed : {:?}", err);
@@ -122,19 +141,23 @@ fn main() {
         }
     };
 
-    let mut description : rfmod::DspDescription = Default::default();
-
-    description.read = Some(my_DSP_callback as fn(&_, &mut _, &mut _, _, _, _) -> _);
-    description.name = "test".to_owned();
-
-    let dsp = match fmod.create_DSP_with_description(&mut description) {
+    let volume = rfmod::DspParameterDesc {
+        min: 0f32,
+        max: 1f32,
+        default_val: 0.2f32,
+        name: "volume".to_owned(),
+        label: "%".to_owned(),
+        description: "Output volume".to_owned(),
+    };
+    let dsp = match fmod.create_DSP_from_processor("test", &[volume],
+                                                   Box::new(Attenuator { volume: 0.2f32 })) {
         Ok(dsp) => dsp,
         Err(e) => {
-            panic!("FmodSys.create_DSP_with_description failed : {:?}", e);
+            panic!("FmodSys.create_DSP_from_processor failed : {:?}", e);
         }
     };
 
-    dsp.set_bypass(true);
+    dsp.set_bypass(true).unwrap();
     let connection = match fmod.add_DSP(&dsp) {
         Ok(c) => c,
         Err(e) => {
@@ -146,9 +169,9 @@ fn main() {
     loop {
         match get_key() as char {
             'f' => {
-                dsp.set_bypass(active);
+                dsp.set_bypass(active).unwrap();
                 active = !active;
-                fmod.update();
+                fmod.update().unwrap();
             }
             c if c == 27u8 as char => break,
             _ => {}
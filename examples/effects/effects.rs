@@ -58,19 +58,19 @@ fn switch_dsp_state(dsp: &rfmod::Dsp, fmod: &rfmod::Sys, dsp_type: isize) {
         Ok(c) => c,
         Err(_) => return
     } {
-        dsp.remove();
+        dsp.remove().unwrap();
     } else {
         match fmod.add_DSP(dsp) { _ => {}};
         match dsp_type {
             3 => {
-                dsp.set_parameter(rfmod::DspTypeEcho::Delay as i32, 50f32);
+                dsp.set_parameter(rfmod::DspTypeEcho::Delay as i32, 50f32).unwrap();
             },
             5 => {
-                dsp.set_parameter(rfmod::DspDistortion::Level as i32, 0.8f32);
+                dsp.set_parameter(rfmod::DspDistortion::Level as i32, 0.8f32).unwrap();
             },
             7 => {
-                dsp.set_parameter(rfmod::DspTypeParameq::Center as i32, 5000f32);
-                dsp.set_parameter(rfmod::DspTypeParameq::Gain as i32, 0f32);
+                dsp.set_parameter(rfmod::DspTypeParameq::Center as i32, 5000f32).unwrap();
+                dsp.set_parameter(rfmod::DspTypeParameq::Gain as i32, 0f32).unwrap();
             }
             _ => {}
         };
@@ -89,16 +89,16 @@ fn main() {
     if tmp.len() < 1 {
         panic!("USAGE: ./effects [music_file]");
     }
-    let fmod = match rfmod::Sys::new() {
+    let builder = match rfmod::Sys::builder() {
         Ok(f) => f,
         Err(e) => {
-            panic!("Sys::new() : {:?}", e);
+            panic!("Sys::builder() : {:?}", e);
         }
     };
 
-    match fmod.init_with_parameters(32i32, rfmod::InitFlag(rfmod::INIT_NORMAL)) {
-        rfmod::Status::Ok => {}
-        e => {
+    let fmod = match builder.init_with_parameters(32i32, rfmod::INIT_NORMAL) {
+        Ok(f) => f,
+        Err(e) => {
             panic!("Sys::init() failed : {:?}", e);
         }
     };
@@ -108,11 +108,11 @@ fn main() {
     println!("==============================================");
 
     let arg1 = tmp.get(0).unwrap();
-    let sound = match fmod.create_sound(&(*arg1), Some(rfmod::Mode(rfmod::SOFTWARE)), None) {
+    let sound = match fmod.create_sound(&(*arg1), Some(rfmod::SOFTWARE), None) {
         Ok(s) => s,
         Err(e) => panic!("create sound error: {:?}", e)
     };
-    sound.set_mode(rfmod::Mode(rfmod::LOOP_NORMAL));
+    sound.set_mode(rfmod::LOOP_NORMAL).unwrap();
 
     match sound.play() {
         Ok(_) => {},
@@ -175,7 +175,7 @@ fn main() {
             },
             Err(e) => panic!("Entry error: {:?}", e)
         }
-        fmod.update();
+        fmod.update().unwrap();
         sleep(Duration::from_millis(30)); // let time to the system for update
     }
 }
\ No newline at end of file
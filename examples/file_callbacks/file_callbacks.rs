@@ -26,33 +26,36 @@
 
 extern crate rfmod;
 
-use rfmod::SeekStyle;
-
-#[allow(unused_variables)]
-fn my_open(music_name: &str, unicode: i32) -> Option<(rfmod::FmodFile, Option<rfmod::UserData>)> {
-    println!("Let's start by opening {} !", music_name);
-    let file = match rfmod::FmodFile::open(music_name) {
-        Some(f) => f,
-        None => panic!("Couldn't open: {}", music_name)
-    };
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::Path;
 
-    Some((file, None))
+/* file wrapper tracing what FMOD does with it */
+struct TracedFile {
+    file: File
 }
 
-#[allow(unused_variables)]
-fn my_close(music_file: &mut rfmod::FmodFile, user_data: Option<&mut rfmod::UserData>) {
-    println!("This is the end !");
-    music_file.close();
+impl Read for TracedFile {
+    fn read(&mut self, buffer: &mut [u8]) -> io::Result<usize> {
+        self.file.read(buffer)
+    }
 }
 
-#[allow(unused_variables)]
-fn my_read(handle: &mut rfmod::FmodFile, buffer: &mut [u8], size_to_read: u32, user_data: Option<&mut rfmod::UserData>) -> usize {
-    handle.read(buffer)
+impl Seek for TracedFile {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.file.seek(pos)
+    }
 }
 
-#[allow(unused_variables)]
-fn my_seek(handle: &mut rfmod::FmodFile, pos: u32, user_data: Option<&mut rfmod::UserData>) {
-    handle.seek(pos as i64, SeekStyle::SeekSet);
+impl Drop for TracedFile {
+    fn drop(&mut self) {
+        println!("This is the end !");
+    }
+}
+
+fn my_open(music_name: &Path) -> io::Result<Box<dyn rfmod::ReadSeek>> {
+    println!("Let's start by opening {} !", music_name.display());
+    Ok(Box::new(TracedFile { file: File::open(music_name)? }))
 }
 
 fn main() {
@@ -67,27 +70,23 @@ fn main() {
     if tmp.len() < 1 {
         panic!("USAGE: ./file_callback [music_file]");
     }
-    let fmod = match rfmod::Sys::new() {
+    let builder = match rfmod::Sys::builder() {
         Ok(f) => f,
         Err(e) => {
-            panic!("Sys::new() : {:?}", e);
+            panic!("Sys::builder() : {:?}", e);
         }
     };
 
-    match fmod.init_with_parameters(1i32, rfmod::InitFlag(rfmod::INIT_NORMAL)) {
-        rfmod::Status::Ok => {}
-        e => {
+    let fmod = match builder.init_with_parameters(1i32, rfmod::INIT_NORMAL) {
+        Ok(f) => f,
+        Err(e) => {
             panic!("Sys::init() failed : {:?}", e);
         }
     };
 
-    match fmod.set_file_system(Some(my_open as fn(&_, _) -> _),
-        Some(my_close as fn(&mut _, Option<&mut _>)),
-        Some(my_read as fn(&mut _, &mut _, _, Option<&mut _>) -> _),
-        Some(my_seek as fn(&mut _, _, Option<&mut _>)),
-        2048i32) {
-        rfmod::Status::Ok => {}
-        e => {
+    match fmod.set_file_system(my_open, 2048i32) {
+        Ok(_) => {}
+        Err(e) => {
             panic!("FmodSys.set_file_system failed : {:?}", e);
         }
     };
@@ -98,7 +97,7 @@ fn main() {
 
     let arg1 = tmp.get(0).unwrap();
     let sound = match fmod.create_stream((*arg1).as_ref(),
-        Some(rfmod::Mode(rfmod::_2D | rfmod::HARDWARE | rfmod::LOOP_OFF)), None)
+        Some(rfmod::_2D | rfmod::HARDWARE | rfmod::LOOP_OFF), None)
     {
         Ok(s) => s,
         Err(e) => panic!("create sound error: {:?}", e)
@@ -110,7 +109,7 @@ fn main() {
     };
 
     let length = match sound.get_length(rfmod::TIMEUNIT_MS) {
-        Ok(l) => l,
+        Ok(l) => l.value(),
         Err(e) => panic!("sound.get_length error: {:?}", e)
     };
     while match chan.is_playing() {
@@ -121,7 +120,7 @@ fn main() {
         }
     } {
         let position = match chan.get_position(rfmod::TIMEUNIT_MS) {
-            Ok(p) => p,
+            Ok(p) => p.value(),
             Err(e) => {
                 println!("channel.get_position failed: {:?}", e);
                 return;
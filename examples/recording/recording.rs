@@ -54,10 +54,10 @@ fn get_key() -> Result<isize, Error> {
 }
 
 fn main() {
-    let fmod = match rfmod::Sys::new() {
+    let builder = match rfmod::Sys::builder() {
         Ok(f) => f,
         Err(e) => {
-            panic!("Sys::new() : {:?}", e);
+            panic!("Sys::builder() : {:?}", e);
         }
     };
 
@@ -76,10 +76,10 @@ fn main() {
         match get_key() {
             Ok(n) => {
                 match match n {
-                    1 => Some(fmod.set_output(rfmod::OutputType::OSS)),
-                    2 => Some(fmod.set_output(rfmod::OutputType::ALSA)),
-                    3 => Some(fmod.set_output(rfmod::OutputType::ESD)),
-                    4 => Some(fmod.set_output(rfmod::OutputType::PulseAudio)),
+                    1 => Some(builder.set_output(rfmod::OutputType::OSS)),
+                    2 => Some(builder.set_output(rfmod::OutputType::ALSA)),
+                    3 => Some(builder.set_output(rfmod::OutputType::ESD)),
+                    4 => Some(builder.set_output(rfmod::OutputType::PulseAudio)),
                     -1 => {
                         return;
                     }
@@ -95,7 +95,7 @@ fn main() {
         }
     }
 
-    let num_drivers = match match fmod.get_num_drivers() {
+    let num_drivers = match match builder.get_num_drivers() {
         Ok(n) => n as usize,
         Err(e) => panic!("rfmod.get_num_drivers failed: {:?}", e)
     } {
@@ -109,7 +109,7 @@ fn main() {
     println!("--------------------------------");
     while it < num_drivers as i32 {
         //check this function
-        let t = match fmod.get_driver_info(it, 256usize) {
+        let t = match builder.get_driver_info(it, 256usize) {
             Ok((_, name)) => name,
             Err(e) => panic!("get_driver_info error: {:?}", e)
         };
@@ -123,7 +123,7 @@ fn main() {
                 match nb {
                     -1 => return,
                     nb if nb < num_drivers as isize => {
-                        fmod.set_driver(nb as i32);
+                        builder.set_driver(nb as i32).unwrap();
                         break;
                     }
                     _ => {
@@ -137,9 +137,9 @@ fn main() {
         }
     }
 
-    match fmod.init() {
-        rfmod::Status::Ok => {}
-        e => {
+    let fmod = match builder.init() {
+        Ok(f) => f,
+        Err(e) => {
             panic!("FmodSys.init failed : {:?}", e);
         }
     };
@@ -152,7 +152,7 @@ fn main() {
     exinfo.default_frequency = 44100;
     exinfo.length            = (exinfo.default_frequency * mem::size_of::<i16>() as i32 * exinfo.num_channels * secs) as u32;
 
-    let sound = match fmod.create_sound("", Some(rfmod::Mode(rfmod::_2D | rfmod::SOFTWARE | rfmod::OPENUSER)),
+    let sound = match fmod.create_sound("", Some(rfmod::_2D | rfmod::SOFTWARE | rfmod::OPENUSER),
         Some(&mut exinfo)) {
         Ok(s) => s,
         Err(e) => panic!("create sound error: {:?}", e)
@@ -173,7 +173,7 @@ fn main() {
                 match match nb {
                     0 => {
                         match fmod.start_record(record_driver, &sound, false) {
-                            rfmod::Status::Ok => {
+                            Ok(_) => {
                                 while match fmod.is_recording(record_driver) {
                                     Ok(r) => r,
                                     Err(e) => {
@@ -188,18 +188,18 @@ fn main() {
                                             return;
                                         }
                                     });
-                                    fmod.update();
+                                    fmod.update().unwrap();
                                     sleep(Duration::from_millis(15))
                                 }
-                                Some(rfmod::Status::Ok)
+                                Some(Ok(()))
                             }
-                            e => Some(e)
+                            Err(e) => Some(Err(e))
                         }
                     },
                     1 => {
                         match sound.play() {
                             Ok(chan) => {
-                                fmod.update();
+                                fmod.update().unwrap();
                                 while match chan.is_playing() {
                                     Ok(p) => p,
                                     Err(e) => {
@@ -208,24 +208,24 @@ fn main() {
                                     }
                                 } {
                                     print!("\rPlaying : {} / {}", match chan.get_position(rfmod::TIMEUNIT_MS) {
-                                        Ok(l) => l,
+                                        Ok(l) => l.value(),
                                         Err(e) => {
                                             println!("channel.get_position failed: {:?}", e);
                                             return;
                                         }
                                     }, match sound.get_length(rfmod::TIMEUNIT_MS) {
-                                        Ok(l) => l,
+                                        Ok(l) => l.value(),
                                         Err(e) => {
                                             println!("sound.get_length failed: {:?}", e);
                                             return;
                                         }
                                     });
-                                    fmod.update();
+                                    fmod.update().unwrap();
                                     sleep(Duration::from_millis(15));
                                 }
-                                Some(rfmod::Status::Ok)
+                                Some(Ok(()))
                             }
-                            Err(e) => Some(e)
+                            Err(e) => Some(Err(e))
                         }
                     },
                     2 => {
@@ -238,12 +238,9 @@ fn main() {
                             Ok(_) => {
                                 name.pop().unwrap();
                                 match sound.save_to_wav(&name) {
-                                    Ok(b) => if b {
+                                    Ok(()) => {
                                         println!("export succeeded");
                                         None
-                                    } else {
-                                        println!("export failed");
-                                        None
                                     },
                                     Err(e) => {
                                         println!("save_to_wav error: {}", e);
@@ -257,8 +254,8 @@ fn main() {
                     -1 => break,
                     _ => None
                 } {
-                    Some(r) if r == rfmod::Status::Ok => {}
-                    Some(e) => {
+                    Some(Ok(())) => {}
+                    Some(Err(e)) => {
                         println!("Error : {:?}", e);
                         break;
                     }
@@ -29,16 +29,16 @@ extern crate rfmod;
 use std::thread::sleep;
 use std::time::Duration;
 
-fn play_to_the_end(sound: rfmod::Sound, len: usize) -> rfmod::Status {
+fn play_to_the_end(sound: rfmod::Sound, len: usize) -> Result<(), rfmod::Error> {
     let length = match sound.get_length(rfmod::TIMEUNIT_MS) {
-        Ok(l) => l,
+        Ok(l) => l.value(),
         Err(e) => panic!("sound.get_length error: {:?}", e)
     };
     let name = match sound.get_name(len) {
         Ok(n) => n,
         Err(e) => panic!("sound.get_name error: {:?}", e)
     };
-    let mut old_position = 100usize;
+    let mut old_position = 100u32;
 
     match sound.play() {
         Ok(chan) => {
@@ -47,7 +47,7 @@ fn play_to_the_end(sound: rfmod::Sound, len: usize) -> rfmod::Status {
                     Ok(b) => {
                         if b == true {
                             let position = match chan.get_position(rfmod::TIMEUNIT_MS) {
-                                Ok(p) => p,
+                                Ok(p) => p.value(),
                                 Err(e) => {
                                     panic!("channel.get_position failed: {:?}", e)
                                 }
@@ -63,12 +63,12 @@ fn play_to_the_end(sound: rfmod::Sound, len: usize) -> rfmod::Status {
                             break;
                         }
                     },
-                    Err(e) => return e,
+                    Err(e) => return Err(e),
                 }
             }
-            rfmod::Status::Ok
+            Ok(())
         }
-        Err(err) => err,
+        Err(err) => Err(err),
     }
 }
 
@@ -84,16 +84,16 @@ fn main() {
     if tmp.len() < 1 {
         panic!("USAGE: ./simple_music_player [music_file]");
     }
-    let fmod = match rfmod::Sys::new() {
+    let builder = match rfmod::Sys::builder() {
         Ok(f) => f,
         Err(e) => {
-            panic!("Sys::new() : {:?}", e);
+            panic!("Sys::builder() : {:?}", e);
         }
     };
 
-    match fmod.init() {
-        rfmod::Status::Ok => {}
-        e => {
+    let fmod = match builder.init() {
+        Ok(f) => f,
+        Err(e) => {
             panic!("Sys::init() failed : {:?}", e);
         }
     };
@@ -108,10 +108,10 @@ fn main() {
     };
 
     match play_to_the_end(sound, arg1.len()) {
-        rfmod::Status::Ok => {
+        Ok(()) => {
             println!("Ok !");
         },
-        err => {
+        Err(err) => {
             panic!("Sys::play_to_the_end() : {:?}", err);
         }
     };
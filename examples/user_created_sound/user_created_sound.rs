@@ -31,29 +31,26 @@ use std::thread::sleep;
 use std::time::Duration;
 use std::io::{self, BufRead, Error};
 
-#[allow(unused_variables)]
-fn pcmreadcallback(sound: &rfmod::Sound, data: &mut [i16]) -> rfmod::Status {
-    static mut t1 : f32 = 0f32; // time
-    static mut t2 : f32 = 0f32; // time
-    static mut v1 : f32 = 0f32; // velocity
-    static mut v2 : f32 = 0f32; // velocity
-    let mut count = 0usize;
-
-    while count < data.len() {
-        unsafe {
-            data[count] = (t1.sin() * 32767f32) as i16; // left channel
-            count += 1;
-            data[count] = (t2.sin() * 32767f32) as i16; // right channel
-            count += 1;
-
-            t1 += 0.01f32 + v1;
-            t2 += 0.0142f32 + v2;
-            v1 += t1.sin() * 0.002f32;
-            v2 += t2.sin() * 0.002f32;
+/// Generates two sine waves whose frequencies drift over time.
+struct Generator {
+    t1: f32, // time
+    t2: f32, // time
+    v1: f32, // velocity
+    v2: f32, // velocity
+}
+
+impl Generator {
+    fn fill(&mut self, data: &mut [i16]) {
+        for frame in data.chunks_mut(2) {
+            frame[0] = (self.t1.sin() * 32767f32) as i16; // left channel
+            frame[1] = (self.t2.sin() * 32767f32) as i16; // right channel
+
+            self.t1 += 0.01f32 + self.v1;
+            self.t2 += 0.0142f32 + self.v2;
+            self.v1 += self.t1.sin() * 0.002f32;
+            self.v2 += self.t2.sin() * 0.002f32;
         }
     }
-
-    rfmod::Status::Ok
 }
 
 fn get_key() -> Result<isize, Error> {
@@ -79,16 +76,16 @@ fn get_key() -> Result<isize, Error> {
 
 fn main() {
     let channels = 2i32;
-    let fmod = match rfmod::Sys::new() {
+    let builder = match rfmod::Sys::builder() {
         Ok(f) => f,
         Err(e) => {
-            panic!("Sys::new() : {:?}", e);
+            panic!("Sys::builder() : {:?}", e);
         }
     };
 
-    match fmod.init_with_parameters(32i32, rfmod::InitFlag(rfmod::INIT_NORMAL)) {
-        rfmod::Status::Ok => {}
-        e => {
+    let fmod = match builder.init_with_parameters(32i32, rfmod::INIT_NORMAL) {
+        Ok(f) => f,
+        Err(e) => {
             panic!("Sys::init() failed : {:?}", e);
         }
     };
@@ -118,14 +115,23 @@ fn main() {
     exinfo.num_channels = channels;
     exinfo.default_frequency  = 44100;
     exinfo.format = rfmod::SoundFormat::PCM16;
-    exinfo.pcm_read_callback = Some(pcmreadcallback as fn(&_, &mut _) -> _);
+    let mut generator = Generator { t1: 0f32, t2: 0f32, v1: 0f32, v2: 0f32 };
+    exinfo.pcm_read_callback = Some(Box::new(move |_, buffer| {
+        match buffer {
+            rfmod::PcmBuffer::PCM16(data) => {
+                generator.fill(data);
+                rfmod::Status::Ok
+            }
+            _ => rfmod::Status::Format
+        }
+    }));
 
     let sound = match match ret {
         1 => fmod.create_sound("",
-            Some(rfmod::Mode(rfmod::_2D | rfmod::OPENUSER | rfmod::HARDWARE | rfmod::LOOP_NORMAL
-            | rfmod::CREATESTREAM)), Some(&mut exinfo)),
+            Some(rfmod::_2D | rfmod::OPENUSER | rfmod::HARDWARE | rfmod::LOOP_NORMAL
+            | rfmod::CREATESTREAM), Some(&mut exinfo)),
         2 => fmod.create_sound("",
-            Some(rfmod::Mode(rfmod::_2D | rfmod::OPENUSER | rfmod::HARDWARE | rfmod::LOOP_NORMAL)),
+            Some(rfmod::_2D | rfmod::OPENUSER | rfmod::HARDWARE | rfmod::LOOP_NORMAL),
             Some(&mut exinfo)),
         _ => return
     } {
@@ -139,7 +145,7 @@ fn main() {
     };
 
     let length = match sound.get_length(rfmod::TIMEUNIT_MS) {
-        Ok(l) => l,
+        Ok(l) => l.value(),
         Err(e) => panic!("sound.get_length failed: {:?}", e)
     };
     while match chan.is_playing() {
@@ -150,7 +156,7 @@ fn main() {
         }
     } {
         let position = match chan.get_position(rfmod::TIMEUNIT_MS) {
-            Ok(p) => p,
+            Ok(p) => p.value(),
             Err(e) => {
                 println!("channel.get_position failed: {:?}", e);
                 return;
@@ -0,0 +1,349 @@
+/*
+* Rust-FMOD - Copyright (c) 2014 Gomez Guillaume.
+*
+* The Original software, FMOD library, is provided by FIRELIGHT TECHNOLOGIES.
+*
+* This software is provided 'as-is', without any express or implied warranty.
+* In no event will the authors be held liable for any damages arising from
+* the use of this software.
+*
+* Permission is granted to anyone to use this software for any purpose,
+* including commercial applications, and to alter it and redistribute it
+* freely, subject to the following restrictions:
+*
+* 1. The origin of this software must not be misrepresented; you must not claim
+*    that you wrote the original software. If you use this software in a product,
+*    an acknowledgment in the product documentation would be appreciated but is
+*    not required.
+*
+* 2. Altered source versions must be plainly marked as such, and must not be
+*    misrepresented as being the original software.
+*
+* 3. This notice may not be removed or altered from any source distribution.
+*/
+
+use file::ReadSeek;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, Cursor, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use zip;
+
+const PACK_MAGIC: &[u8; 4] = b"RFPK";
+const PACK_VERSION: u32 = 1;
+
+/// Archive in which sounds can be opened, mounted in an
+/// [`ArchiveFileSystem`](struct.ArchiveFileSystem.html).
+pub trait Archive: Send + Sync {
+    /// Opens the entry `name`, a path relative to the root of the archive using `/` as
+    /// separator. Fails with `io::ErrorKind::NotFound` if the archive doesn't contain it.
+    fn open_entry(&self, name: &str) -> io::Result<Box<dyn ReadSeek>>;
+}
+
+/* FMOD gets the names given by the user, which can use the platform separator */
+fn entry_name(path: &Path) -> String {
+    let name = path.to_string_lossy().replace('\\', "/");
+    let mut name = &name[..];
+
+    loop {
+        if name.starts_with("./") {
+            name = &name[2..];
+        } else if name.starts_with('/') {
+            name = &name[1..];
+        } else {
+            return name.to_owned();
+        }
+    }
+}
+
+fn not_found(name: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::NotFound, format!("{} is not in the archive", name))
+}
+
+/* window over the data of an entry, every opened entry has its own file */
+struct EntryReader {
+    file: File,
+    start: u64,
+    size: u64,
+    position: u64
+}
+
+impl EntryReader {
+    fn new(path: &Path, start: u64, size: u64) -> io::Result<EntryReader> {
+        let mut file = File::open(path)?;
+
+        file.seek(SeekFrom::Start(start))?;
+        Ok(EntryReader {
+            file: file,
+            start: start,
+            size: size,
+            position: 0
+        })
+    }
+}
+
+impl Read for EntryReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let max = ::std::cmp::min(buf.len() as u64, self.size - self.position) as usize;
+        let read = self.file.read(&mut buf[..max])?;
+
+        self.position += read as u64;
+        Ok(read)
+    }
+}
+
+impl Seek for EntryReader {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let new_pos = match pos {
+            SeekFrom::Start(p) => p as i64,
+            SeekFrom::Current(p) => self.position as i64 + p,
+            SeekFrom::End(p) => self.size as i64 + p
+        };
+
+        if new_pos < 0 {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput,
+                                      "seek to a negative position"));
+        }
+        /* reads past the end are empty */
+        let new_pos = ::std::cmp::min(new_pos as u64, self.size);
+
+        self.file.seek(SeekFrom::Start(self.start + new_pos))?;
+        self.position = new_pos;
+        Ok(self.position)
+    }
+}
+
+enum ZipEntry {
+    /* data can be read from the archive file directly */
+    Stored { start: u64, size: u64 },
+    /* data has to be decompressed in memory to be seekable */
+    Compressed(usize)
+}
+
+/// Zip archive. Stored entries are read directly from the archive, compressed ones are
+/// decompressed in memory when they are opened.
+pub struct ZipArchive {
+    path: PathBuf,
+    archive: Mutex<zip::ZipArchive<File>>,
+    entries: HashMap<String, ZipEntry>
+}
+
+impl ZipArchive {
+    /// Reads the index of the zip file at `path`.
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<ZipArchive> {
+        let mut archive = zip::ZipArchive::new(File::open(path.as_ref())?)?;
+        let mut entries = HashMap::new();
+
+        for i in 0..archive.len() {
+            let file = archive.by_index_raw(i)?;
+
+            if file.is_dir() {
+                continue;
+            }
+            let entry = match file.compression() {
+                zip::CompressionMethod::Stored => ZipEntry::Stored {
+                    start: file.data_start(),
+                    size: file.size()
+                },
+                _ => ZipEntry::Compressed(i)
+            };
+            entries.insert(file.name().to_owned(), entry);
+        }
+        Ok(ZipArchive {
+            path: path.as_ref().to_owned(),
+            archive: Mutex::new(archive),
+            entries: entries
+        })
+    }
+}
+
+impl Archive for ZipArchive {
+    fn open_entry(&self, name: &str) -> io::Result<Box<dyn ReadSeek>> {
+        match self.entries.get(name) {
+            Some(&ZipEntry::Stored { start, size }) => {
+                Ok(Box::new(EntryReader::new(&self.path, start, size)?))
+            }
+            Some(&ZipEntry::Compressed(index)) => {
+                let mut archive = match self.archive.lock() {
+                    Ok(a) => a,
+                    Err(_) => return Err(io::Error::new(io::ErrorKind::Other,
+                                                        "zip archive lock poisoned"))
+                };
+                let mut file = archive.by_index(index)?;
+                let mut data = Vec::with_capacity(file.size() as usize);
+
+                file.read_to_end(&mut data)?;
+                Ok(Box::new(Cursor::new(data)))
+            }
+            None => Err(not_found(name))
+        }
+    }
+}
+
+/// Uncompressed pack of files, made of an index followed by the data of the files. All
+/// numbers are little endian:
+///
+/// ```text
+/// magic    b"RFPK"
+/// version  u32, 1
+/// count    u32
+/// entries  count times:
+///              name_len  u16
+///              name      name_len bytes of UTF-8
+///              offset    u64, from the start of the pack
+///              size      u64
+/// data
+/// ```
+pub struct PackArchive {
+    path: PathBuf,
+    entries: HashMap<String, (u64, u64)>
+}
+
+fn read_u16(reader: &mut dyn Read) -> io::Result<u16> {
+    let mut buf = [0u8; 2];
+
+    reader.read_exact(&mut buf)?;
+    Ok(u16::from_le_bytes(buf))
+}
+
+fn read_u32(reader: &mut dyn Read) -> io::Result<u32> {
+    let mut buf = [0u8; 4];
+
+    reader.read_exact(&mut buf)?;
+    Ok(u32::from_le_bytes(buf))
+}
+
+fn read_u64(reader: &mut dyn Read) -> io::Result<u64> {
+    let mut buf = [0u8; 8];
+
+    reader.read_exact(&mut buf)?;
+    Ok(u64::from_le_bytes(buf))
+}
+
+fn invalid_pack(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+impl PackArchive {
+    /// Reads the index of the pack file at `path`.
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<PackArchive> {
+        let mut file = io::BufReader::new(File::open(path.as_ref())?);
+        let mut magic = [0u8; 4];
+
+        file.read_exact(&mut magic)?;
+        if &magic != PACK_MAGIC {
+            return Err(invalid_pack("not a pack file"));
+        }
+        if read_u32(&mut file)? != PACK_VERSION {
+            return Err(invalid_pack("unsupported pack version"));
+        }
+        let count = read_u32(&mut file)?;
+        let mut entries = HashMap::new();
+
+        for _ in 0..count {
+            let mut name = vec![0u8; read_u16(&mut file)? as usize];
+
+            file.read_exact(&mut name)?;
+            let name = match String::from_utf8(name) {
+                Ok(n) => n,
+                Err(_) => return Err(invalid_pack("entry name is not valid UTF-8"))
+            };
+            let offset = read_u64(&mut file)?;
+            let size = read_u64(&mut file)?;
+
+            entries.insert(name, (offset, size));
+        }
+        Ok(PackArchive {
+            path: path.as_ref().to_owned(),
+            entries: entries
+        })
+    }
+
+    /// Writes a pack file at `path` containing `files`, given as the name of the entry and the
+    /// file to copy in it.
+    pub fn create<P: AsRef<Path>>(path: P, files: &[(&str, &Path)]) -> io::Result<()> {
+        let mut index_size = 12u64;
+        let mut sizes = Vec::with_capacity(files.len());
+
+        for &(name, source) in files {
+            if name.len() > u16::MAX as usize {
+                return Err(io::Error::new(io::ErrorKind::InvalidInput, "entry name too long"));
+            }
+            index_size += 2 + name.len() as u64 + 16;
+            sizes.push(::std::fs::metadata(source)?.len());
+        }
+        let mut out = io::BufWriter::new(File::create(path)?);
+        let mut offset = index_size;
+
+        out.write_all(PACK_MAGIC)?;
+        out.write_all(&PACK_VERSION.to_le_bytes())?;
+        out.write_all(&(files.len() as u32).to_le_bytes())?;
+        for (&(name, _), size) in files.iter().zip(sizes.iter()) {
+            out.write_all(&(name.len() as u16).to_le_bytes())?;
+            out.write_all(name.as_bytes())?;
+            out.write_all(&offset.to_le_bytes())?;
+            out.write_all(&size.to_le_bytes())?;
+            offset += *size;
+        }
+        for (&(_, source), size) in files.iter().zip(sizes.iter()) {
+            /* the size written in the index has to match even if the file changed since */
+            if io::copy(&mut File::open(source)?.take(*size), &mut out)? != *size {
+                return Err(io::Error::new(io::ErrorKind::UnexpectedEof,
+                                          "file shrank while being packed"));
+            }
+        }
+        out.flush()
+    }
+}
+
+impl Archive for PackArchive {
+    fn open_entry(&self, name: &str) -> io::Result<Box<dyn ReadSeek>> {
+        match self.entries.get(name) {
+            Some(&(offset, size)) => Ok(Box::new(EntryReader::new(&self.path, offset, size)?)),
+            None => Err(not_found(name))
+        }
+    }
+}
+
+/// File system looking for the files opened by FMOD in mounted archives, set with
+/// [`Sys::set_archive_file_system`](struct.Sys.html#method.set_archive_file_system).
+///
+/// ```ignore
+/// let mut archives = rfmod::ArchiveFileSystem::new();
+///
+/// archives.mount(rfmod::ZipArchive::open("sounds.zip")?);
+/// archives.mount(rfmod::PackArchive::open("patch.pack")?);
+/// fmod.set_archive_file_system(archives, -1)?;
+/// let sound = fmod.create_stream("sfx/explosion.ogg", None, None)?;
+/// ```
+pub struct ArchiveFileSystem {
+    archives: Vec<Box<dyn Archive>>
+}
+
+impl ArchiveFileSystem {
+    pub fn new() -> ArchiveFileSystem {
+        ArchiveFileSystem {
+            archives: Vec::new()
+        }
+    }
+
+    /// Adds `archive` to the file system. Archives mounted last are searched first, so they
+    /// can override the files of the previous ones.
+    pub fn mount<A: Archive + 'static>(&mut self, archive: A) {
+        self.archives.push(Box::new(archive));
+    }
+
+    /// Opens `path` from the first archive containing it.
+    pub fn open(&self, path: &Path) -> io::Result<Box<dyn ReadSeek>> {
+        let name = entry_name(path);
+
+        for archive in self.archives.iter().rev() {
+            match archive.open_entry(&name) {
+                Err(ref e) if e.kind() == io::ErrorKind::NotFound => {}
+                r => return r
+            }
+        }
+        Err(not_found(&name))
+    }
+}
@@ -1,7 +1,7 @@
 /*
 * Rust-FMOD - Copyright (c) 2016 Gomez Guillaume.
 *
-* The Original software, FmodEx library, is provided by FIRELIGHT TECHNOLOGIES.
+* The Original software, FMOD library, is provided by FIRELIGHT TECHNOLOGIES.
 *
 * This software is provided 'as-is', without any express or implied warranty.
 * In no event will the authors be held liable for any damages arising from
@@ -22,61 +22,302 @@
 * 3. This notice may not be removed or altered from any source distribution.
 */
 
-use FmodFile;
-use ffi::FMOD_ASYNCREADINFO;
+use ffi;
+use file::ReadSeek;
+use std::cmp::Ordering;
+use std::sync::atomic;
+use std::collections::BinaryHeap;
+use std::io::{self, SeekFrom};
+use std::ptr;
+use std::slice;
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
 
-use libc;
+/* file opened by a file system, shared with its pending asynchronous reads */
+pub struct SharedFile {
+    pub file: Mutex<Box<dyn ReadSeek>>,
+    /* number of reads handed to the reader and not completed yet, and if they are cancelled */
+    pending: Mutex<(usize, bool)>,
+    completed: Condvar
+}
+
+impl SharedFile {
+    pub fn new(file: Box<dyn ReadSeek>) -> SharedFile {
+        SharedFile {
+            file: Mutex::new(file),
+            pending: Mutex::new((0, false)),
+            completed: Condvar::new()
+        }
+    }
+
+    /* FMOD frees the read requests once this returns */
+    pub fn cancel(&self) -> ::Status {
+        let mut pending = match self.pending.lock() {
+            Ok(p) => p,
+            Err(_) => return ::Status::Internal
+        };
 
+        pending.1 = true;
+        while pending.0 > 0 {
+            pending = match self.completed.wait(pending) {
+                Ok(p) => p,
+                Err(_) => return ::Status::Internal
+            };
+        }
+        pending.1 = false;
+        ::Status::Ok
+    }
+}
+
+/// Executes the asynchronous reads requested by FMOD, see
+/// [`Sys::set_async_file_system`](struct.Sys.html#method.set_async_file_system).
+///
+/// It is called from the FMOD file thread and has to return without blocking. Closures taking
+/// an [`AsyncReadInfo`](struct.AsyncReadInfo.html) implement it.
+pub trait AsyncReader: Send + Sync {
+    fn read(&self, info: AsyncReadInfo);
+}
+
+impl<F: Fn(AsyncReadInfo) + Send + Sync> AsyncReader for F {
+    fn read(&self, info: AsyncReadInfo) {
+        self(info)
+    }
+}
+
+/// Read requested by FMOD, to be done with [`AsyncReadInfo::read`](#method.read) from any
+/// thread. Dropping it without reading fails the request.
 pub struct AsyncReadInfo {
-    /// [r] The file handle that was filled out in the open callback.
-    pub handle     : FmodFile,
-    /// [r] Seek position, make sure you read from this file offset.
-    pub offset     : u32,
-    /// [r] how many bytes requested for read.
-    pub sizebytes  : u32,
-    /// [r] 0 = low importance. 100 = extremely important (ie 'must read now or stuttering may
-    /// occur')
-    pub priority   : u32,
-    /// [w] Buffer to read file data into.
-    pub buffer     : Vec<u8>,
-    /// [r/w] Result code, Ok tells the system it is ready to consume the data. Set this last!
-    ///
-    /// Default value = FMOD_ERR_NOTREADY.
-    pub result     : ::Status,
-    #[doc(hidden)]
-    ffi: *mut ffi::FMOD_ASYNCREADINFO,
+    info: *mut ffi::FMOD_ASYNCREADINFO,
+    file: Arc<SharedFile>,
+    done: bool
+}
+
+/* FMOD keeps the request alive until it is completed or cancelled */
+unsafe impl Send for AsyncReadInfo {}
+
+pub fn from_ffi(info: *mut ffi::FMOD_ASYNCREADINFO,
+                file: Arc<SharedFile>) -> Result<AsyncReadInfo, ::Status> {
+    match file.pending.lock() {
+        Ok(mut pending) => pending.0 += 1,
+        Err(_) => return Err(::Status::Internal)
+    }
+    Ok(AsyncReadInfo {
+        info: info,
+        file: file,
+        done: false
+    })
 }
 
 impl AsyncReadInfo {
-    #[doc(hidden)]
-    pub fn new(a: *mut ffi::FMOD_ASYNCREADINFO) -> AsyncReadInfo {
-        unsafe {
-            AsyncReadInfo {
-                handle: file::from_ffi((*a).handle),
-                offset: (*a).offset,
-                sizebytes: (*a).sizebytes,
-                priority: (*a).priority,
-                buffer: Vec::new(),
-                result: (*a).result,
-            }
+    /// Position in the file to read from.
+    pub fn offset(&self) -> u32 {
+        unsafe { (*self.info).offset }
+    }
+
+    /// Number of bytes requested.
+    pub fn size(&self) -> u32 {
+        unsafe { (*self.info).sizebytes }
+    }
+
+    /// 0 = low importance. 100 = extremely important (ie 'must read now or stuttering may
+    /// occur').
+    pub fn priority(&self) -> i32 {
+        unsafe { (*self.info).priority }
+    }
+
+    /// Returns `true` if FMOD has closed the file, in which case the read is skipped.
+    pub fn is_cancelled(&self) -> bool {
+        /* the request may have been freed if the cancellation couldn't be waited for */
+        match self.file.pending.lock() {
+            Ok(pending) => pending.1,
+            Err(_) => true
         }
     }
 
-    #[doc(hidden)]
-    pub fn to_ffi(self) -> *mut ffi::FMOD_ASYNCREADINFO {
-        let mut out = self.ffi;
+    /// Reads the requested data and hands it to FMOD.
+    pub fn read(mut self) {
+        if self.is_cancelled() {
+            return;
+        }
+        let result = {
+            let buffer = unsafe {
+                slice::from_raw_parts_mut((*self.info).buffer as *mut u8, self.size() as usize)
+            };
+            match self.file.file.lock() {
+                Ok(mut file) => match file.seek(SeekFrom::Start(self.offset() as u64)) {
+                    Ok(_) => read_full(&mut **file, buffer),
+                    Err(_) => Err(::Status::FileCouldNotSeek)
+                },
+                Err(_) => Err(::Status::FileBad)
+            }
+        };
 
+        match result {
+            Ok(read) => self.complete(read as u32, if read < self.size() as usize {
+                ::Status::FileEOF
+            } else {
+                ::Status::Ok
+            }),
+            Err(e) => self.complete(0, e)
+        }
+    }
+
+    fn complete(&mut self, bytes_read: u32, result: ::Status) {
         unsafe {
-            (*out).offset = self.offset;
-            (*out).sizebytes = self.sizebytes;
-            (*out).priority = self.priority;
-            (*out).buffer = ::libc::malloc(self.buffer.len());
-            for (pos, i) in self.buffer.iter().enumerate() {
-                *(out.offset(pos)) = i;
-            }
-            (*out).bytesread = self.buffer.len();
-            (*out).result = self.result;
+            (*self.info).bytesread = bytes_read;
+            /* has to be set last, FMOD uses the data as soon as it changes */
+            atomic::fence(atomic::Ordering::Release);
+            ptr::write_volatile(&mut (*self.info).result, result);
+        }
+        self.done = true;
+    }
+}
+
+impl Drop for AsyncReadInfo {
+    fn drop(&mut self) {
+        if !self.done && !self.is_cancelled() {
+            self.complete(0, ::Status::FileBad);
+        }
+        if let Ok(mut pending) = self.file.pending.lock() {
+            pending.0 -= 1;
+        }
+        self.file.completed.notify_all();
+    }
+}
+
+/* FMOD takes a short read for the end of the file */
+pub fn read_full(file: &mut dyn ReadSeek, buffer: &mut [u8]) -> Result<usize, ::Status> {
+    let mut read = 0;
+
+    while read < buffer.len() {
+        match file.read(&mut buffer[read..]) {
+            Ok(0) => break,
+            Ok(r) => read += r,
+            Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(_) => return Err(::Status::FileBad)
+        }
+    }
+    Ok(read)
+}
+
+/* request waiting in a ThreadPoolReader, ordered by priority then by arrival */
+struct QueuedRead {
+    info: AsyncReadInfo,
+    priority: i32,
+    order: u64
+}
+
+impl PartialEq for QueuedRead {
+    fn eq(&self, other: &QueuedRead) -> bool {
+        self.priority == other.priority && self.order == other.order
+    }
+}
+
+impl Eq for QueuedRead {}
+
+impl PartialOrd for QueuedRead {
+    fn partial_cmp(&self, other: &QueuedRead) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for QueuedRead {
+    fn cmp(&self, other: &QueuedRead) -> Ordering {
+        self.priority.cmp(&other.priority).then(other.order.cmp(&self.order))
+    }
+}
+
+struct Queue {
+    reads: BinaryHeap<QueuedRead>,
+    next_order: u64,
+    stopped: bool
+}
+
+struct SharedQueue {
+    queue: Mutex<Queue>,
+    available: Condvar
+}
+
+/// [`AsyncReader`](trait.AsyncReader.html) doing the reads on its own threads, most important
+/// reads first. The threads are stopped when it is dropped.
+pub struct ThreadPoolReader {
+    shared: Arc<SharedQueue>,
+    threads: Vec<thread::JoinHandle<()>>
+}
+
+impl ThreadPoolReader {
+    pub fn new(num_threads: usize) -> ThreadPoolReader {
+        let shared = Arc::new(SharedQueue {
+            queue: Mutex::new(Queue {
+                reads: BinaryHeap::new(),
+                next_order: 0,
+                stopped: false
+            }),
+            available: Condvar::new()
+        });
+        let threads = (0..num_threads).map(|_| {
+            let shared = shared.clone();
+
+            thread::spawn(move || {
+                loop {
+                    let read = {
+                        let mut queue = match shared.queue.lock() {
+                            Ok(q) => q,
+                            Err(_) => return
+                        };
+
+                        loop {
+                            if let Some(read) = queue.reads.pop() {
+                                break read;
+                            }
+                            if queue.stopped {
+                                return;
+                            }
+                            queue = match shared.available.wait(queue) {
+                                Ok(q) => q,
+                                Err(_) => return
+                            };
+                        }
+                    };
+                    read.info.read();
+                }
+            })
+        }).collect();
+
+        ThreadPoolReader {
+            shared: shared,
+            threads: threads
+        }
+    }
+}
+
+impl AsyncReader for ThreadPoolReader {
+    /* the request fails when dropped if the queue is poisoned */
+    fn read(&self, info: AsyncReadInfo) {
+        let mut queue = match self.shared.queue.lock() {
+            Ok(q) => q,
+            Err(_) => return
+        };
+        let order = queue.next_order;
+
+        queue.next_order += 1;
+        queue.reads.push(QueuedRead {
+            priority: info.priority(),
+            info: info,
+            order: order
+        });
+        self.shared.available.notify_one();
+    }
+}
+
+impl Drop for ThreadPoolReader {
+    fn drop(&mut self) {
+        if let Ok(mut queue) = self.shared.queue.lock() {
+            queue.stopped = true;
+        }
+        self.shared.available.notify_all();
+        for thread in self.threads.drain(..) {
+            let _ = thread.join();
         }
-        out
     }
 }
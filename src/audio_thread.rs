@@ -0,0 +1,352 @@
+/*
+* Rust-FMOD - Copyright (c) 2014 Gomez Guillaume.
+*
+* The Original software, FMOD library, is provided by FIRELIGHT TECHNOLOGIES.
+*
+* This software is provided 'as-is', without any express or implied warranty.
+* In no event will the authors be held liable for any damages arising from
+* the use of this software.
+*
+* Permission is granted to anyone to use this software for any purpose,
+* including commercial applications, and to alter it and redistribute it
+* freely, subject to the following restrictions:
+*
+* 1. The origin of this software must not be misrepresented; you must not claim
+*    that you wrote the original software. If you use this software in a product,
+*    an acknowledgment in the product documentation would be appreciated but is
+*    not required.
+*
+* 2. Altered source versions must be plainly marked as such, and must not be
+*    misrepresented as being the original software.
+*
+* 3. This notice may not be removed or altered from any source distribution.
+*/
+
+
+use channel::Channel;
+use fmod_sys::Sys;
+use sound::Sound;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::thread;
+use std::time::{Duration, Instant};
+use types::Mode;
+use vector::Vector;
+
+/// Identifier of a sound loaded through an [`AudioClient`](struct.AudioClient.html).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct SoundId(u64);
+
+/// Identifier of a sound played through an [`AudioClient`](struct.AudioClient.html).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ChannelId(u64);
+
+/// Result of the commands and notifications sent back by an
+/// [`AudioThread`](struct.AudioThread.html).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AudioEvent {
+    /// The sound is loaded and can be played.
+    SoundLoaded(SoundId),
+    /// The sound couldn't be loaded, playing it fails.
+    SoundFailed(SoundId, ::Error),
+    /// The sound couldn't be played.
+    PlayFailed(ChannelId, ::Error),
+    /// The channel stopped playing, because its sound ended, it was stopped or FMOD stole it.
+    ChannelEnded(ChannelId),
+    /// Any other command or the update of the system failed.
+    Error(::Error)
+}
+
+enum Command {
+    LoadSound(SoundId, String, Option<Mode>),
+    ReleaseSound(SoundId),
+    Play(ChannelId, SoundId, bool),
+    Stop(ChannelId),
+    SetPaused(ChannelId, bool),
+    SetVolume(ChannelId, f32),
+    Set3DAttributes(ChannelId, Vector, Vector),
+    Set3DListenerAttributes(i32, Vector, Vector, Vector, Vector),
+    Shutdown
+}
+
+/// Handle sending commands to an [`AudioThread`](struct.AudioThread.html) from any thread.
+///
+/// The commands are executed in order on the audio thread, their identifiers are returned right
+/// away and the failures are sent back as [`AudioEvent`](enum.AudioEvent.html)s. Commands sent
+/// after the audio thread stopped are ignored.
+#[derive(Clone)]
+pub struct AudioClient {
+    commands: Sender<Command>,
+    next_id: Arc<AtomicU64>
+}
+
+impl AudioClient {
+    fn send(&self, command: Command) {
+        let _ = self.commands.send(command);
+    }
+
+    fn next_id(&self) -> u64 {
+        self.next_id.fetch_add(1, Ordering::Relaxed)
+    }
+
+    /// Loads `music` with [`Sys::create_sound`](struct.Sys.html#method.create_sound).
+    pub fn load_sound(&self, music: &str, options: Option<Mode>) -> SoundId {
+        let id = SoundId(self.next_id());
+
+        self.send(Command::LoadSound(id, music.to_owned(), options));
+        id
+    }
+
+    /// Releases the sound, the channels playing it are stopped.
+    pub fn release_sound(&self, sound: SoundId) {
+        self.send(Command::ReleaseSound(sound));
+    }
+
+    pub fn play(&self, sound: SoundId) -> ChannelId {
+        self.play_with_parameters(sound, false)
+    }
+
+    /// Plays the sound, starting paused if `paused` is `true` so it can be set up first.
+    pub fn play_with_parameters(&self, sound: SoundId, paused: bool) -> ChannelId {
+        let id = ChannelId(self.next_id());
+
+        self.send(Command::Play(id, sound, paused));
+        id
+    }
+
+    pub fn stop(&self, channel: ChannelId) {
+        self.send(Command::Stop(channel));
+    }
+
+    pub fn set_paused(&self, channel: ChannelId, paused: bool) {
+        self.send(Command::SetPaused(channel, paused));
+    }
+
+    pub fn set_volume(&self, channel: ChannelId, volume: f32) {
+        self.send(Command::SetVolume(channel, volume));
+    }
+
+    pub fn set_3D_attributes(&self, channel: ChannelId, position: Vector, velocity: Vector) {
+        self.send(Command::Set3DAttributes(channel, position, velocity));
+    }
+
+    pub fn set_3D_listener_attributes(&self, listener: i32, position: Vector, velocity: Vector,
+                                      forward: Vector, up: Vector) {
+        self.send(Command::Set3DListenerAttributes(listener, position, velocity, forward, up));
+    }
+}
+
+/* state living on the audio thread */
+struct Mixer {
+    sys: Sys,
+    /* sounds which failed to load are kept to report the failure when they are played */
+    sounds: HashMap<SoundId, Result<Sound, ::Error>>,
+    channels: HashMap<ChannelId, (SoundId, Channel)>,
+    events: Sender<AudioEvent>
+}
+
+impl Mixer {
+    fn send(&self, event: AudioEvent) {
+        let _ = self.events.send(event);
+    }
+
+    fn check<T>(&self, result: Result<T, ::Error>) {
+        if let Err(e) = result {
+            self.send(AudioEvent::Error(e));
+        }
+    }
+
+    /* channels which ended are dropped from the map, so a command sent to one is ignored */
+    fn channel(&self, id: ChannelId) -> Option<&Channel> {
+        self.channels.get(&id).and_then(|(_, channel)| channel.upgrade())
+    }
+
+    fn execute(&mut self, command: Command) {
+        match command {
+            Command::LoadSound(id, music, options) => {
+                let sound = self.sys.create_sound(&music, options, None);
+
+                self.send(match sound {
+                    Ok(_) => AudioEvent::SoundLoaded(id),
+                    Err(e) => AudioEvent::SoundFailed(id, e)
+                });
+                self.sounds.insert(id, sound);
+            }
+            Command::ReleaseSound(id) => {
+                let stopped : Vec<ChannelId> = self.channels.iter()
+                                                   .filter(|&(_, &(sound, _))| sound == id)
+                                                   .map(|(&channel, _)| channel).collect();
+
+                for channel in stopped {
+                    self.stop(channel);
+                }
+                self.sounds.remove(&id);
+            }
+            Command::Play(id, sound, paused) => {
+                let result = match self.sounds.get(&sound) {
+                    Some(Ok(s)) => s.play_with_parameters(paused, None),
+                    Some(Err(e)) => Err(*e),
+                    None => Err(::Error::new(::Status::InvalidHandle, "AudioClient::play"))
+                };
+
+                match result {
+                    Ok(channel) => { self.channels.insert(id, (sound, channel)); }
+                    Err(e) => self.send(AudioEvent::PlayFailed(id, e))
+                }
+            }
+            Command::Stop(id) => self.stop(id),
+            Command::SetPaused(id, paused) => {
+                if let Some(channel) = self.channel(id) {
+                    self.check(channel.set_paused(paused));
+                }
+            }
+            Command::SetVolume(id, volume) => {
+                if let Some(channel) = self.channel(id) {
+                    self.check(channel.set_volume(volume));
+                }
+            }
+            Command::Set3DAttributes(id, position, velocity) => {
+                if let Some(channel) = self.channel(id) {
+                    self.check(channel.set_3D_attributes(&position, &velocity));
+                }
+            }
+            Command::Set3DListenerAttributes(listener, position, velocity, forward, up) => {
+                let result = self.sys.set_3D_listener_attributes(listener, &position, &velocity,
+                                                                 &forward, &up);
+
+                self.check(result);
+            }
+            Command::Shutdown => {}
+        }
+    }
+
+    fn stop(&mut self, id: ChannelId) {
+        if let Some((_, channel)) = self.channels.remove(&id) {
+            if channel.is_valid() {
+                self.check(channel.stop());
+            }
+            self.send(AudioEvent::ChannelEnded(id));
+        }
+    }
+
+    fn update(&mut self) {
+        let result = self.sys.update();
+
+        self.check(result);
+        let ended : Vec<ChannelId> = self.channels.iter().filter(|(_, (_, channel))| {
+            !channel.is_playing().unwrap_or(false)
+        }).map(|(&id, _)| id).collect();
+
+        for id in ended {
+            self.channels.remove(&id);
+            self.send(AudioEvent::ChannelEnded(id));
+        }
+    }
+}
+
+/// Thread owning an FMOD system, so sounds can be played from any thread through
+/// [`AudioClient`](struct.AudioClient.html)s without sharing FMOD objects.
+///
+/// ```ignore
+/// let audio = rfmod::AudioThread::spawn(|| Ok(rfmod::Sys::builder()?.init()?),
+///                                       Duration::from_millis(20))?;
+/// let client = audio.client();
+/// let music = client.load_sound("music.ogg", None);
+/// let channel = client.play(music);
+///
+/// client.set_volume(channel, 0.5);
+/// for event in audio.events().try_iter() {
+///     println!("{:?}", event);
+/// }
+/// ```
+///
+/// The system and everything created from it are released when the `AudioThread` is dropped.
+pub struct AudioThread {
+    client: AudioClient,
+    events: Receiver<AudioEvent>,
+    thread: Option<thread::JoinHandle<()>>
+}
+
+impl AudioThread {
+    /// Starts the audio thread, creating the system with `init` on it, and calls
+    /// [`Sys::update`](struct.Sys.html#method.update) every `update_interval`. Returns the error
+    /// of `init` if it fails.
+    pub fn spawn<F>(init: F, update_interval: Duration) -> Result<AudioThread, ::Error>
+        where F: FnOnce() -> Result<Sys, ::Error> + Send + 'static {
+        let (commands, command_receiver) = mpsc::channel();
+        let (event_sender, events) = mpsc::channel();
+        let (init_sender, init_receiver) = mpsc::channel();
+        let thread = thread::spawn(move || {
+            let sys = match init() {
+                Ok(sys) => {
+                    let _ = init_sender.send(Ok(()));
+                    sys
+                }
+                Err(e) => {
+                    let _ = init_sender.send(Err(e));
+                    return;
+                }
+            };
+            let mut mixer = Mixer {
+                sys: sys,
+                sounds: HashMap::new(),
+                channels: HashMap::new(),
+                events: event_sender
+            };
+            let mut next_update = Instant::now();
+
+            loop {
+                let now = Instant::now();
+
+                if now >= next_update {
+                    mixer.update();
+                    next_update = now + update_interval;
+                }
+                let timeout = next_update.saturating_duration_since(Instant::now());
+
+                match command_receiver.recv_timeout(timeout) {
+                    Ok(Command::Shutdown) | Err(RecvTimeoutError::Disconnected) => break,
+                    Ok(command) => mixer.execute(command),
+                    Err(RecvTimeoutError::Timeout) => {}
+                }
+            }
+            /* the channels go before the sounds playing on them */
+            mixer.channels.clear();
+            mixer.sounds.clear();
+        });
+
+        match init_receiver.recv() {
+            Ok(Ok(())) => Ok(AudioThread {
+                client: AudioClient {
+                    commands: commands,
+                    next_id: Arc::new(AtomicU64::new(0))
+                },
+                events: events,
+                thread: Some(thread)
+            }),
+            Ok(Err(e)) => Err(e),
+            Err(_) => Err(::Error::new(::Status::Internal, "AudioThread::spawn"))
+        }
+    }
+
+    /// Returns a new client sending commands to this thread.
+    pub fn client(&self) -> AudioClient {
+        self.client.clone()
+    }
+
+    /// Events sent back by the audio thread.
+    pub fn events(&self) -> &Receiver<AudioEvent> {
+        &self.events
+    }
+}
+
+impl Drop for AudioThread {
+    fn drop(&mut self) {
+        self.client.send(Command::Shutdown);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
@@ -0,0 +1,55 @@
+/*
+* Rust-FMOD - Copyright (c) 2014 Gomez Guillaume.
+*
+* The Original software, FMOD library, is provided by FIRELIGHT TECHNOLOGIES.
+*
+* This software is provided 'as-is', without any express or implied warranty.
+* In no event will the authors be held liable for any damages arising from
+* the use of this software.
+*
+* Permission is granted to anyone to use this software for any purpose,
+* including commercial applications, and to alter it and redistribute it
+* freely, subject to the following restrictions:
+*
+* 1. The origin of this software must not be misrepresented; you must not claim
+*    that you wrote the original software. If you use this software in a product,
+*    an acknowledgment in the product documentation would be appreciated but is
+*    not required.
+*
+* 2. Altered source versions must be plainly marked as such, and must not be
+*    misrepresented as being the original software.
+*
+* 3. This notice may not be removed or altered from any source distribution.
+*/
+
+
+use ffi;
+use std::marker::PhantomData;
+use std::ops::Deref;
+
+/// View of an FMOD object owned by someone else, returned by getters like
+/// [`Channel::get_current_sound`](struct.Channel.html#method.get_current_sound) or
+/// [`ChannelGroup::get_group`](struct.ChannelGroup.html#method.get_group).
+///
+/// Dropping it doesn't release the object and it can't outlive the handle it was obtained
+/// from. The object is used through `Deref`.
+pub struct Borrowed<'a, T> {
+    value: T,
+    marker: PhantomData<&'a ()>
+}
+
+impl<'a, T> Deref for Borrowed<'a, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+/* handles made by FFI::wrap never release their object */
+pub fn wrap<'a, P, T: ffi::FFI<P>>(pointer: *mut P) -> Borrowed<'a, T> {
+    Borrowed {
+        value: ffi::FFI::wrap(pointer),
+        marker: PhantomData
+    }
+}
@@ -24,27 +24,32 @@
 
 use dsp;
 use sound;
+use channel;
 use types::TimeUnit;
 use fmod_sys;
 use file;
+use std::io;
+use std::path::Path;
 
-/*pub type SystemCallback = Option<fn(fmod: &Sys, _type:SystemCallbackType, command_data1: *mut c_void,
-    command_data2: *mut c_void) -> ::Status>;*/
+/// system callback, see [`Sys::set_callback`](../struct.Sys.html#method.set_callback)
+pub type SystemCallback = Option<Box<dyn FnMut(&fmod_sys::Sys, fmod_sys::SystemEvent) -> ::Status + Send>>;
 
 /* file callbacks */
-pub type FileOpenCallback = Option<fn(name: &str, unicode: i32) -> Option<(file::FmodFile, Option<fmod_sys::UserData>)>>;
-pub type FileCloseCallback = Option<fn(handle: &mut file::FmodFile, user_data: Option<&mut fmod_sys::UserData>)>;
-pub type FileReadCallback = Option<fn(handle: &mut file::FmodFile, buffer: &mut [u8], size_to_read: u32, user_data: Option<&mut fmod_sys::UserData>) -> usize>;
-pub type FileSeekCallback = Option<fn(handle: &mut file::FmodFile, pos: u32, user_data: Option<&mut fmod_sys::UserData>)>;
+/// file system, see [`Sys::set_file_system`](../struct.Sys.html#method.set_file_system)
+pub type FileSystemFactory = Box<dyn Fn(&Path) -> io::Result<Box<dyn file::ReadSeek>> + Send + Sync>;
 /*pub type FMOD_FILE_ASYNCREADCALLBACK = Option<extern "C" fn(arg1: *mut FMOD_ASYNCREADINFO, arg2: *mut c_void) -> ::Status>;
 pub type FMOD_FILE_ASYNCCANCELCALLBACK = Option<extern "C" fn(arg1: *mut c_void, arg2: *mut c_void, arg3: c_uint) -> ::Status>;*/
 
+/// channel callback, see [`Channel::set_callback`](../struct.Channel.html#method.set_callback)
+pub type ChannelCallback = Option<Box<dyn FnMut(&channel::Channel, channel::ChannelEvent) -> ::Status + Send>>;
+
 /// sound callback
 pub type SoundNonBlockCallback = Option<fn(sound: &sound::Sound, result: ::Status) -> ::Status>;
-/// callback which allow to set/change data that will be played
-pub type SoundPcmReadCallback = Option<fn(sound: &sound::Sound, data: &mut [i16]) -> ::Status>;
+/// callback which allow to set/change data that will be played, the buffer is typed after
+/// [`CreateSoundexInfo::format`](../struct.CreateSoundexInfo.html#structfield.format)
+pub type SoundPcmReadCallback = Option<Box<dyn FnMut(&sound::Sound, sound::PcmBuffer) -> ::Status + Send>>;
 /// notify the user that music position has changed
-pub type SoundPcmSetPosCallback = Option<fn(sound: &sound::Sound, sub_sound: i32, position: u32, postype: TimeUnit) -> ::Status>;
+pub type SoundPcmSetPosCallback = Option<Box<dyn FnMut(&sound::Sound, i32, u32, TimeUnit) -> ::Status + Send>>;
 
 /*  codec callbacks */
 /*pub type FMOD_CODEC_OPENCALLBACK = Option<extern "C" fn(codec_state: *mut FMOD_CODEC_STATE, user_mode: FMOD_MODE, userexinfo: *mut FMOD_CREATESOUNDEXINFO) -> ::Status>;
@@ -23,17 +23,24 @@
 */
 
 use types::*;
+use position::Position;
 use libc::{c_int, c_void};
 use ffi;
 use dsp::Dsp;
+use dsp_connection;
 use dsp_connection::DspConnection;
 use channel_group::ChannelGroup;
 use fmod_sys;
-use fmod_sys::{MemoryUsageDetails, Sys};
+use fmod_sys::{MemoryUsageDetails, Sys, SysHandle};
 use vector;
 use sound::Sound;
-use std::mem::transmute;
 use std::default::Default;
+use callbacks::ChannelCallback;
+use borrowed;
+use borrowed::Borrowed;
+use user_data;
+use std::any::Any;
+use std::sync::Arc;
 
 /// Structure which contains data for
 /// [`Channel::set_speaker_mix`](struct.Channel.html#method.set_speaker_mix) and
@@ -81,20 +88,106 @@ pub struct ReverbChannelProperties {
     pub connection_point: Dsp
 }
 
+/// Event received by the closure given to
+/// [`Channel::set_callback`](struct.Channel.html#method.set_callback).
+#[derive(Debug, PartialEq)]
+pub enum ChannelEvent<'a> {
+    /// The sound has ended or the channel has been stopped. This is the last event received for
+    /// this channel.
+    End,
+    /// The channel has been swapped out (`true`) or swapped back in (`false`).
+    VirtualVoice(bool),
+    /// A sync point has been reached, the value is its index in the played sound.
+    SyncPoint(i32),
+    /// The geometry occlusion of the channel has been calculated. The direct and reverb
+    /// occlusion values can be clamped or changed before they are applied.
+    Occlusion {
+        direct: &'a mut f32,
+        reverb: &'a mut f32
+    }
+}
+
+/* stored as FMOD user data of channels having a callback or a user data */
+struct UserData {
+    callback: ChannelCallback,
+    user_data: Option<user_data::UserData>
+}
+
+extern "C" fn channel_callback(channel: *mut ffi::FMOD_CHANNEL, _type: ::ChannelCallbackType,
+                               command_data1: *mut c_void,
+                               command_data2: *mut c_void) -> ::Status {
+    let mut tmp = ::std::ptr::null_mut();
+
+    if unsafe { ffi::FMOD_Channel_GetUserData(channel, &mut tmp) } != ::Status::Ok ||
+       tmp.is_null() {
+        return ::Status::Ok;
+    }
+    let event = match _type {
+        ::ChannelCallbackType::End => ChannelEvent::End,
+        ::ChannelCallbackType::VirtualVoice => {
+            ChannelEvent::VirtualVoice(command_data1 as isize != 0)
+        }
+        ::ChannelCallbackType::SyncPoint => {
+            ChannelEvent::SyncPoint(command_data1 as isize as i32)
+        }
+        ::ChannelCallbackType::Occlusion => unsafe {
+            ChannelEvent::Occlusion {
+                direct: &mut *(command_data1 as *mut f32),
+                reverb: &mut *(command_data2 as *mut f32)
+            }
+        },
+        _ => return ::Status::Ok
+    };
+    let is_end = event == ChannelEvent::End;
+    let user_data = tmp as *mut UserData;
+    /* taken out so the closure can replace itself through Channel::set_callback */
+    let ret = match unsafe { (*user_data).callback.take() } {
+        Some(mut callback) => {
+            let ret = callback(&ffi::FFI::wrap(channel), event);
+
+            unsafe {
+                if (*user_data).callback.is_none() {
+                    (*user_data).callback = Some(callback);
+                }
+            }
+            ret
+        }
+        None => ::Status::Ok
+    };
+    if is_end {
+        unsafe {
+            ffi::FMOD_Channel_SetUserData(channel, ::std::ptr::null_mut());
+            drop(Box::from_raw(user_data));
+        }
+    }
+    ret
+}
+
 /// Channel Object
+///
+/// FMOD reuses its channels once their sound ends or when they are stolen by a more important
+/// sound. The handle it gives identifies both the channel and the generation of its slot, so a
+/// `Channel` never controls the sound played after a reuse: its methods fail with
+/// `Status::ChannelStolen` or `Status::InvalidHandle` instead, which
+/// [`is_valid`](#method.is_valid) and [`upgrade`](#method.upgrade) check beforehand.
+///
+/// Like sounds and DSPs, a channel keeps the system which plays it alive.
 pub struct Channel {
-    channel: *mut ffi::FMOD_CHANNEL
+    channel: *mut ffi::FMOD_CHANNEL,
+    /* keeps the system alive, only unset on the channels given to callbacks */
+    owner: Option<Arc<SysHandle>>
 }
 
-impl Drop for Channel {
-    fn drop(&mut self) {
-        self.release();
+pub fn from_ptr(channel: *mut ffi::FMOD_CHANNEL, owner: Option<Arc<SysHandle>>) -> Channel {
+    Channel {
+        channel: channel,
+        owner: owner
     }
 }
 
 impl ffi::FFI<ffi::FMOD_CHANNEL> for Channel {
     fn wrap(channel: *mut ffi::FMOD_CHANNEL) -> Channel {
-        Channel {channel: channel}
+        Channel {channel: channel, owner: None}
     }
 
     fn unwrap(c: &Channel) -> *mut ffi::FMOD_CHANNEL {
@@ -103,29 +196,49 @@ impl ffi::FFI<ffi::FMOD_CHANNEL> for Channel {
 }
 
 impl Channel {
-    pub fn new() -> Channel {
-        Channel {channel: ::std::ptr::null_mut()}
+    /// Drops the handle, the channel keeps playing.
+    pub fn release(self) {}
+
+    /// Returns `false` once the channel has been stolen or reused by FMOD.
+    pub fn is_valid(&self) -> bool {
+        let mut index = 0i32;
+
+        unsafe { ffi::FMOD_Channel_GetIndex(self.channel, &mut index) == ::Status::Ok }
     }
 
-    pub fn release(&mut self) {
-        self.channel = ::std::ptr::null_mut();
+    /// Returns the channel if it still plays what it was returned for, like `Weak::upgrade`.
+    ///
+    /// ```ignore
+    /// if let Some(channel) = music.upgrade() {
+    ///     channel.set_volume(0.5)?;
+    /// }
+    /// ```
+    pub fn upgrade(&self) -> Option<&Channel> {
+        if self.is_valid() {
+            Some(self)
+        } else {
+            None
+        }
     }
 
-    pub fn get_system_object(&self) -> Result<Sys, ::Status> {
+    pub fn get_system_object(&self) -> Result<Borrowed<'_, Sys>, ::Error> {
         let mut system = ::std::ptr::null_mut();
 
         match unsafe { ffi::FMOD_Channel_GetSystemObject(self.channel, &mut system) } {
-            ::Status::Ok => Ok(ffi::FFI::wrap(system)),
-            e => Err(e)
+            ::Status::Ok => Ok(borrowed::wrap(system)),
+            e => Err(::Error::new(e, "FMOD_Channel_GetSystemObject"))
         }
     }
 
-    pub fn stop(&self) -> ::Status {
-        unsafe { ffi::FMOD_Channel_Stop(self.channel) }
+    pub fn stop(&self) -> Result<(), ::Error> {
+        match unsafe { ffi::FMOD_Channel_Stop(self.channel) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_Channel_Stop"))
+        }
     }
 
     /// channel_offset:  0/1 -> left channel/right channel
-    pub fn get_spectrum(&self, spectrum_size: usize, channel_offset: Option<i32>, window_type: Option<::DspFftWindow>) -> Result<Vec<f32>, ::Status> {
+    pub fn get_spectrum(&self, spectrum_size: usize, channel_offset: Option<i32>, window_type: Option<::DspFftWindow>) -> Result<Vec<f32>, ::Error> {
         let mut ptr : Vec<f32> = ::std::iter::repeat(0f32).take(spectrum_size).collect();
         let c_window_type = match window_type {
             Some(wt) => wt,
@@ -138,16 +251,16 @@ impl Channel {
 
         match unsafe { ffi::FMOD_Channel_GetSpectrum(self.channel, ptr.as_mut_ptr(), spectrum_size as c_int, c_channel_offset, c_window_type) } {
             ::Status::Ok => Ok(ptr),
-            e => Err(e),
+            e => Err(::Error::new(e, "FMOD_Channel_GetSpectrum")),
         }
     }
 
-    pub fn get_wave_data(&self, wave_size: usize, channel_offset: i32) -> Result<Vec<f32>, ::Status> {
+    pub fn get_wave_data(&self, wave_size: usize, channel_offset: i32) -> Result<Vec<f32>, ::Error> {
         let mut ptr : Vec<f32> = ::std::iter::repeat(0f32).take(wave_size).collect();
 
         match unsafe { ffi::FMOD_Channel_GetWaveData(self.channel, ptr.as_mut_ptr(), wave_size as c_int, channel_offset) } {
             ::Status::Ok => Ok(ptr),
-            e => Err(e)
+            e => Err(::Error::new(e, "FMOD_Channel_GetWaveData"))
         }
     }
 
@@ -155,99 +268,111 @@ impl Channel {
         !self.channel.is_null()
     }
 
-    pub fn is_playing(&self) -> Result<bool, ::Status> {
+    pub fn is_playing(&self) -> Result<bool, ::Error> {
         let mut is_playing = 0;
 
         match unsafe { ffi::FMOD_Channel_IsPlaying(self.channel, &mut is_playing) } {
             ::Status::Ok => Ok(is_playing == 1),
-            err => Err(err),
+            err => Err(::Error::new(err, "FMOD_Channel_IsPlaying")),
         }
     }
 
-    pub fn is_virtual(&self) -> Result<bool, ::Status> {
+    pub fn is_virtual(&self) -> Result<bool, ::Error> {
         let mut is_virtual = 0i32;
 
         match unsafe { ffi::FMOD_Channel_IsVirtual(self.channel, &mut is_virtual) } {
             ::Status::Ok => Ok(is_virtual == 1),
-            e => Err(e)
+            e => Err(::Error::new(e, "FMOD_Channel_IsVirtual"))
         }
     }
 
-    pub fn get_audibility(&self) -> Result<f32, ::Status> {
+    pub fn get_audibility(&self) -> Result<f32, ::Error> {
         let mut audibility = 0f32;
 
         match unsafe { ffi::FMOD_Channel_GetAudibility(self.channel, &mut audibility) } {
             ::Status::Ok => Ok(audibility),
-            e => Err(e)
+            e => Err(::Error::new(e, "FMOD_Channel_GetAudibility"))
         }
     }
 
-    pub fn get_current_sound(&self) -> Result<Sound, ::Status> {
+    pub fn get_current_sound(&self) -> Result<Borrowed<'_, Sound>, ::Error> {
         let mut sound = ::std::ptr::null_mut();
 
         match unsafe { ffi::FMOD_Channel_GetCurrentSound(self.channel, &mut sound) } {
-            ::Status::Ok => Ok(ffi::FFI::wrap(sound)),
-            e => Err(e)
+            ::Status::Ok => Ok(borrowed::wrap(sound)),
+            e => Err(::Error::new(e, "FMOD_Channel_GetCurrentSound"))
         }
     }
 
-    pub fn get_index(&self) -> Result<i32, ::Status> {
+    pub fn get_index(&self) -> Result<i32, ::Error> {
         let mut index = 0i32;
 
         match unsafe { ffi::FMOD_Channel_GetIndex(self.channel, &mut index) } {
             ::Status::Ok => Ok(index),
-            e => Err(e)
+            e => Err(::Error::new(e, "FMOD_Channel_GetIndex"))
         }
     }
 
-    pub fn set_volume(&self, volume: f32) -> ::Status {
-        unsafe { ffi::FMOD_Channel_SetVolume(self.channel, volume) }
+    pub fn set_volume(&self, volume: f32) -> Result<(), ::Error> {
+        match unsafe { ffi::FMOD_Channel_SetVolume(self.channel, volume) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_Channel_SetVolume"))
+        }
     }
 
-    pub fn get_volume(&self) -> Result<f32, ::Status> {
+    pub fn get_volume(&self) -> Result<f32, ::Error> {
         let mut volume = 0f32;
 
         match unsafe { ffi::FMOD_Channel_GetVolume(self.channel, &mut volume) } {
             ::Status::Ok => Ok(volume),
-            e => Err(e),
+            e => Err(::Error::new(e, "FMOD_Channel_GetVolume")),
         }
     }
 
-    pub fn set_frequency(&self, frequency: f32) -> ::Status {
-        unsafe { ffi::FMOD_Channel_SetFrequency(self.channel, frequency) }
+    pub fn set_frequency(&self, frequency: f32) -> Result<(), ::Error> {
+        match unsafe { ffi::FMOD_Channel_SetFrequency(self.channel, frequency) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_Channel_SetFrequency"))
+        }
     }
 
-    pub fn get_frequency(&self) -> Result<f32, ::Status> {
+    pub fn get_frequency(&self) -> Result<f32, ::Error> {
         let mut frequency = 0f32;
 
         match unsafe { ffi::FMOD_Channel_GetFrequency(self.channel, &mut frequency) } {
             ::Status::Ok => Ok(frequency),
-            e => Err(e),
+            e => Err(::Error::new(e, "FMOD_Channel_GetFrequency")),
         }
     }
 
-    pub fn set_pan(&self, pan: f32) -> ::Status {
-        unsafe { ffi::FMOD_Channel_SetPan(self.channel, pan) }
+    pub fn set_pan(&self, pan: f32) -> Result<(), ::Error> {
+        match unsafe { ffi::FMOD_Channel_SetPan(self.channel, pan) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_Channel_SetPan"))
+        }
     }
 
-    pub fn get_pan(&self) -> Result<f32, ::Status> {
+    pub fn get_pan(&self) -> Result<f32, ::Error> {
         let mut pan = 0f32;
 
         match unsafe { ffi::FMOD_Channel_GetPan(self.channel, &mut pan) } {
             ::Status::Ok => Ok(pan),
-            e => Err(e),
+            e => Err(::Error::new(e, "FMOD_Channel_GetPan")),
         }
     }
 
-    pub fn set_mute(&self, mute: bool) -> ::Status {
+    pub fn set_mute(&self, mute: bool) -> Result<(), ::Error> {
         let t = match mute {
             true => 1,
             false => 0,
         };
-        unsafe { ffi::FMOD_Channel_SetMute(self.channel, t) }
+        match unsafe { ffi::FMOD_Channel_SetMute(self.channel, t) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_Channel_SetMute"))
+        }
     }
 
-    pub fn get_mute(&self) -> Result<bool, ::Status> {
+    pub fn get_mute(&self) -> Result<bool, ::Error> {
         let mut mute = 0;
 
         match unsafe { ffi::FMOD_Channel_GetMute(self.channel, &mut mute) } {
@@ -255,19 +380,22 @@ impl Channel {
                 1 => true,
                 _ => false,
             }),
-            e => Err(e),
+            e => Err(::Error::new(e, "FMOD_Channel_GetMute")),
         }
     }
 
-    pub fn set_paused(&self, paused: bool) -> ::Status {
+    pub fn set_paused(&self, paused: bool) -> Result<(), ::Error> {
         let t: ffi::FMOD_BOOL = match paused {
             true => 1,
             false => 0,
         };
-        unsafe { ffi::FMOD_Channel_SetPaused(self.channel, t) }
+        match unsafe { ffi::FMOD_Channel_SetPaused(self.channel, t) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_Channel_SetPaused"))
+        }
     }
 
-    pub fn get_paused(&self) -> Result<bool, ::Status> {
+    pub fn get_paused(&self) -> Result<bool, ::Error> {
         let mut t = 0;
 
         match unsafe { ffi::FMOD_Channel_GetPaused(self.channel, &mut t) } {
@@ -275,35 +403,41 @@ impl Channel {
                 1 => true,
                 _ => false,
             }),
-            e => Err(e),
+            e => Err(::Error::new(e, "FMOD_Channel_GetPaused")),
         }
     }
 
     pub fn set_delay(&self, delay_type: ::DelayType, delay_hi: usize,
-                     delay_lo: usize) -> ::Status {
-        unsafe { ffi::FMOD_Channel_SetDelay(self.channel, delay_type, delay_hi as u32,
-                                            delay_lo as u32) }
+                     delay_lo: usize) -> Result<(), ::Error> {
+        match unsafe { ffi::FMOD_Channel_SetDelay(self.channel, delay_type, delay_hi as u32,
+                                                  delay_lo as u32) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_Channel_SetDelay"))
+        }
     }
 
     pub fn get_delay(&self, delay_type: ::DelayType)
-                    -> Result<(::DelayType, usize, usize), ::Status> {
+                    -> Result<(::DelayType, usize, usize), ::Error> {
         let mut delaylo = 0u32;
         let mut delayhi = 0u32;
 
         match unsafe { ffi::FMOD_Channel_GetDelay(self.channel, delay_type, &mut delayhi,
                                                   &mut delaylo) } {
             ::Status::Ok => Ok((delay_type, delayhi as usize, delaylo as usize)),
-            e => Err(e),
+            e => Err(::Error::new(e, "FMOD_Channel_GetDelay")),
         }
     }
 
-    pub fn set_speaker_mix(&self, smo: &SpeakerMixOptions) -> ::Status {
-        unsafe { ffi::FMOD_Channel_SetSpeakerMix(self.channel, smo.front_left, smo.front_right,
-                                                 smo.center, smo.lfe, smo.back_left, smo.back_right,
-                                                 smo.side_left, smo.side_right) }
+    pub fn set_speaker_mix(&self, smo: &SpeakerMixOptions) -> Result<(), ::Error> {
+        match unsafe { ffi::FMOD_Channel_SetSpeakerMix(self.channel, smo.front_left, smo.front_right,
+                                                       smo.center, smo.lfe, smo.back_left, smo.back_right,
+                                                       smo.side_left, smo.side_right) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_Channel_SetSpeakerMix"))
+        }
     }
 
-    pub fn get_speaker_mix(&self) -> Result<SpeakerMixOptions, ::Status> {
+    pub fn get_speaker_mix(&self) -> Result<SpeakerMixOptions, ::Error> {
         let mut smo = SpeakerMixOptions{
                           front_left: 0f32,
                           front_right: 0f32,
@@ -321,68 +455,82 @@ impl Channel {
                                                        &mut smo.back_right, &mut smo.side_left,
                                                        &mut smo.side_right) } {
             ::Status::Ok => Ok(smo),
-            e => Err(e),
+            e => Err(::Error::new(e, "FMOD_Channel_GetSpeakerMix")),
         }
     }
 
-    pub fn set_speaker_level(&self, speaker: ::Speaker, levels: &mut Vec<f32>) -> ::Status {
-        unsafe { ffi::FMOD_Channel_SetSpeakerLevels(self.channel, speaker, levels.as_mut_ptr(),
-                                                    levels.len() as i32) }
+    pub fn set_speaker_level(&self, speaker: ::Speaker, levels: &mut Vec<f32>) -> Result<(), ::Error> {
+        match unsafe { ffi::FMOD_Channel_SetSpeakerLevels(self.channel, speaker, levels.as_mut_ptr(),
+                                                          levels.len() as i32) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_Channel_SetSpeakerLevels"))
+        }
     }
 
     pub fn get_speaker_level(&self, speaker: ::Speaker,
-                             num_levels: usize) -> Result<Vec<f32>, ::Status> {
+                             num_levels: usize) -> Result<Vec<f32>, ::Error> {
         let mut ptr : Vec<f32> = ::std::iter::repeat(0f32).take(num_levels).collect();
 
         match unsafe { ffi::FMOD_Channel_GetSpeakerLevels(self.channel, speaker, ptr.as_mut_ptr(),
                                                           num_levels as i32) } {
             ::Status::Ok => Ok(ptr),
-            e => Err(e),
+            e => Err(::Error::new(e, "FMOD_Channel_GetSpeakerLevels")),
         }
     }
 
-    pub fn set_input_channel_mix(&self, levels: &mut Vec<f32>) -> ::Status {
-        unsafe { ffi::FMOD_Channel_SetInputChannelMix(self.channel, levels.as_mut_ptr(),
-                                                      levels.len() as i32) }
+    pub fn set_input_channel_mix(&self, levels: &mut Vec<f32>) -> Result<(), ::Error> {
+        match unsafe { ffi::FMOD_Channel_SetInputChannelMix(self.channel, levels.as_mut_ptr(),
+                                                            levels.len() as i32) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_Channel_SetInputChannelMix"))
+        }
     }
 
-    pub fn get_input_channel_mix(&self, num_levels: usize) -> Result<Vec<f32>, ::Status> {
+    pub fn get_input_channel_mix(&self, num_levels: usize) -> Result<Vec<f32>, ::Error> {
         let mut ptr : Vec<f32> = ::std::iter::repeat(0f32).take(num_levels).collect();
 
         match unsafe { ffi::FMOD_Channel_GetInputChannelMix(self.channel, ptr.as_mut_ptr(),
                                                             num_levels as i32) } {
             ::Status::Ok => Ok(ptr),
-            e => Err(e),
+            e => Err(::Error::new(e, "FMOD_Channel_GetInputChannelMix")),
         }
     }
 
-    pub fn set_priority(&self, priority: i32) -> ::Status {
-        unsafe { ffi::FMOD_Channel_SetPriority(self.channel, priority) }
+    pub fn set_priority(&self, priority: i32) -> Result<(), ::Error> {
+        match unsafe { ffi::FMOD_Channel_SetPriority(self.channel, priority) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_Channel_SetPriority"))
+        }
     }
 
-    pub fn get_priority(&self) -> Result<i32, ::Status> {
+    pub fn get_priority(&self) -> Result<i32, ::Error> {
         let mut t = 0i32;
 
         match unsafe { ffi::FMOD_Channel_GetPriority(self.channel, &mut t) } {
             ::Status::Ok => Ok(t),
-            e => Err(e),
+            e => Err(::Error::new(e, "FMOD_Channel_GetPriority")),
         }
     }
 
-    pub fn set_position(&self, position: usize, TimeUnit(postype): TimeUnit) -> ::Status {
-        unsafe { ffi::FMOD_Channel_SetPosition(self.channel, position as u32, postype) }
+    pub fn set_position(&self, position: Position) -> Result<(), ::Error> {
+        match unsafe { ffi::FMOD_Channel_SetPosition(self.channel, position.value(),
+                                                     position.time_unit().bits()) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_Channel_SetPosition"))
+        }
     }
 
-    pub fn get_position(&self, TimeUnit(postype): TimeUnit) -> Result<usize, ::Status> {
+    pub fn get_position(&self, unit: TimeUnit) -> Result<Position, ::Error> {
         let mut t = 0u32;
 
-        match unsafe { ffi::FMOD_Channel_GetPosition(self.channel, &mut t, postype) } {
-            ::Status::Ok => Ok(t as usize),
-            e => Err(e),
+        match unsafe { ffi::FMOD_Channel_GetPosition(self.channel, &mut t, unit.bits()) } {
+            ::Status::Ok => Position::new(t, unit).ok_or(::Error::new(::Status::InvalidParam,
+                                                                     "Channel::get_position")),
+            e => Err(::Error::new(e, "FMOD_Channel_GetPosition")),
         }
     }
 
-    pub fn set_reverb_properties(&self, prop: &ReverbChannelProperties) -> ::Status {
+    pub fn set_reverb_properties(&self, prop: &ReverbChannelProperties) -> Result<(), ::Error> {
         let t = ffi::FMOD_REVERB_CHANNELPROPERTIES{
                     Direct: prop.direct,
                     Room: prop.room,
@@ -390,10 +538,13 @@ impl Channel {
                     ConnectionPoint: ::std::ptr::null_mut()
                 };
 
-        unsafe { ffi::FMOD_Channel_SetReverbProperties(self.channel, &t) }
+        match unsafe { ffi::FMOD_Channel_SetReverbProperties(self.channel, &t) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_Channel_SetReverbProperties"))
+        }
     }
 
-    pub fn get_reverb_properties(&self) -> Result<ReverbChannelProperties, ::Status> {
+    pub fn get_reverb_properties(&self) -> Result<ReverbChannelProperties, ::Error> {
         let mut t = ffi::FMOD_REVERB_CHANNELPROPERTIES{
                         Direct: 0,
                         Room: 0,
@@ -407,77 +558,92 @@ impl Channel {
                 room: t.Room,
                 flags: t.Flags,
                 connection_point: ffi::FFI::wrap(t.ConnectionPoint)}),
-            e => Err(e),
+            e => Err(::Error::new(e, "FMOD_Channel_GetReverbProperties")),
         }
     }
 
-    pub fn set_low_pass_gain(&self, gain: f32) -> ::Status {
-        unsafe { ffi::FMOD_Channel_SetLowPassGain(self.channel, gain) }
+    pub fn set_low_pass_gain(&self, gain: f32) -> Result<(), ::Error> {
+        match unsafe { ffi::FMOD_Channel_SetLowPassGain(self.channel, gain) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_Channel_SetLowPassGain"))
+        }
     }
 
-    pub fn get_low_pass_gain(&self) -> Result<f32, ::Status> {
+    pub fn get_low_pass_gain(&self) -> Result<f32, ::Error> {
         let mut t = 0f32;
 
         match unsafe { ffi::FMOD_Channel_GetLowPassGain(self.channel, &mut t) } {
             ::Status::Ok => Ok(t),
-            e => Err(e),
+            e => Err(::Error::new(e, "FMOD_Channel_GetLowPassGain")),
         }
     }
 
-    pub fn set_channel_group(&mut self, channel_group: &ChannelGroup) -> ::Status {
-        unsafe { ffi::FMOD_Channel_SetChannelGroup(self.channel, ffi::FFI::unwrap(channel_group)) }
+    pub fn set_channel_group(&mut self, channel_group: &ChannelGroup) -> Result<(), ::Error> {
+        match unsafe { ffi::FMOD_Channel_SetChannelGroup(self.channel, ffi::FFI::unwrap(channel_group)) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_Channel_SetChannelGroup"))
+        }
     }
 
-    pub fn get_channel_group(&self) -> Result<ChannelGroup, ::Status> {
+    pub fn get_channel_group(&self) -> Result<Borrowed<'_, ChannelGroup>, ::Error> {
         let mut channel_group = ::std::ptr::null_mut();
 
         match unsafe { ffi::FMOD_Channel_GetChannelGroup(self.channel, &mut channel_group) } {
-            ::Status::Ok => Ok(ffi::FFI::wrap(channel_group)),
-            e => Err(e)
+            ::Status::Ok => Ok(borrowed::wrap(channel_group)),
+            e => Err(::Error::new(e, "FMOD_Channel_GetChannelGroup"))
         }
     }
 
     pub fn set_3D_attributes(&self, position: &vector::Vector,
-                             velocity: &vector::Vector) -> ::Status {
+                             velocity: &vector::Vector) -> Result<(), ::Error> {
         let mut t_position = vector::get_ffi(position);
         let mut t_velocity = vector::get_ffi(velocity);
 
-        unsafe { ffi::FMOD_Channel_Set3DAttributes(self.channel, &mut t_position, &mut t_velocity) }
+        match unsafe { ffi::FMOD_Channel_Set3DAttributes(self.channel, &mut t_position, &mut t_velocity) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_Channel_Set3DAttributes"))
+        }
     }
 
-    pub fn get_3D_attributes(&self) -> Result<(vector::Vector, vector::Vector), ::Status> {
+    pub fn get_3D_attributes(&self) -> Result<(vector::Vector, vector::Vector), ::Error> {
         let mut position = vector::get_ffi(&vector::Vector::new());
         let mut velocity = vector::get_ffi(&vector::Vector::new());
 
         match unsafe { ffi::FMOD_Channel_Get3DAttributes(self.channel, &mut position,
                                                          &mut velocity) } {
             ::Status::Ok => Ok((vector::from_ptr(position), vector::from_ptr(velocity))),
-            e => Err(e)
+            e => Err(::Error::new(e, "FMOD_Channel_Get3DAttributes"))
         }
     }
 
-    pub fn set_3D_min_max_distance(&self, min_distance: f32, max_distance: f32) -> ::Status {
-        unsafe { ffi::FMOD_Channel_Set3DMinMaxDistance(self.channel, min_distance, max_distance) }
+    pub fn set_3D_min_max_distance(&self, min_distance: f32, max_distance: f32) -> Result<(), ::Error> {
+        match unsafe { ffi::FMOD_Channel_Set3DMinMaxDistance(self.channel, min_distance, max_distance) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_Channel_Set3DMinMaxDistance"))
+        }
     }
 
-    pub fn get_3D_min_max_distance(&self) -> Result<(f32, f32), ::Status> {
+    pub fn get_3D_min_max_distance(&self) -> Result<(f32, f32), ::Error> {
         let mut min_distance = 0f32;
         let mut max_distance = 0f32;
 
         match unsafe { ffi::FMOD_Channel_Get3DMinMaxDistance(self.channel, &mut min_distance,
                                                              &mut max_distance) } {
             ::Status::Ok => Ok((min_distance, max_distance)),
-            e => Err(e)
+            e => Err(::Error::new(e, "FMOD_Channel_Get3DMinMaxDistance"))
         }
     }
 
     pub fn set_3D_cone_settings(&self, inside_cone_angle: f32, outside_cone_angle: f32,
-                                outside_volume: f32) -> ::Status {
-        unsafe { ffi::FMOD_Channel_Set3DConeSettings(self.channel, inside_cone_angle,
-                                                     outside_cone_angle, outside_volume) }
+                                outside_volume: f32) -> Result<(), ::Error> {
+        match unsafe { ffi::FMOD_Channel_Set3DConeSettings(self.channel, inside_cone_angle,
+                                                           outside_cone_angle, outside_volume) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_Channel_Set3DConeSettings"))
+        }
     }
 
-    pub fn get_3D_cone_settings(&self) -> Result<(f32, f32, f32), ::Status> {
+    pub fn get_3D_cone_settings(&self) -> Result<(f32, f32, f32), ::Error> {
         let mut inside_cone_angle = 0f32;
         let mut outside_cone_angle = 0f32;
         let mut outside_volume = 0f32;
@@ -486,36 +652,42 @@ impl Channel {
                                                            &mut outside_cone_angle,
                                                            &mut outside_volume) } {
             ::Status::Ok => Ok((inside_cone_angle, outside_cone_angle, outside_volume)),
-            e => Err(e)
+            e => Err(::Error::new(e, "FMOD_Channel_Get3DConeSettings"))
         }
     }
 
-    pub fn set_3D_cone_orientation(&self, orientation: &vector::Vector) -> ::Status {
+    pub fn set_3D_cone_orientation(&self, orientation: &vector::Vector) -> Result<(), ::Error> {
         let mut t_orientation = vector::get_ffi(orientation);
 
-        unsafe { ffi::FMOD_Channel_Set3DConeOrientation(self.channel, &mut t_orientation) }
+        match unsafe { ffi::FMOD_Channel_Set3DConeOrientation(self.channel, &mut t_orientation) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_Channel_Set3DConeOrientation"))
+        }
     }
 
-    pub fn get_3D_cone_orientation(&self) -> Result<vector::Vector, ::Status> {
+    pub fn get_3D_cone_orientation(&self) -> Result<vector::Vector, ::Error> {
         let mut orientation = vector::get_ffi(&vector::Vector::new());
 
         match unsafe { ffi::FMOD_Channel_Get3DConeOrientation(self.channel, &mut orientation) } {
             ::Status::Ok => Ok(vector::from_ptr(orientation)),
-            e => Err(e)
+            e => Err(::Error::new(e, "FMOD_Channel_Get3DConeOrientation"))
         }
     }
 
-    pub fn set_3D_custom_rolloff(&self, points: &Vec<vector::Vector>) -> ::Status {
+    pub fn set_3D_custom_rolloff(&self, points: &Vec<vector::Vector>) -> Result<(), ::Error> {
         let mut t_points = Vec::new();
 
         for tmp in points.iter() {
             t_points.push(vector::get_ffi(tmp));
         }
-        unsafe { ffi::FMOD_Channel_Set3DCustomRolloff(self.channel, t_points.as_mut_ptr(),
-                                                      points.len() as c_int) }
+        match unsafe { ffi::FMOD_Channel_Set3DCustomRolloff(self.channel, t_points.as_mut_ptr(),
+                                                            points.len() as c_int) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_Channel_Set3DCustomRolloff"))
+        }
     }
 
-    pub fn get_3D_custom_rolloff(&self) -> Result<Vec<vector::Vector>, ::Status> {
+    pub fn get_3D_custom_rolloff(&self) -> Result<Vec<vector::Vector>, ::Error> {
         let mut points = ::std::ptr::null_mut();
         let mut num_points = 0i32;
 
@@ -530,76 +702,91 @@ impl Channel {
                     }
                     Ok(ret_points)
                 }
-                e => Err(e)
+                e => Err(::Error::new(e, "FMOD_Channel_Get3DCustomRolloff"))
             }
         }
     }
 
-    pub fn set_3D_occlusion(&self, direct_occlusion: f32, reverb_occlusion: f32) -> ::Status {
-        unsafe { ffi::FMOD_Channel_Set3DOcclusion(self.channel, direct_occlusion,
-                                                  reverb_occlusion) }
+    pub fn set_3D_occlusion(&self, direct_occlusion: f32, reverb_occlusion: f32) -> Result<(), ::Error> {
+        match unsafe { ffi::FMOD_Channel_Set3DOcclusion(self.channel, direct_occlusion,
+                                                        reverb_occlusion) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_Channel_Set3DOcclusion"))
+        }
     }
 
-    pub fn get_3D_occlusion(&self) -> Result<(f32, f32), ::Status> {
+    pub fn get_3D_occlusion(&self) -> Result<(f32, f32), ::Error> {
         let mut direct_occlusion = 0f32;
         let mut reverb_occlusion = 0f32;
 
         match unsafe { ffi::FMOD_Channel_Get3DOcclusion(self.channel, &mut direct_occlusion,
                                                         &mut reverb_occlusion) } {
             ::Status::Ok => Ok((direct_occlusion, reverb_occlusion)),
-            e => Err(e)
+            e => Err(::Error::new(e, "FMOD_Channel_Get3DOcclusion"))
         }
     }
 
-    pub fn set_3D_spread(&self, angle: f32) -> ::Status {
-        unsafe { ffi::FMOD_Channel_Set3DSpread(self.channel, angle) }
+    pub fn set_3D_spread(&self, angle: f32) -> Result<(), ::Error> {
+        match unsafe { ffi::FMOD_Channel_Set3DSpread(self.channel, angle) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_Channel_Set3DSpread"))
+        }
     }
 
-    pub fn get_3D_spread(&self) -> Result<f32, ::Status> {
+    pub fn get_3D_spread(&self) -> Result<f32, ::Error> {
         let mut angle = 0f32;
 
         match unsafe { ffi::FMOD_Channel_Get3DSpread(self.channel, &mut angle) } {
             ::Status::Ok => Ok(angle),
-            e => Err(e)
+            e => Err(::Error::new(e, "FMOD_Channel_Get3DSpread"))
         }
     }
 
-    pub fn set_3D_pan_level(&self, level: f32) -> ::Status {
-        unsafe { ffi::FMOD_Channel_Set3DPanLevel(self.channel, level) }
+    pub fn set_3D_pan_level(&self, level: f32) -> Result<(), ::Error> {
+        match unsafe { ffi::FMOD_Channel_Set3DPanLevel(self.channel, level) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_Channel_Set3DPanLevel"))
+        }
     }
 
-    pub fn get_3D_pan_level(&self) -> Result<f32, ::Status> {
+    pub fn get_3D_pan_level(&self) -> Result<f32, ::Error> {
         let mut level = 0f32;
 
         match unsafe { ffi::FMOD_Channel_Get3DPanLevel(self.channel, &mut level) } {
             ::Status::Ok => Ok(level),
-            e => Err(e)
+            e => Err(::Error::new(e, "FMOD_Channel_Get3DPanLevel"))
         }
     }
 
-    pub fn set_3D_doppler_level(&self, level: f32) -> ::Status {
-        unsafe { ffi::FMOD_Channel_Set3DDopplerLevel(self.channel, level) }
+    pub fn set_3D_doppler_level(&self, level: f32) -> Result<(), ::Error> {
+        match unsafe { ffi::FMOD_Channel_Set3DDopplerLevel(self.channel, level) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_Channel_Set3DDopplerLevel"))
+        }
     }
 
-    pub fn get_3D_doppler_level(&self) -> Result<f32, ::Status> {
+    pub fn get_3D_doppler_level(&self) -> Result<f32, ::Error> {
         let mut level = 0f32;
 
         match unsafe { ffi::FMOD_Channel_Get3DDopplerLevel(self.channel, &mut level) } {
             ::Status::Ok => Ok(level),
-            e => Err(e)
+            e => Err(::Error::new(e, "FMOD_Channel_Get3DDopplerLevel"))
         }
     }
 
     pub fn set_3D_distance_filter(&self, custom: bool, custom_level: f32,
-                                  center_freq: f32) -> ::Status {
-        unsafe { ffi::FMOD_Channel_Set3DDistanceFilter(self.channel, if custom {
-                1
-            } else {
-                0
-            }, custom_level, center_freq) }
+                                  center_freq: f32) -> Result<(), ::Error> {
+        match unsafe { ffi::FMOD_Channel_Set3DDistanceFilter(self.channel, if custom {
+                      1
+                  } else {
+                      0
+                  }, custom_level, center_freq) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_Channel_Set3DDistanceFilter"))
+        }
     }
 
-    pub fn get_3D_distance_filter(&self) -> Result<(bool, f32, f32), ::Status> {
+    pub fn get_3D_distance_filter(&self) -> Result<(bool, f32, f32), ::Error> {
         let mut custom = 0i32;
         let mut custom_level = 0f32;
         let mut center_freq = 0f32;
@@ -608,103 +795,188 @@ impl Channel {
                                                              &mut custom_level,
                                                              &mut center_freq) } {
             ::Status::Ok => Ok((custom == 1, custom_level, center_freq)),
-            e => Err(e)
+            e => Err(::Error::new(e, "FMOD_Channel_Get3DDistanceFilter"))
         }
     }
 
-    pub fn get_DSP_head(&self) -> Result<Dsp, ::Status> {
+    pub fn get_DSP_head(&self) -> Result<Borrowed<'_, Dsp>, ::Error> {
         let mut dsp = ::std::ptr::null_mut();
 
         match unsafe { ffi::FMOD_Channel_GetDSPHead(self.channel, &mut dsp) } {
-            ::Status::Ok => Ok(ffi::FFI::wrap(dsp)),
-            e => Err(e)
+            ::Status::Ok => Ok(borrowed::wrap(dsp)),
+            e => Err(::Error::new(e, "FMOD_Channel_GetDSPHead"))
         }
     }
 
-    pub fn add_DSP(&self, dsp: &Dsp) -> Result<DspConnection, ::Status> {
+    pub fn add_DSP(&self, dsp: &Dsp) -> Result<DspConnection, ::Error> {
+        let owner = fmod_sys::get_owner(&*self.get_system_object()?);
         let mut connection = ::std::ptr::null_mut();
 
         match unsafe { ffi::FMOD_Channel_AddDSP(self.channel, ffi::FFI::unwrap(dsp),
                                                 &mut connection) } {
-            ::Status::Ok => Ok(ffi::FFI::wrap(connection)),
-            e => Err(e)
+            ::Status::Ok => Ok(dsp_connection::from_ptr(connection, owner)),
+            e => Err(::Error::new(e, "FMOD_Channel_AddDSP"))
         }
     }
 
-    pub fn set_mode(&self, Mode(mode): Mode) -> ::Status {
-        unsafe { ffi::FMOD_Channel_SetMode(self.channel, mode) }
+    pub fn set_mode(&self, mode: Mode) -> Result<(), ::Error> {
+        mode.validate()?;
+        match unsafe { ffi::FMOD_Channel_SetMode(self.channel, mode.bits()) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_Channel_SetMode"))
+        }
     }
 
-    pub fn get_mode(&self) -> Result<Mode, ::Status> {
+    pub fn get_mode(&self) -> Result<Mode, ::Error> {
         let mut mode = 0u32;
 
         match unsafe { ffi::FMOD_Channel_GetMode(self.channel, &mut mode) } {
             ::Status::Ok => Ok(Mode(mode)),
-            e => Err(e)
+            e => Err(::Error::new(e, "FMOD_Channel_GetMode"))
         }
     }
 
-    pub fn set_loop_count(&self, loop_count: i32) -> ::Status {
-        unsafe { ffi::FMOD_Channel_SetLoopCount(self.channel, loop_count) }
+    pub fn set_loop_count(&self, loop_count: i32) -> Result<(), ::Error> {
+        match unsafe { ffi::FMOD_Channel_SetLoopCount(self.channel, loop_count) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_Channel_SetLoopCount"))
+        }
     }
 
-    pub fn get_loop_count(&self) -> Result<i32, ::Status> {
+    pub fn get_loop_count(&self) -> Result<i32, ::Error> {
         let mut loop_count = 0i32;
 
         match unsafe { ffi::FMOD_Channel_GetLoopCount(self.channel, &mut loop_count) } {
             ::Status::Ok => Ok(loop_count),
-            e => Err(e)
+            e => Err(::Error::new(e, "FMOD_Channel_GetLoopCount"))
         }
     }
 
-    pub fn set_loop_points(&self, loop_start: u32, TimeUnit(loop_start_type): TimeUnit,
-        loop_end: u32, TimeUnit(loop_end_type): TimeUnit) -> ::Status {
-            unsafe { ffi::FMOD_Channel_SetLoopPoints(self.channel, loop_start, loop_start_type,
-                                                     loop_end, loop_end_type) }
+    pub fn set_loop_points(&self, loop_start: Position,
+                           loop_end: Position) -> Result<(), ::Error> {
+        match unsafe { ffi::FMOD_Channel_SetLoopPoints(self.channel, loop_start.value(),
+                                                       loop_start.time_unit().bits(),
+                                                       loop_end.value(),
+                                                       loop_end.time_unit().bits()) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_Channel_SetLoopPoints"))
+        }
     }
 
-    pub fn get_loop_points(&self, TimeUnit(loop_start_type): TimeUnit,
-                           TimeUnit(loop_end_type): TimeUnit) -> Result<(u32, u32), ::Status> {
+    /// Returns:
+    ///
+    /// Ok(loop_start, loop_end)
+    pub fn get_loop_points(&self, loop_start_type: TimeUnit,
+                           loop_end_type: TimeUnit) -> Result<(Position, Position), ::Error> {
         let mut loop_start = 0u32;
         let mut loop_end = 0u32;
 
         match unsafe { ffi::FMOD_Channel_GetLoopPoints(self.channel, &mut loop_start,
-                                                       loop_start_type, &mut loop_end,
-                                                       loop_end_type) } {
-            ::Status::Ok => Ok((loop_start, loop_end)),
-            e => Err(e)
+                                                       loop_start_type.bits(), &mut loop_end,
+                                                       loop_end_type.bits()) } {
+            ::Status::Ok => match (Position::new(loop_start, loop_start_type),
+                                   Position::new(loop_end, loop_end_type)) {
+                (Some(start), Some(end)) => Ok((start, end)),
+                _ => Err(::Error::new(::Status::InvalidParam, "Channel::get_loop_points"))
+            },
+            e => Err(::Error::new(e, "FMOD_Channel_GetLoopPoints"))
         }
     }
 
-    pub fn set_user_data<'r, T>(&'r self, user_data: &'r mut T) -> ::Status {
-        unsafe { ffi::FMOD_Channel_SetUserData(self.channel, transmute(user_data)) }
+    /// Sets a closure called on the channel events, replacing the previous one. The closure is
+    /// called from [`Sys::update`](struct.Sys.html#method.update), except for
+    /// [`ChannelEvent::Occlusion`](enum.ChannelEvent.html) which is called from the geometry
+    /// thread. It is dropped after [`ChannelEvent::End`](enum.ChannelEvent.html) is received.
+    pub fn set_callback<F>(&self, callback: F) -> Result<(), ::Error>
+        where F: FnMut(&Channel, ChannelEvent) -> ::Status + Send + 'static {
+        let user_data = self.get_or_create_user_data()?;
+
+        unsafe { (*user_data).callback = Some(Box::new(callback)) };
+        Ok(())
     }
 
-    pub fn get_user_data<'r, T>(&'r self) -> Result<&'r mut T, ::Status> {
-        unsafe {
-            let mut user_data : *mut c_void = ::std::ptr::null_mut();
+    /// Removes the closure set with [`Channel::set_callback`](#method.set_callback).
+    pub fn remove_callback(&self) -> Result<(), ::Error> {
+        let user_data = self.get_user_data_ptr()?;
 
-            match ffi::FMOD_Channel_GetUserData(self.channel, &mut user_data) {
-               ::Status::Ok => {
-                    let tmp : &mut T = transmute::<*mut c_void, &mut T>(user_data);
-                    
-                    Ok(tmp)
-                },
-                e => Err(e)
+        if !user_data.is_null() {
+            unsafe { (*user_data).callback = None };
+        }
+        Ok(())
+    }
+
+    fn get_user_data_ptr(&self) -> Result<*mut UserData, ::Error> {
+        let mut user_data = ::std::ptr::null_mut();
+
+        match unsafe { ffi::FMOD_Channel_GetUserData(self.channel, &mut user_data) } {
+            ::Status::Ok => Ok(user_data as *mut UserData),
+            e => Err(::Error::new(e, "FMOD_Channel_GetUserData"))
+        }
+    }
+
+    fn get_or_create_user_data(&self) -> Result<*mut UserData, ::Error> {
+        let user_data = self.get_user_data_ptr()?;
+
+        if !user_data.is_null() {
+            return Ok(user_data);
+        }
+        let user_data = Box::into_raw(Box::new(UserData {
+            callback: None,
+            user_data: None
+        }));
+
+        match unsafe { ffi::FMOD_Channel_SetUserData(self.channel, user_data as *mut c_void) } {
+            ::Status::Ok => {}
+            e => {
+                unsafe { drop(Box::from_raw(user_data)) };
+                return Err(::Error::new(e, "FMOD_Channel_SetUserData"));
+            }
+        }
+        /* the callback frees the user data once the channel has ended */
+        let callback = channel_callback as extern "C" fn(*mut _, _, *mut _, *mut _) -> _;
+
+        match unsafe { ffi::FMOD_Channel_SetCallback(self.channel, Some(callback)) } {
+            ::Status::Ok => Ok(user_data),
+            e => {
+                unsafe {
+                    ffi::FMOD_Channel_SetUserData(self.channel, ::std::ptr::null_mut());
+                    drop(Box::from_raw(user_data));
+                }
+                Err(::Error::new(e, "FMOD_Channel_SetCallback"))
             }
         }
     }
 
+    /// Stores `user_data` in the channel, replacing and freeing the previous one. It is freed
+    /// once the channel has ended.
+    pub fn set_user_data<T: Any + Send>(&self, user_data: T) -> Result<(), ::Error> {
+        let data = self.get_or_create_user_data()?;
+
+        unsafe { (*data).user_data = Some(Box::new(user_data)) };
+        Ok(())
+    }
+
+    /// Returns a copy of the user data if it is a `T`. It can't be borrowed since
+    /// [`Sys::update`](struct.Sys.html#method.update) frees it once the channel has ended.
+    pub fn get_user_data<T: Any + Clone>(&self) -> Option<T> {
+        match self.get_user_data_ptr() {
+            Ok(data) if !data.is_null() => unsafe {
+                (*data).user_data.as_ref().and_then(|d| d.downcast_ref()).cloned()
+            },
+            _ => None
+        }
+    }
+
     pub fn get_memory_info(&self, MemoryBits(memory_bits): MemoryBits,
                            EventMemoryBits(event_memory_bits): EventMemoryBits)
-                           -> Result<(u32, MemoryUsageDetails), ::Status> {
+                           -> Result<(u32, MemoryUsageDetails), ::Error> {
         let mut details = fmod_sys::get_memory_usage_details_ffi(Default::default());
         let mut memory_used = 0u32;
 
         match unsafe { ffi::FMOD_Channel_GetMemoryInfo(self.channel, memory_bits, event_memory_bits,
                                                        &mut memory_used, &mut details) } {
             ::Status::Ok => Ok((memory_used, fmod_sys::from_memory_usage_details_ptr(details))),
-            e => Err(e)
+            e => Err(::Error::new(e, "FMOD_Channel_GetMemoryInfo"))
         }
     }
 }
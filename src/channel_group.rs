@@ -24,31 +24,37 @@
 
 use types::*;
 use ffi;
+use user_data;
+use std::any::Any;
 use channel;
 use dsp;
 use dsp_connection;
 use libc::{c_int, c_void};
 use vector;
 use fmod_sys;
-use fmod_sys::MemoryUsageDetails;
-use std::mem::transmute;
+use fmod_sys::{MemoryUsageDetails, SysHandle};
+use std::sync::Arc;
+use borrowed;
+use borrowed::Borrowed;
 use libc::{c_char};
 use std::default::Default;
 
 /// ChannelGroup object
 pub struct ChannelGroup {
     channel_group: *mut ffi::FMOD_CHANNELGROUP,
+    /* keeps the system alive, only set on the channel groups releasing their FMOD channel group */
+    owner: Option<Arc<SysHandle>>
 }
 
 impl Drop for ChannelGroup {
     fn drop(&mut self) {
-        self.release();
+        let _ = self.release();
     }
 }
 
 impl ffi::FFI<ffi::FMOD_CHANNELGROUP> for ChannelGroup {
     fn wrap(channel_group: *mut ffi::FMOD_CHANNELGROUP) -> ChannelGroup {
-        ChannelGroup {channel_group: channel_group}
+        ChannelGroup {channel_group: channel_group, owner: None}
     }
 
     fn unwrap(c: &ChannelGroup) -> *mut ffi::FMOD_CHANNELGROUP {
@@ -56,57 +62,86 @@ impl ffi::FFI<ffi::FMOD_CHANNELGROUP> for ChannelGroup {
     }
 }
 
+pub fn from_ptr_first(channel_group: *mut ffi::FMOD_CHANNELGROUP,
+                      owner: Option<Arc<SysHandle>>) -> ChannelGroup {
+    ChannelGroup {channel_group: channel_group, owner: owner}
+}
+
 impl ChannelGroup {
-    pub fn release(&mut self) -> ::Status {
-        if !self.channel_group.is_null() {
+    /// Releases the channel group if it was created by this object, borrowed ones are left
+    /// untouched.
+    pub fn release(&mut self) -> Result<(), ::Error> {
+        if self.owner.is_some() && !self.channel_group.is_null() {
+            let data = self.get_user_data_ptr()?;
+
             match unsafe { ffi::FMOD_ChannelGroup_Release(self.channel_group) } {
                ::Status::Ok => {
                     self.channel_group = ::std::ptr::null_mut();
-                   ::Status::Ok
+                    self.owner = None;
+                    unsafe { user_data::free(data) };
+                   Ok(())
                 }
-                e => e
+                e => Err(::Error::new(e, "FMOD_ChannelGroup_Release"))
             }
         } else {
-           ::Status::Ok
+           Ok(())
+        }
+    }
+
+    pub fn get_system_object(&self) -> Result<Borrowed<'_, fmod_sys::Sys>, ::Error> {
+        let mut system = ::std::ptr::null_mut();
+
+        match unsafe { ffi::FMOD_ChannelGroup_GetSystemObject(self.channel_group, &mut system) } {
+            ::Status::Ok => Ok(borrowed::wrap(system)),
+            e => Err(::Error::new(e, "FMOD_ChannelGroup_GetSystemObject"))
         }
     }
 
-    pub fn set_volume(&self, volume: f32) -> ::Status {
-        unsafe { ffi::FMOD_ChannelGroup_SetVolume(self.channel_group, volume) }
+    pub fn set_volume(&self, volume: f32) -> Result<(), ::Error> {
+        match unsafe { ffi::FMOD_ChannelGroup_SetVolume(self.channel_group, volume) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_ChannelGroup_SetVolume"))
+        }
     }
 
-    pub fn get_volume(&self) -> Result<f32, ::Status> {
+    pub fn get_volume(&self) -> Result<f32, ::Error> {
         let mut volume = 0f32;
 
         match unsafe { ffi::FMOD_ChannelGroup_GetVolume(self.channel_group, &mut volume) } {
             ::Status::Ok => Ok(volume),
-            e => Err(e)
+            e => Err(::Error::new(e, "FMOD_ChannelGroup_GetVolume"))
         }
     }
 
-    pub fn set_pitch(&self, pitch: f32) -> ::Status {
-        unsafe { ffi::FMOD_ChannelGroup_SetPitch(self.channel_group, pitch) }
+    pub fn set_pitch(&self, pitch: f32) -> Result<(), ::Error> {
+        match unsafe { ffi::FMOD_ChannelGroup_SetPitch(self.channel_group, pitch) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_ChannelGroup_SetPitch"))
+        }
     }
 
-    pub fn get_pitch(&self) -> Result<f32, ::Status> {
+    pub fn get_pitch(&self) -> Result<f32, ::Error> {
         let mut pitch = 0f32;
 
         match unsafe { ffi::FMOD_ChannelGroup_GetPitch(self.channel_group, &mut pitch) } {
             ::Status::Ok => Ok(pitch),
-            e => Err(e)
+            e => Err(::Error::new(e, "FMOD_ChannelGroup_GetPitch"))
         }
     }
 
-    pub fn set_paused(&self, paused: bool) -> ::Status {
+    pub fn set_paused(&self, paused: bool) -> Result<(), ::Error> {
         let t_paused = match paused {
             true => 1,
             _ => 0
         };
 
-        unsafe { ffi::FMOD_ChannelGroup_SetPaused(self.channel_group, t_paused) }
+        match unsafe { ffi::FMOD_ChannelGroup_SetPaused(self.channel_group, t_paused) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_ChannelGroup_SetPaused"))
+        }
     }
 
-    pub fn get_paused(&self) -> Result<bool, ::Status> {
+    pub fn get_paused(&self) -> Result<bool, ::Error> {
         let mut paused = 0;
 
         match unsafe { ffi::FMOD_ChannelGroup_GetPaused(self.channel_group, &mut paused) } {
@@ -114,20 +149,23 @@ impl ChannelGroup {
                 1 => true,
                 _ => false
             }),
-            e => Err(e)
+            e => Err(::Error::new(e, "FMOD_ChannelGroup_GetPaused"))
         }
     }
 
-    pub fn set_mute(&self, mute: bool) -> ::Status {
+    pub fn set_mute(&self, mute: bool) -> Result<(), ::Error> {
         let t_mute = match mute {
             true => 1,
             _ => 0
         };
 
-        unsafe { ffi::FMOD_ChannelGroup_SetMute(self.channel_group, t_mute) }
+        match unsafe { ffi::FMOD_ChannelGroup_SetMute(self.channel_group, t_mute) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_ChannelGroup_SetMute"))
+        }
     }
 
-    pub fn get_mute(&self) -> Result<bool, ::Status> {
+    pub fn get_mute(&self) -> Result<bool, ::Error> {
         let mut mute = 0;
 
         match unsafe { ffi::FMOD_ChannelGroup_GetMute(self.channel_group, &mut mute) } {
@@ -135,16 +173,20 @@ impl ChannelGroup {
                 1 => true,
                 _ => false
             }),
-            e => Err(e)
+            e => Err(::Error::new(e, "FMOD_ChannelGroup_GetMute"))
         }
     }
 
-    pub fn set_3D_occlusion(&self, direct_occlusion: f32, reverb_occlusion: f32) -> ::Status {
-        unsafe { ffi::FMOD_ChannelGroup_Set3DOcclusion(self.channel_group, direct_occlusion,
-                                                       reverb_occlusion) }
+    pub fn set_3D_occlusion(&self, direct_occlusion: f32,
+                            reverb_occlusion: f32) -> Result<(), ::Error> {
+        match unsafe { ffi::FMOD_ChannelGroup_Set3DOcclusion(self.channel_group, direct_occlusion,
+                                                             reverb_occlusion) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_ChannelGroup_Set3DOcclusion"))
+        }
     }
 
-    pub fn get_3D_occlusion(&self) -> Result<(f32, f32), ::Status> {
+    pub fn get_3D_occlusion(&self) -> Result<(f32, f32), ::Error> {
         let mut direct_occlusion = 0f32;
         let mut reverb_occlusion = 0f32;
 
@@ -152,28 +194,40 @@ impl ChannelGroup {
                                                              &mut direct_occlusion,
                                                              &mut reverb_occlusion) } {
             ::Status::Ok => Ok((direct_occlusion, reverb_occlusion)),
-            e => Err(e)
+            e => Err(::Error::new(e, "FMOD_ChannelGroup_Get3DOcclusion"))
         }
     }
 
-    pub fn stop(&self) -> ::Status {
-        unsafe { ffi::FMOD_ChannelGroup_Stop(self.channel_group) }
+    pub fn stop(&self) -> Result<(), ::Error> {
+        match unsafe { ffi::FMOD_ChannelGroup_Stop(self.channel_group) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_ChannelGroup_Stop"))
+        }
     }
 
-    pub fn override_volume(&self, volume: f32) -> ::Status {
-        unsafe { ffi::FMOD_ChannelGroup_OverrideVolume(self.channel_group, volume) }
+    pub fn override_volume(&self, volume: f32) -> Result<(), ::Error> {
+        match unsafe { ffi::FMOD_ChannelGroup_OverrideVolume(self.channel_group, volume) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_ChannelGroup_OverrideVolume"))
+        }
     }
 
-    pub fn override_frequency(&self, frequency: f32) -> ::Status {
-        unsafe { ffi::FMOD_ChannelGroup_OverrideFrequency(self.channel_group, frequency) }
+    pub fn override_frequency(&self, frequency: f32) -> Result<(), ::Error> {
+        match unsafe { ffi::FMOD_ChannelGroup_OverrideFrequency(self.channel_group, frequency) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_ChannelGroup_OverrideFrequency"))
+        }
     }
 
-    pub fn override_pan(&self, pan: f32) -> ::Status {
-        unsafe { ffi::FMOD_ChannelGroup_OverridePan(self.channel_group, pan) }
+    pub fn override_pan(&self, pan: f32) -> Result<(), ::Error> {
+        match unsafe { ffi::FMOD_ChannelGroup_OverridePan(self.channel_group, pan) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_ChannelGroup_OverridePan"))
+        }
     }
 
     pub fn override_reverb_properties(&self, properties: &channel::ReverbChannelProperties)
-                                      -> ::Status {
+                                      -> Result<(), ::Error> {
         let prop = ffi::FMOD_REVERB_CHANNELPROPERTIES{
             Direct: properties.direct,
             Room: properties.room,
@@ -181,77 +235,93 @@ impl ChannelGroup {
             ConnectionPoint: ffi::FFI::unwrap(&properties.connection_point)
         };
 
-        unsafe { ffi::FMOD_ChannelGroup_OverrideReverbProperties(self.channel_group, &prop) }
+        match unsafe { ffi::FMOD_ChannelGroup_OverrideReverbProperties(self.channel_group,
+                                                                       &prop) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_ChannelGroup_OverrideReverbProperties"))
+        }
     }
 
-    pub fn override_3D_attributes(&self, pos: &vector::Vector, vel: &vector::Vector) -> ::Status {
+    pub fn override_3D_attributes(&self, pos: &vector::Vector,
+                                  vel: &vector::Vector) -> Result<(), ::Error> {
         let mut t_pos = vector::get_ffi(pos);
         let mut t_vel = vector::get_ffi(vel);
 
-        unsafe { ffi::FMOD_ChannelGroup_Override3DAttributes(self.channel_group, &mut t_pos,
-                                                             &mut t_vel) }
+        match unsafe { ffi::FMOD_ChannelGroup_Override3DAttributes(self.channel_group, &mut t_pos,
+                                                                   &mut t_vel) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_ChannelGroup_Override3DAttributes"))
+        }
     }
 
     pub fn override_speaker_mix(&self, front_left: f32, front_right: f32, center: f32, lfe: f32,
                                 back_left: f32, back_right: f32, side_left: f32,
-                                side_right: f32) -> ::Status {
-        unsafe { ffi::FMOD_ChannelGroup_OverrideSpeakerMix(self.channel_group, front_left,
-                                                           front_right, center, lfe, back_left,
-                                                           back_right, side_left, side_right) }
+                                side_right: f32) -> Result<(), ::Error> {
+        match unsafe { ffi::FMOD_ChannelGroup_OverrideSpeakerMix(self.channel_group, front_left,
+                                                                 front_right, center, lfe,
+                                                                 back_left, back_right, side_left,
+                                                                 side_right) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_ChannelGroup_OverrideSpeakerMix"))
+        }
     }
 
-    pub fn add_group(&self, group: &ChannelGroup) -> ::Status {
-        unsafe { ffi::FMOD_ChannelGroup_AddGroup(self.channel_group, group.channel_group) }
+    pub fn add_group(&self, group: &ChannelGroup) -> Result<(), ::Error> {
+        match unsafe { ffi::FMOD_ChannelGroup_AddGroup(self.channel_group, group.channel_group) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_ChannelGroup_AddGroup"))
+        }
     }
 
-    pub fn get_num_groups(&self) -> Result<i32, ::Status> {
+    pub fn get_num_groups(&self) -> Result<i32, ::Error> {
         let mut index = 0i32;
 
         match unsafe { ffi::FMOD_ChannelGroup_GetNumGroups(self.channel_group, &mut index) } {
             ::Status::Ok => Ok(index),
-            e => Err(e)
+            e => Err(::Error::new(e, "FMOD_ChannelGroup_GetNumGroups"))
         }
     }
 
-    pub fn get_group(&self, index: i32) -> Result<ChannelGroup, ::Status> {
+    pub fn get_group(&self, index: i32) -> Result<Borrowed<'_, ChannelGroup>, ::Error> {
         let mut group = ::std::ptr::null_mut();
 
         match unsafe { ffi::FMOD_ChannelGroup_GetGroup(self.channel_group, index, &mut group) } {
-            ::Status::Ok => Ok(ChannelGroup{channel_group: group}),
-            e => Err(e)
+            ::Status::Ok => Ok(borrowed::wrap(group)),
+            e => Err(::Error::new(e, "FMOD_ChannelGroup_GetGroup"))
         }
     }
 
-    pub fn get_parent_group(&self) -> Result<ChannelGroup, ::Status> {
+    pub fn get_parent_group(&self) -> Result<Borrowed<'_, ChannelGroup>, ::Error> {
         let mut parent_group = ::std::ptr::null_mut();
 
         match unsafe { ffi::FMOD_ChannelGroup_GetParentGroup(self.channel_group,
                                                              &mut parent_group) } {
-            ::Status::Ok => Ok(ChannelGroup{channel_group: parent_group}),
-            e => Err(e)
+            ::Status::Ok => Ok(borrowed::wrap(parent_group)),
+            e => Err(::Error::new(e, "FMOD_ChannelGroup_GetParentGroup"))
         }
     }
 
-    pub fn get_DSP_head(&self) -> Result<dsp::Dsp, ::Status> {
+    pub fn get_DSP_head(&self) -> Result<Borrowed<'_, dsp::Dsp>, ::Error> {
         let mut dsp = ::std::ptr::null_mut();
 
         match unsafe { ffi::FMOD_ChannelGroup_GetDSPHead(self.channel_group, &mut dsp) } {
-            ::Status::Ok => Ok(ffi::FFI::wrap(dsp)),
-            e => Err(e)
+            ::Status::Ok => Ok(borrowed::wrap(dsp)),
+            e => Err(::Error::new(e, "FMOD_ChannelGroup_GetDSPHead"))
         }
     }
 
-    pub fn add_DSP(&self, dsp: &dsp::Dsp) -> Result<dsp_connection::DspConnection, ::Status> {
+    pub fn add_DSP(&self, dsp: &dsp::Dsp) -> Result<dsp_connection::DspConnection, ::Error> {
+        let owner = fmod_sys::get_owner(&*self.get_system_object()?);
         let mut dsp_connection = ::std::ptr::null_mut();
 
         match unsafe { ffi::FMOD_ChannelGroup_AddDSP(self.channel_group, ffi::FFI::unwrap(dsp),
                                                      &mut dsp_connection) } {
-            ::Status::Ok => Ok(ffi::FFI::wrap(dsp_connection)),
-            e => Err(e)
+            ::Status::Ok => Ok(dsp_connection::from_ptr(dsp_connection, owner)),
+            e => Err(::Error::new(e, "FMOD_ChannelGroup_AddDSP"))
         }
     }
 
-    pub fn get_name(&self, name_len: usize) -> Result<String, ::Status> {
+    pub fn get_name(&self, name_len: usize) -> Result<String, ::Error> {
         let mut c = Vec::with_capacity(name_len + 1);
 
         for _ in 0..(name_len + 1) {
@@ -262,32 +332,33 @@ impl ChannelGroup {
                                                       c.as_mut_ptr() as *mut c_char,
                                                       name_len as i32) } {
             ::Status::Ok => Ok(String::from_utf8(c).unwrap()),
-            e => Err(e)
+            e => Err(::Error::new(e, "FMOD_ChannelGroup_GetName"))
         }
     }
 
-    pub fn get_num_channels(&self) -> Result<u32, ::Status> {
+    pub fn get_num_channels(&self) -> Result<u32, ::Error> {
         let mut num_channels = 0i32;
 
         match unsafe { ffi::FMOD_ChannelGroup_GetNumChannels(self.channel_group,
                                                              &mut num_channels) } {
             ::Status::Ok => Ok(num_channels as u32),
-            e => Err(e)
+            e => Err(::Error::new(e, "FMOD_ChannelGroup_GetNumChannels"))
         }
     }
 
-    pub fn get_channel(&self, index: i32) -> Result<channel::Channel, ::Status> {
+    pub fn get_channel(&self, index: i32) -> Result<channel::Channel, ::Error> {
+        let owner = fmod_sys::get_owner(&*self.get_system_object()?);
         let mut channel = ::std::ptr::null_mut();
 
         match unsafe { ffi::FMOD_ChannelGroup_GetChannel(self.channel_group, index,
                                                          &mut channel) } {
-            ::Status::Ok => Ok(ffi::FFI::wrap(channel)),
-            e => Err(e)
+            ::Status::Ok => Ok(channel::from_ptr(channel, owner)),
+            e => Err(::Error::new(e, "FMOD_ChannelGroup_GetChannel"))
         }
     }
 
     pub fn get_spectrum(&self, spectrum_size: usize, channel_offset: Option<i32>,
-                        window_type: Option<::DspFftWindow>) -> Result<Vec<f32>, ::Status> {
+                        window_type: Option<::DspFftWindow>) -> Result<Vec<f32>, ::Error> {
         let mut ptr : Vec<f32> = ::std::iter::repeat(0f32).take(spectrum_size).collect();
         let c_window_type = match window_type {
             Some(wt) => wt,
@@ -299,24 +370,24 @@ impl ChannelGroup {
                                                           spectrum_size as c_int, c_channel_offset,
                                                           c_window_type) } {
             ::Status::Ok => Ok(ptr),
-            e => Err(e),
+            e => Err(::Error::new(e, "FMOD_ChannelGroup_GetSpectrum")),
         }
     }
 
     pub fn get_wave_data(&self, wave_size: usize,
-                         channel_offset: i32) -> Result<Vec<f32>, ::Status> {
+                         channel_offset: i32) -> Result<Vec<f32>, ::Error> {
         let mut ptr : Vec<f32> = ::std::iter::repeat(0f32).take(wave_size).collect();
 
         match unsafe { ffi::FMOD_ChannelGroup_GetWaveData(self.channel_group, ptr.as_mut_ptr(),
                                                           wave_size as c_int, channel_offset) } {
             ::Status::Ok => Ok(ptr),
-            e => Err(e)
+            e => Err(::Error::new(e, "FMOD_ChannelGroup_GetWaveData"))
         }
     }
 
     pub fn get_memory_info(&self, MemoryBits(memory_bits): MemoryBits,
                            EventMemoryBits(event_memory_bits): EventMemoryBits)
-                           -> Result<(u32, MemoryUsageDetails), ::Status> {
+                           -> Result<(u32, MemoryUsageDetails), ::Error> {
         let mut details = fmod_sys::get_memory_usage_details_ffi(Default::default());
         let mut memory_used = 0u32;
 
@@ -324,25 +395,39 @@ impl ChannelGroup {
                                                             event_memory_bits, &mut memory_used,
                                                             &mut details) } {
             ::Status::Ok => Ok((memory_used, fmod_sys::from_memory_usage_details_ptr(details))),
-            e => Err(e)
+            e => Err(::Error::new(e, "FMOD_ChannelGroup_GetMemoryInfo"))
+        }
+    }
+
+    /// Stores `user_data` in the channel group, replacing and freeing the previous one. It is freed
+    /// when the channel group is released.
+    pub fn set_user_data<T: Any + Send>(&mut self, user_data: T) -> Result<(), ::Error> {
+        let old = self.get_user_data_ptr()?;
+        let data = user_data::into_ptr(user_data);
+
+        match unsafe { ffi::FMOD_ChannelGroup_SetUserData(self.channel_group, data) } {
+            ::Status::Ok => {
+                unsafe { user_data::free(old) };
+                Ok(())
+            }
+            e => {
+                unsafe { user_data::free(data) };
+                Err(::Error::new(e, "FMOD_ChannelGroup_SetUserData"))
+            }
         }
     }
 
-    pub fn set_user_data<'r, T>(&'r self, user_data: &'r mut T) -> ::Status {
-        unsafe { ffi::FMOD_ChannelGroup_SetUserData(self.channel_group, transmute(user_data)) }
+    /// Returns the user data if it is a `T`.
+    pub fn get_user_data<T: Any>(&self) -> Option<&T> {
+        self.get_user_data_ptr().ok().and_then(|data| unsafe { user_data::downcast(data) })
     }
 
-    pub fn get_user_data<'r, T>(&'r self) -> Result<&'r mut T, ::Status> {
-        unsafe {
-            let mut user_data : *mut c_void = ::std::ptr::null_mut();
+    fn get_user_data_ptr(&self) -> Result<*mut c_void, ::Error> {
+        let mut user_data = ::std::ptr::null_mut();
 
-            match ffi::FMOD_ChannelGroup_GetUserData(self.channel_group, &mut user_data) {
-               ::Status::Ok => {
-                    let tmp : &mut T = transmute::<*mut c_void, &mut T>(user_data);
-                    Ok(tmp)
-                },
-                e => Err(e)
-            }
+        match unsafe { ffi::FMOD_ChannelGroup_GetUserData(self.channel_group, &mut user_data) } {
+            ::Status::Ok => Ok(user_data),
+            e => Err(::Error::new(e, "FMOD_ChannelGroup_GetUserData"))
         }
     }
 }
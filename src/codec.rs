@@ -0,0 +1,453 @@
+/*
+* Rust-FMOD - Copyright (c) 2014 Gomez Guillaume.
+*
+* The Original software, FMOD library, is provided by FIRELIGHT TECHNOLOGIES.
+*
+* This software is provided 'as-is', without any express or implied warranty.
+* In no event will the authors be held liable for any damages arising from
+* the use of this software.
+*
+* Permission is granted to anyone to use this software for any purpose,
+* including commercial applications, and to alter it and redistribute it
+* freely, subject to the following restrictions:
+*
+* 1. The origin of this software must not be misrepresented; you must not claim
+*    that you wrote the original software. If you use this software in a product,
+*    an acknowledgment in the product documentation would be appreciated but is
+*    not required.
+*
+* 2. Altered source versions must be plainly marked as such, and must not be
+*    misrepresented as being the original software.
+*
+* 3. This notice may not be removed or altered from any source distribution.
+*/
+
+use ffi;
+use types::{Mode, TimeUnit};
+use libc::{c_char, c_int, c_uint, c_void};
+use std::io::{self, Read, Seek, SeekFrom};
+use std::ffi::CString;
+use std::sync::{Arc, Mutex};
+use std::slice;
+
+/// Trait to implement a codec in Rust, see
+/// [`Sys::register_codec`](struct.Sys.html#method.register_codec).
+///
+/// A codec only recognizes files, decoding is done by the
+/// [`CodecInstance`](trait.CodecInstance.html) it returns for each opened file. Since sounds can
+/// be opened from several FMOD threads at the same time, `open` can be called concurrently.
+pub trait Codec: Send + Sync {
+    /// Name of the codec.
+    fn name(&self) -> &str;
+
+    /// Units that can be given to
+    /// [`CodecInstance::set_position`](trait.CodecInstance.html#tymethod.set_position).
+    fn time_units(&self) -> TimeUnit {
+        ::TIMEUNIT_PCM
+    }
+
+    /// Tells FMOD to open the files as streams by default, for formats which take long to decode
+    /// or use large amounts of memory once decoded.
+    fn default_as_stream(&self) -> bool {
+        false
+    }
+
+    /// Tells that the instances implement
+    /// [`CodecInstance::get_position`](trait.CodecInstance.html#method.get_position),
+    /// otherwise FMOD tracks the position itself.
+    fn has_position(&self) -> bool {
+        false
+    }
+
+    /// Tells that the instances implement
+    /// [`CodecInstance::get_length`](trait.CodecInstance.html#method.get_length).
+    fn has_length(&self) -> bool {
+        false
+    }
+
+    /// Reads the start of `file` and returns a decoder for it, or `Err(Status::Format)` if the
+    /// file isn't in the format of this codec so FMOD can try the next one.
+    fn open(&self, file: &mut CodecFile, mode: Mode) -> Result<Box<dyn CodecInstance>, ::Status>;
+}
+
+/// Decoder of a file opened by a [`Codec`](trait.Codec.html), dropped when the sound is
+/// released.
+pub trait CodecInstance: Send {
+    /// Formats of the sound. A codec for a container format (like FSB) returns one format per
+    /// subsound, other codecs return a single format.
+    fn wave_formats(&self) -> Vec<CodecWaveFormat>;
+
+    /// Decodes data in the format given by [`wave_formats`](#tymethod.wave_formats) into
+    /// `buffer` and returns the number of bytes written. Returns `Err(Status::FileEOF)` once
+    /// the end of the sound has been reached.
+    fn read(&mut self, file: &mut CodecFile, buffer: &mut [u8]) -> Result<usize, ::Status>;
+
+    /// Seeks to `position` of the given subsound. `unit` is one of the units returned by
+    /// [`Codec::time_units`](trait.Codec.html#method.time_units).
+    fn set_position(&mut self, file: &mut CodecFile, sub_sound: i32, position: u32,
+                    unit: TimeUnit) -> ::Status;
+
+    /// Returns the current position, only called if
+    /// [`Codec::has_position`](trait.Codec.html#method.has_position) returns `true`.
+    fn get_position(&mut self, _file: &mut CodecFile, _unit: TimeUnit) -> Result<u32, ::Status> {
+        Err(::Status::Unsupported)
+    }
+
+    /// Returns the length in a unit not given in the wave formats, only called if
+    /// [`Codec::has_length`](trait.Codec.html#method.has_length) returns `true`.
+    fn get_length(&mut self, _file: &mut CodecFile, _unit: TimeUnit) -> Result<u32, ::Status> {
+        Err(::Status::Unsupported)
+    }
+}
+
+/// Format of a sound decoded by a [`CodecInstance`](trait.CodecInstance.html).
+#[derive(Clone)]
+pub struct CodecWaveFormat {
+    /// Name of the sound, truncated to 255 bytes.
+    pub name         : String,
+    /// Format of the decoded data.
+    pub format       : ::SoundFormat,
+    /// Number of channels.
+    pub channels     : i32,
+    /// Default frequency in hz.
+    pub frequency    : i32,
+    /// Length in bytes of the source data.
+    pub length_bytes : u32,
+    /// Length in decoded PCM samples.
+    pub length_pcm   : u32,
+    /// Size in PCM samples of the optimal decoding chunk, reads are done in multiples of it.
+    pub block_align  : i32,
+    /// Loop start in PCM samples.
+    pub loop_start   : i32,
+    /// Loop end in PCM samples.
+    pub loop_end     : i32,
+    /// Mode used by default when the sound is loaded.
+    pub mode         : Mode,
+    /// Speaker channel mask as defined for WAVEFORMATEXTENSIBLE, 0 for natural speaker order.
+    pub channel_mask : u32
+}
+
+impl Default for CodecWaveFormat {
+    fn default() -> CodecWaveFormat {
+        CodecWaveFormat {
+            name: String::new(),
+            format: ::SoundFormat::PCM16,
+            channels: 0,
+            frequency: 0,
+            length_bytes: 0,
+            length_pcm: 0,
+            block_align: 0,
+            loop_start: 0,
+            loop_end: 0,
+            mode: Mode(0),
+            channel_mask: 0
+        }
+    }
+}
+
+fn wave_format_to_c(format: &CodecWaveFormat) -> ffi::FMOD_CODEC_WAVEFORMAT {
+    let mut c_format = ffi::FMOD_CODEC_WAVEFORMAT {
+        name: [0; 256],
+        format: format.format,
+        channels: format.channels,
+        frequency: format.frequency,
+        lengthbytes: format.length_bytes,
+        lengthpcm: format.length_pcm,
+        blockalign: format.block_align,
+        loopstart: format.loop_start,
+        loopend: format.loop_end,
+        mode: format.mode.0,
+        channelmask: format.channel_mask
+    };
+
+    ffi::write_c_chars(&format.name, &mut c_format.name);
+    c_format
+}
+
+/// File being decoded by a codec, read through FMOD file functions.
+pub struct CodecFile<'a> {
+    state: *mut ffi::FMOD_CODEC_STATE,
+    position: &'a mut u32
+}
+
+impl<'a> CodecFile<'a> {
+    /// Size of the file in bytes.
+    pub fn size(&self) -> u32 {
+        unsafe { (*self.state).filesize }
+    }
+
+    /// Sends a tag to FMOD, it can then be read with
+    /// [`Sound::get_tag`](struct.Sound.html#method.get_tag).
+    pub fn add_tag(&mut self, tag_type: ::TagType, name: &str, data: &[u8],
+                   data_type: ::TagDataType, unique: bool) -> ::Status {
+        let metadata = match unsafe { (*self.state).metadata } {
+            Some(m) => m,
+            None => return ::Status::Unsupported
+        };
+        let name = match CString::new(name) {
+            Ok(n) => n,
+            Err(_) => return ::Status::InvalidParam
+        };
+
+        metadata(self.state, tag_type, name.as_ptr() as *mut c_char,
+                 data.as_ptr() as *mut c_void, data.len() as c_uint, data_type,
+                 if unique { 1 } else { 0 })
+    }
+}
+
+impl<'a> Read for CodecFile<'a> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = match unsafe { (*self.state).fileread } {
+            Some(r) => r,
+            None => return Err(io::Error::new(io::ErrorKind::Other, "no FMOD file to read"))
+        };
+        let mut bytes_read = 0u32;
+
+        match read(unsafe { (*self.state).filehandle }, buf.as_mut_ptr() as *mut c_void,
+                   buf.len() as c_uint, &mut bytes_read, ::std::ptr::null_mut()) {
+            ::Status::Ok | ::Status::FileEOF => {
+                *self.position += bytes_read;
+                Ok(bytes_read as usize)
+            }
+            e => Err(::Error::new(e, "FMOD_FILE_READCALLBACK").into())
+        }
+    }
+}
+
+impl<'a> Seek for CodecFile<'a> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let seek = match unsafe { (*self.state).fileseek } {
+            Some(s) => s,
+            None => return Err(io::Error::new(io::ErrorKind::Other, "no FMOD file to seek"))
+        };
+        let new_pos = match pos {
+            SeekFrom::Start(p) => p as i64,
+            SeekFrom::Current(p) => *self.position as i64 + p,
+            SeekFrom::End(p) => self.size() as i64 + p
+        };
+
+        if new_pos < 0 || new_pos > u32::max_value() as i64 {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "invalid seek position"));
+        }
+        match seek(unsafe { (*self.state).filehandle }, new_pos as c_uint,
+                   ::std::ptr::null_mut()) {
+            ::Status::Ok => {
+                *self.position = new_pos as u32;
+                Ok(new_pos as u64)
+            }
+            e => Err(::Error::new(e, "FMOD_FILE_SEEKCALLBACK").into())
+        }
+    }
+}
+
+/* stored as plugin data of the codec state of every opened file */
+struct CodecData {
+    instance: Box<dyn CodecInstance>,
+    /* FMOD reads the formats through the codec state */
+    formats: Vec<ffi::FMOD_CODEC_WAVEFORMAT>,
+    position: u32
+}
+
+fn get_codec_data<'a>(codec_state: *mut ffi::FMOD_CODEC_STATE) -> Option<&'a mut CodecData> {
+    unsafe {
+        if codec_state.is_null() || (*codec_state).plugindata.is_null() {
+            None
+        } else {
+            Some(&mut *((*codec_state).plugindata as *mut CodecData))
+        }
+    }
+}
+
+/* FMOD gives no user data to the codec callbacks, so every registered codec gets its own open
+   callback, picking the codec from its slot */
+const CODEC_SLOTS: usize = 16;
+const NO_CODEC: Option<Arc<dyn Codec>> = None;
+
+static CODECS: Mutex<[Option<Arc<dyn Codec>>; CODEC_SLOTS]> = Mutex::new([NO_CODEC; CODEC_SLOTS]);
+
+extern "C" fn open_callback<const SLOT: usize>(codec_state: *mut ffi::FMOD_CODEC_STATE,
+                                               user_mode: ffi::FMOD_MODE,
+                                               _: *mut ffi::FMOD_CREATESOUNDEXINFO) -> ::Status {
+    let codec = match CODECS.lock() {
+        Ok(codecs) => match codecs[SLOT] {
+            Some(ref c) => c.clone(),
+            None => return ::Status::Format
+        },
+        Err(_) => return ::Status::Internal
+    };
+    let mut position = 0u32;
+    let instance = {
+        let mut file = CodecFile { state: codec_state, position: &mut position };
+
+        match codec.open(&mut file, Mode(user_mode)) {
+            Ok(i) => i,
+            Err(e) => return e
+        }
+    };
+    let formats : Vec<ffi::FMOD_CODEC_WAVEFORMAT> = instance.wave_formats().iter()
+                                                            .map(wave_format_to_c).collect();
+
+    if formats.is_empty() {
+        return ::Status::Format;
+    }
+    let mut data = Box::new(CodecData {
+        instance: instance,
+        formats: formats,
+        position: position
+    });
+
+    unsafe {
+        (*codec_state).numsubsounds = if data.formats.len() > 1 {
+            data.formats.len() as c_int
+        } else {
+            0
+        };
+        (*codec_state).waveformat = data.formats.as_mut_ptr();
+        (*codec_state).plugindata = Box::into_raw(data) as *mut c_void;
+    }
+    ::Status::Ok
+}
+
+static OPEN_CALLBACKS: [ffi::FMOD_CODEC_OPENCALLBACK; CODEC_SLOTS] = [
+    Some(open_callback::<0>), Some(open_callback::<1>), Some(open_callback::<2>),
+    Some(open_callback::<3>), Some(open_callback::<4>), Some(open_callback::<5>),
+    Some(open_callback::<6>), Some(open_callback::<7>), Some(open_callback::<8>),
+    Some(open_callback::<9>), Some(open_callback::<10>), Some(open_callback::<11>),
+    Some(open_callback::<12>), Some(open_callback::<13>), Some(open_callback::<14>),
+    Some(open_callback::<15>)
+];
+
+extern "C" fn close_callback(codec_state: *mut ffi::FMOD_CODEC_STATE) -> ::Status {
+    unsafe {
+        if !codec_state.is_null() && !(*codec_state).plugindata.is_null() {
+            drop(Box::from_raw((*codec_state).plugindata as *mut CodecData));
+            (*codec_state).plugindata = ::std::ptr::null_mut();
+            (*codec_state).waveformat = ::std::ptr::null_mut();
+        }
+    }
+    ::Status::Ok
+}
+
+extern "C" fn read_callback(codec_state: *mut ffi::FMOD_CODEC_STATE, buffer: *mut c_void,
+                            size_bytes: c_uint, bytes_read: *mut c_uint) -> ::Status {
+    let data = match get_codec_data(codec_state) {
+        Some(d) => d,
+        None => return ::Status::Internal
+    };
+    let buffer = unsafe { slice::from_raw_parts_mut(buffer as *mut u8, size_bytes as usize) };
+    let mut file = CodecFile { state: codec_state, position: &mut data.position };
+
+    match data.instance.read(&mut file, buffer) {
+        Ok(read) => {
+            unsafe { *bytes_read = read as c_uint };
+            ::Status::Ok
+        }
+        Err(e) => {
+            unsafe { *bytes_read = 0 };
+            e
+        }
+    }
+}
+
+extern "C" fn get_length_callback(codec_state: *mut ffi::FMOD_CODEC_STATE, length: *mut c_uint,
+                                  length_type: ffi::FMOD_TIMEUNIT) -> ::Status {
+    let data = match get_codec_data(codec_state) {
+        Some(d) => d,
+        None => return ::Status::Internal
+    };
+    let mut file = CodecFile { state: codec_state, position: &mut data.position };
+
+    match data.instance.get_length(&mut file, TimeUnit(length_type)) {
+        Ok(l) => {
+            unsafe { *length = l };
+            ::Status::Ok
+        }
+        Err(e) => e
+    }
+}
+
+extern "C" fn set_position_callback(codec_state: *mut ffi::FMOD_CODEC_STATE, sub_sound: c_int,
+                                    position: c_uint, postype: ffi::FMOD_TIMEUNIT) -> ::Status {
+    let data = match get_codec_data(codec_state) {
+        Some(d) => d,
+        None => return ::Status::Internal
+    };
+    let mut file = CodecFile { state: codec_state, position: &mut data.position };
+
+    data.instance.set_position(&mut file, sub_sound, position, TimeUnit(postype))
+}
+
+extern "C" fn get_position_callback(codec_state: *mut ffi::FMOD_CODEC_STATE,
+                                    position: *mut c_uint,
+                                    postype: ffi::FMOD_TIMEUNIT) -> ::Status {
+    let data = match get_codec_data(codec_state) {
+        Some(d) => d,
+        None => return ::Status::Internal
+    };
+    let mut file = CodecFile { state: codec_state, position: &mut data.position };
+
+    match data.instance.get_position(&mut file, TimeUnit(postype)) {
+        Ok(p) => {
+            unsafe { *position = p };
+            ::Status::Ok
+        }
+        Err(e) => e
+    }
+}
+
+/* keeps the codec name alive as long as FMOD uses the description, which is kept by the system
+   registering it and dropped once it is released */
+pub struct CodecDescription {
+    pub description: ffi::FMOD_CODEC_DESCRIPTION,
+    name: CString,
+    slot: usize
+}
+
+/* puts codec in a free slot and builds the FMOD description calling it */
+pub fn get_codec_description_ffi(codec: Box<dyn Codec>)
+                                 -> Result<Box<CodecDescription>, ::Error> {
+    let TimeUnit(time_units) = codec.time_units();
+    let default_as_stream = codec.default_as_stream();
+    let has_position = codec.has_position();
+    let has_length = codec.has_length();
+    let name = CString::new(codec.name()).unwrap_or(CString::new("").unwrap());
+    let mut codecs = match CODECS.lock() {
+        Ok(c) => c,
+        Err(_) => return Err(::Error::new(::Status::Internal, "Sys::register_codec"))
+    };
+    let slot = match codecs.iter().position(|c| c.is_none()) {
+        Some(s) => s,
+        None => return Err(::Error::new(::Status::PluginInstances, "Sys::register_codec"))
+    };
+    let mut description = Box::new(CodecDescription {
+        description: ffi::FMOD_CODEC_DESCRIPTION {
+            name: ::std::ptr::null_mut(),
+            version: 0,
+            defaultasstream: if default_as_stream { 1 } else { 0 },
+            timeunits: time_units,
+            open: OPEN_CALLBACKS[slot],
+            close: Some(close_callback),
+            read: Some(read_callback),
+            getlength: if has_length { Some(get_length_callback) } else { None },
+            setposition: Some(set_position_callback),
+            getposition: if has_position { Some(get_position_callback) } else { None },
+            soundcreate: None,
+            getwaveformat: None
+        },
+        name: name,
+        slot: slot
+    });
+
+    description.description.name = description.name.as_ptr() as *mut c_char;
+    codecs[slot] = Some(Arc::from(codec));
+    Ok(description)
+}
+
+/* frees the slot of the codec */
+impl Drop for CodecDescription {
+    fn drop(&mut self) {
+        if let Ok(mut codecs) = CODECS.lock() {
+            codecs[self.slot] = None;
+        }
+    }
+}
@@ -0,0 +1,192 @@
+/*
+* Rust-FMOD - Copyright (c) 2014 Gomez Guillaume.
+*
+* The Original software, FMOD library, is provided by FIRELIGHT TECHNOLOGIES.
+*
+* This software is provided 'as-is', without any express or implied warranty.
+* In no event will the authors be held liable for any damages arising from
+* the use of this software.
+*
+* Permission is granted to anyone to use this software for any purpose,
+* including commercial applications, and to alter it and redistribute it
+* freely, subject to the following restrictions:
+*
+* 1. The origin of this software must not be misrepresented; you must not claim
+*    that you wrote the original software. If you use this software in a product,
+*    an acknowledgment in the product documentation would be appreciated but is
+*    not required.
+*
+* 2. Altered source versions must be plainly marked as such, and must not be
+*    misrepresented as being the original software.
+*
+* 3. This notice may not be removed or altered from any source distribution.
+*/
+
+use sound::{Sample, Sound};
+use std::io::{self, Read, Seek, SeekFrom};
+use std::mem;
+use std::slice;
+
+/* number of frames decoded at once by Frames */
+const FRAMES_PER_READ: usize = 1024;
+
+/// Decoder returned by [`Sys::create_decoder`](struct.Sys.html#method.create_decoder), reading
+/// the PCM data of a sound without playing it.
+///
+/// Positions used by `Read` and `Seek` are in bytes of decoded data and seeking has to be done
+/// to the start of a PCM frame.
+pub struct SoundDecoder {
+    sound: Sound,
+    format: ::SoundFormat,
+    channels: i32,
+    frequency: i32,
+    frame_size: u64,
+    length: u64,
+    position: u64
+}
+
+pub fn from_sound(sound: Sound) -> Result<SoundDecoder, ::Error> {
+    let (_, format, channels, bits) = sound.get_format()?;
+    let (frequency, _, _, _) = sound.get_defaults()?;
+
+    match format {
+        ::SoundFormat::PCM8 | ::SoundFormat::PCM16 | ::SoundFormat::PCM24
+        | ::SoundFormat::PCM32 | ::SoundFormat::PCMFloat => {}
+        _ => return Err(::Error::new(::Status::Format, "Sys::create_decoder"))
+    }
+    let length = sound.get_length(::TIMEUNIT_PCMBYTES)?.value() as u64;
+
+    Ok(SoundDecoder {
+        sound: sound,
+        format: format,
+        channels: channels,
+        frequency: frequency as i32,
+        frame_size: (channels * bits / 8) as u64,
+        length: length,
+        position: 0
+    })
+}
+
+impl SoundDecoder {
+    /// Format of the decoded samples.
+    pub fn format(&self) -> ::SoundFormat {
+        self.format
+    }
+
+    /// Number of interleaved channels.
+    pub fn channels(&self) -> i32 {
+        self.channels
+    }
+
+    /// Default frequency of the sound in hz.
+    pub fn frequency(&self) -> i32 {
+        self.frequency
+    }
+
+    /// Length of the sound in PCM frames.
+    pub fn len_frames(&self) -> u64 {
+        self.length / self.frame_size
+    }
+
+    /// Sound being decoded.
+    pub fn sound(&self) -> &Sound {
+        &self.sound
+    }
+
+    /// Returns an iterator over the remaining frames, each frame containing one sample per
+    /// channel. Fails with `Status::Format` if `T` isn't the type of the decoded samples.
+    pub fn frames<T: Sample>(&mut self) -> Result<Frames<'_, T>, ::Error> {
+        if T::format() != self.format {
+            return Err(::Error::new(::Status::Format, "SoundDecoder::frames"));
+        }
+        Ok(Frames {
+            decoder: self,
+            buffer: Vec::new(),
+            offset: 0
+        })
+    }
+}
+
+impl Read for SoundDecoder {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.sound.read_data(buf)?;
+
+        self.position += read as u64;
+        Ok(read)
+    }
+}
+
+impl Seek for SoundDecoder {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let new_pos = match pos {
+            SeekFrom::Start(p) => p as i64,
+            SeekFrom::Current(p) => self.position as i64 + p,
+            SeekFrom::End(p) => self.length as i64 + p
+        };
+
+        if new_pos < 0 || new_pos as u64 % self.frame_size != 0 {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput,
+                                      "seek position is not the start of a PCM frame"));
+        }
+        self.sound.seek_data((new_pos as u64 / self.frame_size) as u32)?;
+        self.position = new_pos as u64;
+        Ok(self.position)
+    }
+}
+
+/// Iterator over the frames of a [`SoundDecoder`](struct.SoundDecoder.html), returned by
+/// [`SoundDecoder::frames`](struct.SoundDecoder.html#method.frames).
+pub struct Frames<'a, T: Sample> {
+    decoder: &'a mut SoundDecoder,
+    buffer: Vec<T>,
+    offset: usize
+}
+
+impl<'a, T: Sample> Frames<'a, T> {
+    fn fill(&mut self) -> Result<(), ::Error> {
+        let channels = self.decoder.channels as usize;
+        let samples = FRAMES_PER_READ * channels;
+
+        self.buffer.clear();
+        self.buffer.resize(samples, T::default());
+        self.offset = 0;
+        let read = {
+            let bytes = unsafe {
+                slice::from_raw_parts_mut(self.buffer.as_mut_ptr() as *mut u8,
+                                          samples * mem::size_of::<T>())
+            };
+
+            self.decoder.sound.read_data(bytes)?
+        };
+        let kept = read / mem::size_of::<T>() / channels * channels;
+
+        self.buffer.truncate(kept);
+        self.decoder.position += (kept * mem::size_of::<T>()) as u64;
+        /* a trailing partial frame is read again by the next call */
+        if kept * mem::size_of::<T>() != read {
+            self.decoder.sound.seek_data((self.decoder.position / self.decoder.frame_size) as u32)?;
+        }
+        Ok(())
+    }
+}
+
+impl<'a, T: Sample> Iterator for Frames<'a, T> {
+    type Item = Result<Vec<T>, ::Error>;
+
+    fn next(&mut self) -> Option<Result<Vec<T>, ::Error>> {
+        let channels = self.decoder.channels as usize;
+
+        if self.offset >= self.buffer.len() {
+            if let Err(e) = self.fill() {
+                return Some(Err(e));
+            }
+            if self.buffer.is_empty() {
+                return None;
+            }
+        }
+        let frame = self.buffer[self.offset..self.offset + channels].to_vec();
+
+        self.offset += channels;
+        Some(Ok(frame))
+    }
+}
@@ -27,13 +27,19 @@ use types::*;
 use callbacks::*;
 use dsp_connection;
 use fmod_sys;
-use fmod_sys::{MemoryUsageDetails, Sys};
+use fmod_sys::{MemoryUsageDetails, Sys, SysHandle};
+use borrowed;
+use borrowed::Borrowed;
 use std::mem::transmute;
 use channel;
 use libc::{c_char, c_void, c_uint, c_int, c_float};
 use std::default::Default;
 use c_vec::CVec;
 use std::ffi::CString;
+use std::slice;
+use std::sync::Arc;
+use std::any::Any;
+use user_data;
 
 extern "C" fn create_callback(dsp_state: *mut ffi::FMOD_DSP_STATE) -> ::Status {
     unsafe {
@@ -223,16 +229,149 @@ extern "C" fn config_callback(dsp_state: *mut ffi::FMOD_DSP_STATE, hwnd: *mut c_
    ::Status::Ok
 }
 
+extern "C" fn processor_release_callback(dsp_state: *mut ffi::FMOD_DSP_STATE) -> ::Status {
+    unsafe {
+        if !dsp_state.is_null() && !(*dsp_state).instance.is_null() {
+            let mut tmp = ::std::ptr::null_mut();
+
+            ffi::FMOD_DSP_GetUserData((*dsp_state).instance, &mut tmp);
+            if !tmp.is_null() {
+                ffi::FMOD_DSP_SetUserData((*dsp_state).instance, ::std::ptr::null_mut());
+                drop(Box::from_raw(tmp as *mut UserData));
+            }
+        }
+        ::Status::Ok
+    }
+}
+
+fn get_processor<'a>(dsp_state: *mut ffi::FMOD_DSP_STATE) -> Option<&'a mut ProcessorState> {
+    unsafe {
+        if !dsp_state.is_null() && !(*dsp_state).instance.is_null() {
+            let mut tmp = ::std::ptr::null_mut();
+
+            ffi::FMOD_DSP_GetUserData((*dsp_state).instance, &mut tmp);
+            if !tmp.is_null() {
+                let user_data : &mut UserData = transmute(tmp);
+
+                user_data.processor.as_mut()
+            } else {
+                None
+            }
+        } else {
+            None
+        }
+    }
+}
+
+extern "C" fn processor_reset_callback(dsp_state: *mut ffi::FMOD_DSP_STATE) -> ::Status {
+    match get_processor(dsp_state) {
+        Some(p) => p.processor.reset(),
+        None => ::Status::Ok
+    }
+}
+
+extern "C" fn processor_read_callback(dsp_state: *mut ffi::FMOD_DSP_STATE, in_buffer: *mut c_float,
+                                      out_buffer: *mut c_float, length: c_uint, in_channels: c_int,
+                                      out_channels: c_int) -> ::Status {
+    match get_processor(dsp_state) {
+        Some(p) => {
+            let (input, output) = unsafe {
+                (slice::from_raw_parts(in_buffer, length as usize * in_channels as usize),
+                 slice::from_raw_parts_mut(out_buffer, length as usize * out_channels as usize))
+            };
+
+            p.processor.process(input, output, length as u32, in_channels as i32,
+                                out_channels as i32)
+        }
+        None => ::Status::Ok
+    }
+}
+
+extern "C" fn processor_set_parameter_callback(dsp_state: *mut ffi::FMOD_DSP_STATE, index: c_int,
+                                               value: c_float) -> ::Status {
+    match get_processor(dsp_state) {
+        Some(p) => p.processor.set_parameter(index as i32, value),
+        None => ::Status::Ok
+    }
+}
+
+extern "C" fn processor_get_parameter_callback(dsp_state: *mut ffi::FMOD_DSP_STATE, index: c_int,
+                                               value: *mut c_float,
+                                               value_str: *mut c_char) -> ::Status {
+    match get_processor(dsp_state) {
+        Some(p) => match p.processor.get_parameter(index as i32) {
+            Ok((v, text)) => {
+                unsafe {
+                    if !value.is_null() {
+                        *value = v;
+                    }
+                    if !value_str.is_null() {
+                        ffi::write_c_chars(&text, slice::from_raw_parts_mut(value_str,
+                                                                       PARAMETER_VALUE_STR_LEN));
+                    }
+                }
+                ::Status::Ok
+            }
+            Err(e) => e
+        },
+        None => ::Status::Ok
+    }
+}
+
+/* FMOD_DSP_GETPARAM_VALUESTR_LENGTH */
+const PARAMETER_VALUE_STR_LEN: usize = 16;
+
+/// Trait to implement DSP units in Rust, see
+/// [`Sys::create_DSP_from_processor`](../struct.Sys.html#method.create_DSP_from_processor).
+///
+/// The processor is owned by the DSP unit it is given to and is dropped when the unit is released.
+/// Its methods are called from FMOD's mixer thread.
+pub trait DspProcessor: Send {
+    /// Processes `length` samples. Both buffers are interleaved, `input` has `in_channels`
+    /// channels and `output` has `out_channels` channels.
+    fn process(&mut self, input: &[f32], output: &mut [f32], length: u32, in_channels: i32,
+               out_channels: i32) -> ::Status;
+
+    /// Called by [`Dsp::set_parameter`](struct.Dsp.html#method.set_parameter). `index` is the
+    /// position of the parameter in the table given when creating the unit.
+    fn set_parameter(&mut self, _index: i32, _value: f32) -> ::Status {
+        ::Status::InvalidParam
+    }
+
+    /// Called by [`Dsp::get_parameter`](struct.Dsp.html#method.get_parameter). Returns the value
+    /// of the parameter and its text representation, which is truncated to 15 bytes.
+    fn get_parameter(&mut self, _index: i32) -> Result<(f32, String), ::Status> {
+        Err(::Status::InvalidParam)
+    }
+
+    /// Called by [`Dsp::reset`](struct.Dsp.html#method.reset) to clear any history buffer.
+    fn reset(&mut self) -> ::Status {
+        ::Status::Ok
+    }
+}
+
+struct ProcessorState {
+    processor: Box<dyn DspProcessor>,
+    /* FMOD keeps pointers to the parameter table, so it lives as long as the unit */
+    parameters: Vec<ffi::FMOD_DSP_PARAMETERDESC>,
+    descriptions: Vec<CString>,
+}
+
 struct UserData {
     callbacks: DspCallbacks,
-    user_data: *mut c_void,
+    user_data: Option<user_data::UserData>,
+    processor: Option<ProcessorState>,
+    /* allocated by set_user_data, freed when the DSP is released */
+    allocated: bool,
 }
 
 impl UserData {
     fn new() -> UserData {
         UserData {
             callbacks: DspCallbacks::new(),
-            user_data: ::std::ptr::null_mut(),
+            user_data: None,
+            processor: None,
+            allocated: false,
         }
     }
 }
@@ -508,6 +647,70 @@ pub fn get_description_ffi(dsp_description: &mut DspDescription) -> ffi::FMOD_DS
     }
 }
 
+pub fn get_processor_description_ffi(name: &str, parameters: &[DspParameterDesc],
+                                     processor: Box<dyn DspProcessor>)
+                                     -> ffi::FMOD_DSP_DESCRIPTION {
+    let descriptions : Vec<CString> = parameters.iter().map(|p| {
+        CString::new(p.description.clone()).unwrap_or(CString::new("").unwrap())
+    }).collect();
+    let mut c_parameters : Vec<ffi::FMOD_DSP_PARAMETERDESC> =
+        parameters.iter().zip(descriptions.iter()).map(|(p, d)| {
+            let mut c_param = ffi::FMOD_DSP_PARAMETERDESC {
+                min: p.min,
+                max: p.max,
+                default_val: p.default_val,
+                name: [0; 16],
+                label: [0; 16],
+                description: d.as_ptr(),
+            };
+
+            ffi::write_c_chars(&p.name, &mut c_param.name);
+            ffi::write_c_chars(&p.label, &mut c_param.label);
+            c_param
+        }).collect();
+    let param_desc = if c_parameters.is_empty() {
+        ::std::ptr::null_mut()
+    } else {
+        c_parameters.as_mut_ptr()
+    };
+    let mut user_data = Box::new(UserData::new());
+    let mut c_name = [0; 32];
+
+    ffi::write_c_chars(name, &mut c_name);
+    user_data.processor = Some(ProcessorState {
+        processor: processor,
+        parameters: c_parameters,
+        descriptions: descriptions,
+    });
+    ffi::FMOD_DSP_DESCRIPTION {
+        name: c_name,
+        version: 0,
+        channels: 0,
+        create: None,
+        release: Some(processor_release_callback as extern "C" fn(*mut _) -> _),
+        reset: Some(processor_reset_callback as extern "C" fn(*mut _) -> _),
+        read: Some(processor_read_callback as extern "C" fn(*mut _, *mut _, *mut _, _, _, _) -> _),
+        set_position: None,
+        num_parameters: parameters.len() as c_int,
+        param_desc: param_desc,
+        set_parameter: Some(processor_set_parameter_callback as extern "C" fn(*mut _, _, _) -> _),
+        get_parameter: Some(processor_get_parameter_callback
+                            as extern "C" fn(*mut _, _, *mut _, *mut _) -> _),
+        config: None,
+        config_width: 0,
+        config_height: 0,
+        user_data: Box::into_raw(user_data) as *mut c_void,
+    }
+}
+
+/* frees the processor of a description FMOD refused to create a unit from */
+pub fn release_processor_description(description: &mut ffi::FMOD_DSP_DESCRIPTION) {
+    if !description.user_data.is_null() {
+        unsafe { drop(Box::from_raw(description.user_data as *mut UserData)) };
+        description.user_data = ::std::ptr::null_mut();
+    }
+}
+
 pub fn get_state_ffi(state: &DspState) -> ffi::FMOD_DSP_STATE {
     ffi::FMOD_DSP_STATE {
         instance: ffi::FFI::unwrap(&state.instance),
@@ -535,33 +738,25 @@ pub struct DspState {
     pub speaker_mask: u16,
 }
 
-pub fn from_ptr_first(dsp: *mut ffi::FMOD_DSP) -> Dsp {
+pub fn from_ptr_first(dsp: *mut ffi::FMOD_DSP, owner: Option<Arc<SysHandle>>) -> Dsp {
     Dsp {
         dsp: dsp,
-        can_be_deleted: true,
-        user_data: UserData {
-            callbacks: DspCallbacks::new(),
-            user_data: ::std::ptr::null_mut()
-        }
+        owner: owner
     }
 }
 
 /// Dsp object
 pub struct Dsp {
     dsp: *mut ffi::FMOD_DSP,
-    can_be_deleted: bool,
-    user_data: UserData
+    /* keeps the system alive, only set on the DSPs releasing their FMOD DSP */
+    owner: Option<Arc<SysHandle>>
 }
 
 impl ffi::FFI<ffi::FMOD_DSP> for Dsp {
     fn wrap(dsp: *mut ffi::FMOD_DSP) -> Dsp {
         Dsp {
             dsp: dsp,
-            can_be_deleted: false,
-            user_data: UserData {
-                callbacks: DspCallbacks::new(),
-                user_data: ::std::ptr::null_mut()
-            }
+            owner: None
         }
     }
 
@@ -572,79 +767,86 @@ impl ffi::FFI<ffi::FMOD_DSP> for Dsp {
 
 impl Drop for Dsp {
     fn drop(&mut self) {
-        self.release();
+        let _ = self.release();
     }
 }
 
 impl Dsp {
-    pub fn get_system_object(&self) -> Result<Sys, ::Status> {
+    pub fn get_system_object(&self) -> Result<Borrowed<'_, Sys>, ::Error> {
         let mut system = ::std::ptr::null_mut();
 
         match unsafe { ffi::FMOD_DSP_GetSystemObject(self.dsp, &mut system) } {
-            ::Status::Ok => Ok(ffi::FFI::wrap(system)),
-            e => Err(e)
+            ::Status::Ok => Ok(borrowed::wrap(system)),
+            e => Err(::Error::new(e, "FMOD_DSP_GetSystemObject"))
         }
     }
 
-    pub fn release(&mut self) -> ::Status {
-        if self.can_be_deleted && !self.dsp.is_null() {
+    /// Releases the DSP if it was created by this object, borrowed DSPs are left untouched.
+    pub fn release(&mut self) -> Result<(), ::Error> {
+        if self.owner.is_some() && !self.dsp.is_null() {
+            /* the processor DSPs free their user data while being released */
+            let allocated = match self.get_user_data_ptr() {
+                Ok(data) if !data.is_null() && unsafe { (*data).allocated } => data,
+                _ => ::std::ptr::null_mut()
+            };
+
             match unsafe { ffi::FMOD_DSP_Release(self.dsp) } {
                ::Status::Ok => {
+                    if !allocated.is_null() {
+                        unsafe { drop(Box::from_raw(allocated)) };
+                    }
                     self.dsp =::std::ptr::null_mut();
-                   ::Status::Ok
+                    self.owner = None;
+                   Ok(())
                 }
-                e => e
+                e => Err(::Error::new(e, "FMOD_DSP_Release"))
             }
         } else {
-           ::Status::Ok
+           Ok(())
         }
     }
 
-    pub fn play(&self) -> Result<channel::Channel, ::Status> {
+    pub fn play(&self) -> Result<channel::Channel, ::Error> {
+        let s = self.get_system_object()?;
         let mut channel = ::std::ptr::null_mut();
 
-        match match self.get_system_object() {
-            Ok(s) => { 
-                unsafe { ffi::FMOD_System_PlayDSP(ffi::FFI::unwrap(&s), ::ChannelIndex::Free,
-                                                  self.dsp, 0, &mut channel) }
-            }
-            Err(e) => e
-        } {
-            ::Status::Ok => Ok(ffi::FFI::wrap(channel)),
-            e => Err(e)
+        match unsafe { ffi::FMOD_System_PlayDSP(ffi::FFI::unwrap(&*s), ::ChannelIndex::Free,
+                                                self.dsp, 0, &mut channel) } {
+            ::Status::Ok => Ok(channel::from_ptr(channel, fmod_sys::get_owner(&s))),
+            e => Err(::Error::new(e, "FMOD_System_PlayDSP"))
         }
     }
 
     pub fn play_with_parameters(&self, channel_id: ::ChannelIndex)
-                                -> Result<channel::Channel, ::Status> {
+                                -> Result<channel::Channel, ::Error> {
+        let s = self.get_system_object()?;
         let mut channel = ::std::ptr::null_mut();
-        
-        match match self.get_system_object() {
-            Ok(s) => { 
-                unsafe { ffi::FMOD_System_PlayDSP(ffi::FFI::unwrap(&s), channel_id, self.dsp, 0,
-                                                  &mut channel) }
-            }
-            Err(e) => e
-        } {
-            ::Status::Ok => Ok(ffi::FFI::wrap(channel)),
-            e => Err(e)
+
+        match unsafe { ffi::FMOD_System_PlayDSP(ffi::FFI::unwrap(&*s), channel_id, self.dsp, 0,
+                                                &mut channel) } {
+            ::Status::Ok => Ok(channel::from_ptr(channel, fmod_sys::get_owner(&s))),
+            e => Err(::Error::new(e, "FMOD_System_PlayDSP"))
         }
     }
 
-    pub fn add_input(&self, target: Dsp) -> Result<dsp_connection::DspConnection, ::Status> {
+    pub fn add_input(&self, target: &Dsp) -> Result<dsp_connection::DspConnection, ::Error> {
+        let owner = fmod_sys::get_owner(&*self.get_system_object()?);
         let mut connection = ::std::ptr::null_mut();
 
         match unsafe { ffi::FMOD_DSP_AddInput(self.dsp, target.dsp, &mut connection) } {
-            ::Status::Ok => Ok(ffi::FFI::wrap(connection)),
-            e => Err(e)
+            ::Status::Ok => Ok(dsp_connection::from_ptr(connection, owner)),
+            e => Err(::Error::new(e, "FMOD_DSP_AddInput"))
         }
     }
 
-    pub fn disconnect_from(&self, target: Dsp) -> ::Status {
-        unsafe { ffi::FMOD_DSP_DisconnectFrom(self.dsp, target.dsp) }
+    pub fn disconnect_from(&self, target: &Dsp) -> Result<(), ::Error> {
+        match unsafe { ffi::FMOD_DSP_DisconnectFrom(self.dsp, target.dsp) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_DSP_DisconnectFrom"))
+        }
     }
 
-    pub fn disconnect_all(&self, inputs: bool, outputs: bool) -> ::Status {
+    pub fn disconnect_all(&self, inputs: bool, outputs: bool) -> Result<(), ::Error> {
         let t_inputs = if inputs == true {
             1
         } else {
@@ -656,112 +858,136 @@ impl Dsp {
             0
         };
 
-        unsafe { ffi::FMOD_DSP_DisconnectAll(self.dsp, t_inputs, t_outputs) }
+        match unsafe { ffi::FMOD_DSP_DisconnectAll(self.dsp, t_inputs, t_outputs) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_DSP_DisconnectAll"))
+        }
     }
 
-    pub fn remove(&self) -> ::Status {
-        unsafe { ffi::FMOD_DSP_Remove(self.dsp) }
+    pub fn remove(&self) -> Result<(), ::Error> {
+        match unsafe { ffi::FMOD_DSP_Remove(self.dsp) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_DSP_Remove"))
+        }
     }
 
-    pub fn get_num_inputs(&self) -> Result<i32, ::Status> {
+    pub fn get_num_inputs(&self) -> Result<i32, ::Error> {
         let mut inputs = 0i32;
 
         match unsafe { ffi::FMOD_DSP_GetNumInputs(self.dsp, &mut inputs) } {
             ::Status::Ok => Ok(inputs),
-            e => Err(e)
+            e => Err(::Error::new(e, "FMOD_DSP_GetNumInputs"))
         }
     }
 
-    pub fn get_num_outputs(&self) -> Result<i32, ::Status> {
+    pub fn get_num_outputs(&self) -> Result<i32, ::Error> {
         let mut outputs = 0i32;
 
         match unsafe { ffi::FMOD_DSP_GetNumOutputs(self.dsp, &mut outputs) } {
             ::Status::Ok => Ok(outputs),
-            e => Err(e)
+            e => Err(::Error::new(e, "FMOD_DSP_GetNumOutputs"))
         }
     }
 
-    pub fn get_input(&self, index: i32) -> Result<(Dsp, dsp_connection::DspConnection), ::Status> {
+    pub fn get_input(&self, index: i32)
+                     -> Result<(Borrowed<'_, Dsp>, dsp_connection::DspConnection), ::Error> {
+        let owner = fmod_sys::get_owner(&*self.get_system_object()?);
         let mut input = ::std::ptr::null_mut();
         let mut input_connection = ::std::ptr::null_mut();
 
         match unsafe { ffi::FMOD_DSP_GetInput(self.dsp, index, &mut input,
                                               &mut input_connection) } {
-            ::Status::Ok => Ok((ffi::FFI::wrap(input), ffi::FFI::wrap(input_connection))),
-            e => Err(e)
+            ::Status::Ok => Ok((borrowed::wrap(input),
+                                dsp_connection::from_ptr(input_connection, owner))),
+            e => Err(::Error::new(e, "FMOD_DSP_GetInput"))
         }
     }
 
-    pub fn get_output(&self, index: i32) -> Result<(Dsp, dsp_connection::DspConnection), ::Status> {
+    pub fn get_output(&self, index: i32)
+                      -> Result<(Borrowed<'_, Dsp>, dsp_connection::DspConnection), ::Error> {
+        let owner = fmod_sys::get_owner(&*self.get_system_object()?);
         let mut output = ::std::ptr::null_mut();
         let mut output_connection = ::std::ptr::null_mut();
 
         match unsafe { ffi::FMOD_DSP_GetOutput(self.dsp, index, &mut output,
                                                &mut output_connection) } {
-            ::Status::Ok => Ok((ffi::FFI::wrap(output), ffi::FFI::wrap(output_connection ))),
-            e => Err(e)
+            ::Status::Ok => Ok((borrowed::wrap(output),
+                                dsp_connection::from_ptr(output_connection, owner))),
+            e => Err(::Error::new(e, "FMOD_DSP_GetOutput"))
         }
     }
 
-    pub fn set_active(&self, active: bool) -> ::Status {
+    pub fn set_active(&self, active: bool) -> Result<(), ::Error> {
         let t_active = if active == true {
             1
         } else {
             0
         };
 
-        unsafe { ffi::FMOD_DSP_SetActive(self.dsp, t_active) }
+        match unsafe { ffi::FMOD_DSP_SetActive(self.dsp, t_active) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_DSP_SetActive"))
+        }
     }
 
-    pub fn get_active(&self) -> Result<bool, ::Status> {
+    pub fn get_active(&self) -> Result<bool, ::Error> {
         let mut active = 0i32;
 
         match unsafe { ffi::FMOD_DSP_GetActive(self.dsp, &mut active) } {
             ::Status::Ok => Ok(active != 0i32),
-            e => Err(e)
+            e => Err(::Error::new(e, "FMOD_DSP_GetActive"))
         }
     }
 
-    pub fn set_bypass(&self, bypass: bool) -> ::Status {
+    pub fn set_bypass(&self, bypass: bool) -> Result<(), ::Error> {
         let t_bypass = if bypass == true {
             1i32
         } else {
             0i32
         };
 
-        unsafe { ffi::FMOD_DSP_SetBypass(self.dsp, t_bypass) }
+        match unsafe { ffi::FMOD_DSP_SetBypass(self.dsp, t_bypass) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_DSP_SetBypass"))
+        }
     }
 
-    pub fn get_bypass(&self) -> Result<bool, ::Status> {
+    pub fn get_bypass(&self) -> Result<bool, ::Error> {
         let mut bypass = 0i32;
 
         match unsafe { ffi::FMOD_DSP_GetBypass(self.dsp, &mut bypass) } {
             ::Status::Ok => Ok(bypass == 1i32),
-            e => Err(e)
+            e => Err(::Error::new(e, "FMOD_DSP_GetBypass"))
         }
     }
 
-    pub fn set_speaker_active(&self, speaker: ::Speaker, active: bool) -> ::Status {
+    pub fn set_speaker_active(&self, speaker: ::Speaker, active: bool) -> Result<(), ::Error> {
         let t_active = if active == true {
             1
         } else {
             0
         };
 
-        unsafe { ffi::FMOD_DSP_SetSpeakerActive(self.dsp, speaker, t_active) }
+        match unsafe { ffi::FMOD_DSP_SetSpeakerActive(self.dsp, speaker, t_active) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_DSP_SetSpeakerActive"))
+        }
     }
 
-    pub fn get_speaker_active(&self, speaker: ::Speaker) -> Result<bool, ::Status> {
+    pub fn get_speaker_active(&self, speaker: ::Speaker) -> Result<bool, ::Error> {
         let mut active = 0i32;
 
         match unsafe { ffi::FMOD_DSP_GetSpeakerActive(self.dsp, speaker, &mut active) } {
             ::Status::Ok => Ok(active == 1i32),
-            e => Err(e)
+            e => Err(::Error::new(e, "FMOD_DSP_GetSpeakerActive"))
         }
     }
 
-    pub fn reset(&self) -> ::Status {
-        unsafe { ffi::FMOD_DSP_Reset(self.dsp) }
+    pub fn reset(&self) -> Result<(), ::Error> {
+        match unsafe { ffi::FMOD_DSP_Reset(self.dsp) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_DSP_Reset"))
+        }
     }
 
     /// value argument depends directly on the index argument,
@@ -786,8 +1012,11 @@ impl Dsp {
     /// * [`DspSfxReverb`](enums/fmod/type.DspSfxReverb.html)
     /// * [`DspLowPassSimple`](enums/fmod/type.DspLowPassSimple.html)
     /// * [`DspHighPassSimple`](enums/fmod/type.DspHighPassSimple.html)
-    pub fn set_parameter(&self, index: i32, value: f32) -> ::Status {
-        unsafe { ffi::FMOD_DSP_SetParameter(self.dsp, index, value) }
+    pub fn set_parameter(&self, index: i32, value: f32) -> Result<(), ::Error> {
+        match unsafe { ffi::FMOD_DSP_SetParameter(self.dsp, index, value) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_DSP_SetParameter"))
+        }
     }
 
     /// value result depends directly on the index argument,
@@ -813,7 +1042,7 @@ impl Dsp {
     /// * [`DspLowPassSimple`](enums/fmod/type.DspLowPassSimple.html)
     /// * [`DspHighPassSimple`](enums/fmod/type.DspHighPassSimple.html)
     pub fn get_parameter(&self, index: i32, value_str_len: usize)
-                        -> Result<(f32, String), ::Status> {
+                        -> Result<(f32, String), ::Error> {
         let mut value = 0f32;
         let mut c = Vec::with_capacity(value_str_len + 1);
 
@@ -825,21 +1054,21 @@ impl Dsp {
                                                   c.as_mut_ptr() as *mut c_char,
                                                   value_str_len as i32) } {
            ::Status::Ok => Ok((value, String::from_utf8(c).unwrap())),
-            e => Err(e)
+            e => Err(::Error::new(e, "FMOD_DSP_GetParameter"))
         }
     }
 
-    pub fn get_num_parameters(&self) -> Result<i32, ::Status> {
+    pub fn get_num_parameters(&self) -> Result<i32, ::Error> {
         let mut num_param = 0i32;
 
         match unsafe { ffi::FMOD_DSP_GetNumParameters(self.dsp, &mut num_param) } {
             ::Status::Ok => Ok(num_param),
-            e => Err(e)
+            e => Err(::Error::new(e, "FMOD_DSP_GetNumParameters"))
         }
     }
 
     pub fn get_parameter_info(&self, index: i32, name: &str, label: &str,
-                              description_len: usize) -> Result<(String, f32, f32), ::Status> {
+                              description_len: usize) -> Result<(String, f32, f32), ::Error> {
         let mut min = 0f32;
         let mut max = 0f32;
         let t_name = name.clone();
@@ -857,11 +1086,11 @@ impl Dsp {
                                                       description_len as i32, &mut min,
                                                       &mut max) } {
             ::Status::Ok => Ok((String::from_utf8(description).unwrap(), min, max)),
-            e => Err(e)
+            e => Err(::Error::new(e, "FMOD_DSP_GetParameterInfo"))
         }
     }
 
-    pub fn get_info(&self, name: &str) -> Result<(u32, i32, i32, i32), ::Status> {
+    pub fn get_info(&self, name: &str) -> Result<(u32, i32, i32, i32), ::Error> {
         let mut version = 0u32;
         let mut channels = 0i32;
         let mut config_width = 0i32;
@@ -872,24 +1101,28 @@ impl Dsp {
                                              &mut channels, &mut config_width,
             &mut config_height) } {
             ::Status::Ok => Ok((version, channels, config_width, config_height)),
-            e => Err(e)
+            e => Err(::Error::new(e, "FMOD_DSP_GetInfo"))
         }
     }
 
-    pub fn set_defaults(&self, frequency: f32, volume: f32, pan: f32, priority: i32) -> ::Status {
-        unsafe { ffi::FMOD_DSP_SetDefaults(self.dsp, frequency, volume, pan, priority) }
+    pub fn set_defaults(&self, frequency: f32, volume: f32, pan: f32,
+                        priority: i32) -> Result<(), ::Error> {
+        match unsafe { ffi::FMOD_DSP_SetDefaults(self.dsp, frequency, volume, pan, priority) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_DSP_SetDefaults"))
+        }
     }
 
-    pub fn get_type(&self) -> Result<::DspType, ::Status> {
+    pub fn get_type(&self) -> Result<::DspType, ::Error> {
         let mut _type = ::DspType::Unknown;
 
         match unsafe { ffi::FMOD_DSP_GetType(self.dsp, &mut _type) } {
             ::Status::Ok => Ok(_type),
-            e => Err(e)
+            e => Err(::Error::new(e, "FMOD_DSP_GetType"))
         }
     }
 
-    pub fn get_defaults(&self) -> Result<(f32, f32, f32, i32), ::Status> {
+    pub fn get_defaults(&self) -> Result<(f32, f32, f32, i32), ::Error> {
         let mut frequency = 0f32;
         let mut volume = 0f32;
         let mut pan = 0f32;
@@ -898,66 +1131,64 @@ impl Dsp {
         match unsafe { ffi::FMOD_DSP_GetDefaults(self.dsp, &mut frequency, &mut volume, &mut pan,
                                                  &mut priority) } {
             ::Status::Ok => Ok((frequency, volume, pan, priority)),
-            e => Err(e)
+            e => Err(::Error::new(e, "FMOD_DSP_GetDefaults"))
         }
     }
 
     pub fn get_memory_info(&self, MemoryBits(memory_bits): MemoryBits,
                            EventMemoryBits(event_memory_bits): EventMemoryBits)
-                           -> Result<(u32, MemoryUsageDetails), ::Status> {
+                           -> Result<(u32, MemoryUsageDetails), ::Error> {
         let mut details = fmod_sys::get_memory_usage_details_ffi(Default::default());
         let mut memory_used = 0u32;
 
         match unsafe { ffi::FMOD_DSP_GetMemoryInfo(self.dsp, memory_bits, event_memory_bits,
                                                    &mut memory_used, &mut details) } {
             ::Status::Ok => Ok((memory_used, fmod_sys::from_memory_usage_details_ptr(details))),
-            e => Err(e)
+            e => Err(::Error::new(e, "FMOD_DSP_GetMemoryInfo"))
         }
     }
 
-    pub fn set_user_data<'r, T>(&'r mut self, user_data: &'r mut T) -> ::Status {
-        let mut data: *mut c_void = ::std::ptr::null_mut();
+    /// Stores `user_data` in the DSP, replacing and freeing the previous one. It is freed when
+    /// the DSP is released by the object which created it, and leaked if FMOD releases the DSP
+    /// itself.
+    pub fn set_user_data<T: Any + Send>(&mut self, user_data: T) -> Result<(), ::Error> {
+        let data = self.get_user_data_ptr()?;
 
-        unsafe {
-            match ffi::FMOD_DSP_GetUserData(self.dsp, &mut data) {
-               ::Status::Ok => {
-                    if data.is_null() {
-                        self.user_data.user_data = ::std::ptr::null_mut();
-
-                        ffi::FMOD_DSP_SetUserData(self.dsp, transmute(&mut self.user_data))
-                    } else {
-                        let tmp : &mut UserData = transmute::<*mut c_void, &mut UserData>(data);
+        if !data.is_null() {
+            unsafe { (*data).user_data = Some(Box::new(user_data)) };
+            return Ok(());
+        }
 
-                        tmp.user_data = transmute::<&mut T, *mut c_void>(user_data);
-                        ffi::FMOD_DSP_SetUserData(self.dsp, transmute(tmp))
-                    }
-                }
-                _ => {
-                    self.user_data.user_data = transmute::<&mut T, *mut c_void>(user_data);
+        let mut data = Box::new(UserData::new());
 
-                    ffi::FMOD_DSP_SetUserData(self.dsp, transmute(&mut self.user_data))
-                }
+        data.user_data = Some(Box::new(user_data));
+        data.allocated = true;
+        let data = Box::into_raw(data);
+        match unsafe { ffi::FMOD_DSP_SetUserData(self.dsp, data as *mut c_void) } {
+            ::Status::Ok => Ok(()),
+            e => {
+                unsafe { drop(Box::from_raw(data)) };
+                Err(::Error::new(e, "FMOD_DSP_SetUserData"))
             }
         }
     }
 
-    pub fn get_user_data<'r, T>(&'r self) -> Result<&'r mut T, ::Status> {
-        unsafe {
-            let mut user_data : *mut c_void = ::std::ptr::null_mut();
+    /// Returns the user data if it is a `T`.
+    pub fn get_user_data<T: Any>(&self) -> Option<&T> {
+        match self.get_user_data_ptr() {
+            Ok(data) if !data.is_null() => unsafe {
+                (*data).user_data.as_ref().and_then(|d| d.downcast_ref())
+            },
+            _ => None
+        }
+    }
 
-            match ffi::FMOD_DSP_GetUserData(self.dsp, &mut user_data) {
-               ::Status::Ok => {
-                    if !user_data.is_null() {
-                        let tmp: &mut UserData = transmute::<*mut c_void, &mut UserData>(user_data);
-                        let tmp2: &mut T = transmute::<*mut c_void, &mut T>(tmp.user_data);
+    fn get_user_data_ptr(&self) -> Result<*mut UserData, ::Error> {
+        let mut data = ::std::ptr::null_mut();
 
-                        Ok(tmp2)
-                    } else {
-                        Err(::Status::Ok)
-                    }
-                },
-                e => Err(e)
-            }
+        match unsafe { ffi::FMOD_DSP_GetUserData(self.dsp, &mut data) } {
+            ::Status::Ok => Ok(data as *mut UserData),
+            e => Err(::Error::new(e, "FMOD_DSP_GetUserData"))
         }
     }
 }
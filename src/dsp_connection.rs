@@ -23,22 +23,36 @@
 */
 
 use ffi;
+use user_data;
+use std::any::Any;
 use types::*;
 use dsp;
 use libc::{c_int, c_void};
 use fmod_sys;
-use fmod_sys::MemoryUsageDetails;
-use std::mem::transmute;
+use fmod_sys::{MemoryUsageDetails, SysHandle};
+use borrowed;
+use borrowed::Borrowed;
 use std::default::Default;
+use std::sync::Arc;
 
 /// DspConnection object
 pub struct DspConnection {
-    dsp_connection: *mut ffi::FMOD_DSPCONNECTION
+    dsp_connection: *mut ffi::FMOD_DSPCONNECTION,
+    /* keeps the system alive */
+    owner: Option<Arc<SysHandle>>
+}
+
+pub fn from_ptr(dsp_connection: *mut ffi::FMOD_DSPCONNECTION,
+                owner: Option<Arc<SysHandle>>) -> DspConnection {
+    DspConnection {
+        dsp_connection: dsp_connection,
+        owner: owner
+    }
 }
 
 impl ffi::FFI<ffi::FMOD_DSPCONNECTION> for DspConnection {
     fn wrap(d: *mut ffi::FMOD_DSPCONNECTION) -> DspConnection {
-        DspConnection {dsp_connection: d}
+        DspConnection {dsp_connection: d, owner: None}
     }
 
     fn unwrap(d: &DspConnection) -> *mut ffi::FMOD_DSPCONNECTION {
@@ -55,58 +69,66 @@ impl Drop for DspConnection {
 impl DspConnection {
     pub fn release(&mut self) {
         self.dsp_connection = ::std::ptr::null_mut();
+        self.owner = None;
     }
 
-    pub fn get_input(&self) -> Result<dsp::Dsp, ::Status> {
+    pub fn get_input(&self) -> Result<Borrowed<'_, dsp::Dsp>, ::Error> {
         let mut input = ::std::ptr::null_mut();
 
         match unsafe { ffi::FMOD_DSPConnection_GetInput(self.dsp_connection, &mut input) } {
-            ::Status::Ok => Ok(ffi::FFI::wrap(input)),
-            e => Err(e)
+            ::Status::Ok => Ok(borrowed::wrap(input)),
+            e => Err(::Error::new(e, "FMOD_DSPConnection_GetInput"))
         }
     }
 
-    pub fn get_output(&self) -> Result<dsp::Dsp, ::Status> {
+    pub fn get_output(&self) -> Result<Borrowed<'_, dsp::Dsp>, ::Error> {
         let mut output = ::std::ptr::null_mut();
 
         match unsafe { ffi::FMOD_DSPConnection_GetOutput(self.dsp_connection, &mut output) } {
-            ::Status::Ok => Ok(ffi::FFI::wrap(output)),
-            e => Err(e)
+            ::Status::Ok => Ok(borrowed::wrap(output)),
+            e => Err(::Error::new(e, "FMOD_DSPConnection_GetOutput"))
         }
     }
 
-    pub fn set_mix(&self, volume: f32) -> ::Status {
-        unsafe { ffi::FMOD_DSPConnection_SetMix(self.dsp_connection, volume) }
+    pub fn set_mix(&self, volume: f32) -> Result<(), ::Error> {
+        match unsafe { ffi::FMOD_DSPConnection_SetMix(self.dsp_connection, volume) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_DSPConnection_SetMix"))
+        }
     }
 
-    pub fn get_mix(&self) -> Result<f32, ::Status> {
+    pub fn get_mix(&self) -> Result<f32, ::Error> {
         let mut volume = 0f32;
 
         match unsafe { ffi::FMOD_DSPConnection_GetMix(self.dsp_connection, &mut volume) } {
             ::Status::Ok => Ok(volume),
-            e => Err(e)
+            e => Err(::Error::new(e, "FMOD_DSPConnection_GetMix"))
         }
     }
 
-    pub fn set_levels(&self, speaker: ::Speaker, levels: &mut Vec<f32>) -> ::Status {
-        unsafe { ffi::FMOD_DSPConnection_SetLevels(self.dsp_connection, speaker,
-                                                   levels.as_mut_ptr(), levels.len() as c_int) }
+    pub fn set_levels(&self, speaker: ::Speaker, levels: &mut Vec<f32>) -> Result<(), ::Error> {
+        match unsafe { ffi::FMOD_DSPConnection_SetLevels(self.dsp_connection, speaker,
+                                                         levels.as_mut_ptr(),
+                                                         levels.len() as c_int) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_DSPConnection_SetLevels"))
+        }
     }
 
-    pub fn get_levels(&self, speaker: ::Speaker, num_levels: usize) -> Result<Vec<f32>, ::Status> {
+    pub fn get_levels(&self, speaker: ::Speaker, num_levels: usize) -> Result<Vec<f32>, ::Error> {
         let mut levels : Vec<f32> = ::std::iter::repeat(0f32).take(num_levels).collect();
 
         match unsafe { ffi::FMOD_DSPConnection_GetLevels(self.dsp_connection, speaker,
                                                          levels.as_mut_ptr(),
                                                          levels.len() as c_int) } {
             ::Status::Ok => Ok(levels),
-            e => Err(e),
+            e => Err(::Error::new(e, "FMOD_DSPConnection_GetLevels")),
         }
     }
 
     pub fn get_memory_info(&self, MemoryBits(memory_bits): MemoryBits,
                            EventMemoryBits(event_memory_bits): EventMemoryBits)
-                           -> Result<(u32, MemoryUsageDetails), ::Status> {
+                           -> Result<(u32, MemoryUsageDetails), ::Error> {
         let mut details = fmod_sys::get_memory_usage_details_ffi(Default::default());
         let mut memory_used = 0u32;
 
@@ -114,26 +136,39 @@ impl DspConnection {
                                                              event_memory_bits, &mut memory_used,
                                                              &mut details) } {
             ::Status::Ok => Ok((memory_used, fmod_sys::from_memory_usage_details_ptr(details))),
-            e => Err(e),
+            e => Err(::Error::new(e, "FMOD_DSPConnection_GetMemoryInfo")),
         }
     }
 
-    pub fn set_user_data<'r, T>(&'r self, user_data: &'r mut T) -> ::Status {
-        unsafe { ffi::FMOD_DSPConnection_SetUserData(self.dsp_connection, transmute(user_data)) }
-    }
+    /// Stores `user_data` in the connection, replacing and freeing the previous one. FMOD doesn't tell when a connection is
+    /// removed, so it is only freed when replaced.
+    pub fn set_user_data<T: Any + Send>(&mut self, user_data: T) -> Result<(), ::Error> {
+        let old = self.get_user_data_ptr()?;
+        let data = user_data::into_ptr(user_data);
 
-    pub fn get_user_data<'r, T>(&'r self) -> Result<&'r mut T, ::Status> {
-        unsafe {
-            let mut user_data : *mut c_void = ::std::ptr::null_mut();
-
-            match ffi::FMOD_DSPConnection_GetUserData(self.dsp_connection, &mut user_data) {
-               ::Status::Ok => {
-                    let tmp : &mut T = transmute::<*mut c_void, &mut T>(user_data);
-                    
-                    Ok(tmp)
-                }
-                e => Err(e),
+        match unsafe { ffi::FMOD_DSPConnection_SetUserData(self.dsp_connection, data) } {
+            ::Status::Ok => {
+                unsafe { user_data::free(old) };
+                Ok(())
             }
+            e => {
+                unsafe { user_data::free(data) };
+                Err(::Error::new(e, "FMOD_DSPConnection_SetUserData"))
+            }
+        }
+    }
+
+    /// Returns the user data if it is a `T`.
+    pub fn get_user_data<T: Any>(&self) -> Option<&T> {
+        self.get_user_data_ptr().ok().and_then(|data| unsafe { user_data::downcast(data) })
+    }
+
+    fn get_user_data_ptr(&self) -> Result<*mut c_void, ::Error> {
+        let mut user_data = ::std::ptr::null_mut();
+
+        match unsafe { ffi::FMOD_DSPConnection_GetUserData(self.dsp_connection, &mut user_data) } {
+            ::Status::Ok => Ok(user_data),
+            e => Err(::Error::new(e, "FMOD_DSPConnection_GetUserData"))
         }
     }
 }
\ No newline at end of file
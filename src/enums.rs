@@ -494,7 +494,7 @@ pub enum DelayType {
 
 #[derive(Clone, PartialEq, PartialOrd, Debug, Copy)]
 #[repr(C)]
-/// These output types are used with [`Sys::set_output`](../../struct.Sys.html#method.set_output) /
+/// These output types are used with [`SysBuilder::set_output`](../../struct.SysBuilder.html#method.set_output) /
 /// [`Sys::get_output`](../../struct.Sys.html#method.get_output), to choose which output method to use.
 pub enum OutputType {
     /// Picks the best output mode for the platform. This is the default.
@@ -505,7 +505,7 @@ pub enum OutputType {
     /// All             - All calls in this mode succeed but make no sound.
     NoSound,
     /// All             - Writes output to fmodoutput.wav by default. Use the 'extradriverdata'
-    /// parameter in [`Sys::init`](../../struct.Sys.html#method.init), by simply passing the
+    /// parameter in [`SysBuilder::init_with_driver_data`](../../struct.SysBuilder.html#method.init_with_driver_data), by simply passing the
     /// filename as a string, to set the wav filename.
     WAVWriter,
     /// All             - Non-realtime version of FMOD_OUTPUTYPE_NOSOUND. User can drive mixer with
@@ -603,11 +603,11 @@ pub enum Speaker {
 #[derive(Clone, PartialEq, PartialOrd, Debug, Copy)]
 #[repr(C)]
 /// These are speaker types defined for use with the
-/// [`Sys::set_speaker_mode`](../../struct.Sys.html#method.set_speaker_mode) or
+/// [`SysBuilder::set_speaker_mode`](../../struct.SysBuilder.html#method.set_speaker_mode) or
 /// [`Sys::get_speaker_mode`](../../struct.Sys.html#method.get_speaker_mode) command.
 pub enum SpeakerMode {
     /// There is no specific . Sound channels are mapped in order of input to output. Use
-    /// [`Sys::set_software_format`](../../struct.Sys.html#method.set_software_format) to specify
+    /// [`SysBuilder::set_software_format`](../../struct.SysBuilder.html#method.set_software_format) to specify
     /// speaker count. See remarks for more information.
     Raw,
     /// The speakers are monaural.
@@ -713,6 +713,26 @@ pub enum OpenState {
 #[repr(C)]
 /// These callback types are used with
 /// [`Channel::set_callback`](../../struct.Channel.html#method.set_callback).
+pub enum ChannelCallbackType {
+    /// Called when a sound ends.
+    End,
+    /// Called when a voice is swapped out or swapped in.
+    VirtualVoice,
+    /// Called when a syncpoint is encountered. Can be from wav file markers.
+    SyncPoint,
+    /// Called when the channel has its geometry occlusion value calculated. Can be used to clamp
+    /// or change the value.
+    Occlusion,
+    /// Maximum number of callback types supported.
+    Max,
+    /// Makes sure this enum is signed 32bit.
+    ForceInt = 65536,
+}
+
+#[derive(Clone, PartialEq, PartialOrd, Debug, Copy)]
+#[repr(C)]
+/// These callback types are used with
+/// [`Sys::set_callback`](../../struct.Sys.html#method.set_callback).
 pub enum SystemCallbackType {
     /// Called from [`Sys::update`](../../struct.Sys.html#method.update) when the enumerated list of
     /// devices has changed.
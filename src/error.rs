@@ -22,6 +22,70 @@
 * 3. This notice may not be removed or altered from any source distribution.
 */
 
+use std::error;
+use std::fmt;
+use std::io;
+
+/// Error returned by the binding when an FMOD function call fails.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Error {
+    /// The status code returned by FMOD.
+    pub status: ::Status,
+    /// Name of the FMOD function which returned the status (ie "FMOD_Channel_SetVolume").
+    pub function: &'static str,
+    /// Human readable description of the status, as returned by [`error_string`](fn.error_string.html).
+    pub description: &'static str,
+    /// Cause of the error found by the binding itself, like the conflicting flags reported by
+    /// [`Mode::validate`](struct.Mode.html#method.validate).
+    pub detail: Option<&'static str>,
+}
+
+impl Error {
+    pub fn new(status: ::Status, function: &'static str) -> Error {
+        Error {
+            status: status,
+            function: function,
+            description: error_string(status),
+            detail: None,
+        }
+    }
+
+    pub fn with_detail(status: ::Status, function: &'static str, detail: &'static str) -> Error {
+        Error {
+            detail: Some(detail),
+            ..Error::new(status, function)
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.detail {
+            Some(detail) => write!(f, "{} failed: {} ({:?}): {}", self.function, self.description,
+                                   self.status, detail),
+            None => write!(f, "{} failed: {} ({:?})", self.function, self.description, self.status)
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn description(&self) -> &str {
+        self.description
+    }
+}
+
+impl From<Error> for io::Error {
+    fn from(e: Error) -> io::Error {
+        io::Error::new(io::ErrorKind::Other, e)
+    }
+}
+
+impl From<Error> for ::Status {
+    fn from(e: Error) -> ::Status {
+        e.status
+    }
+}
+
 pub fn error_string(errcode: ::Status) -> &'static str {
     match errcode {
         ::Status::AlreadyLocked => "Tried to call lock a second time before unlock was called.",
@@ -46,6 +46,16 @@ pub fn strlen(mut c: *const c_char) -> usize {
     len
 }
 
+/* copies as much of text as fits in out, always null terminated */
+pub fn write_c_chars(text: &str, out: &mut [c_char]) {
+    let len = ::std::cmp::min(text.len(), out.len() - 1);
+
+    for (it, b) in text.as_bytes()[..len].iter().enumerate() {
+        out[it] = *b as c_char;
+    }
+    out[len] = 0;
+}
+
 pub type FMOD_BOOL = c_int;
 pub type FMOD_CAPS = c_uint;
 pub type FMOD_INITFLAGS = c_uint;
@@ -65,6 +75,8 @@ pub struct FMOD_SYNCPOINT;
 
 pub type FMOD_SYSTEM_CALLBACK = Option<extern "C" fn(system: *mut FMOD_SYSTEM, _type: ::SystemCallbackType, command_data1: *mut c_void,
     command_data2: *mut c_void) -> ::Status>;
+pub type FMOD_CHANNEL_CALLBACK = Option<extern "C" fn(channel: *mut FMOD_CHANNEL, _type: ::ChannelCallbackType, command_data1: *mut c_void,
+    command_data2: *mut c_void) -> ::Status>;
 
 /* file callbacks */
 pub type FMOD_FILE_OPENCALLBACK = Option<extern "C" fn(name: *mut c_char, unicode: c_int, file_size: *mut c_uint, handle: *mut *mut c_void,
@@ -74,7 +86,7 @@ pub type FMOD_FILE_READCALLBACK = Option<extern "C" fn(handle: *mut c_void, buff
     user_data: *mut c_void) -> ::Status>;
 pub type FMOD_FILE_SEEKCALLBACK = Option<extern "C" fn(handle: *mut c_void, pos: c_uint, user_data: *mut c_void) -> ::Status>;
 pub type FMOD_FILE_ASYNCREADCALLBACK = Option<extern "C" fn(arg1: *mut FMOD_ASYNCREADINFO, arg2: *mut c_void) -> ::Status>;
-pub type FMOD_FILE_ASYNCCANCELCALLBACK = Option<extern "C" fn(arg1: *mut c_void, arg2: *mut c_void, arg3: c_uint) -> ::Status>;
+pub type FMOD_FILE_ASYNCCANCELCALLBACK = Option<extern "C" fn(handle: *mut c_void, userdata: *mut c_void) -> ::Status>;
 
 /* sound callbacks */
 pub type FMOD_SOUND_NONBLOCKCALLBACK = Option<extern "C" fn(sound: *mut FMOD_SOUND, result: ::Status) -> ::Status>;
@@ -140,8 +152,9 @@ extern "C" {
     pub fn FMOD_System_GetAdvancedSettings(system: *mut FMOD_SYSTEM, settings: *mut FMOD_ADVANCEDSETTINGS) -> ::Status;
     pub fn FMOD_System_SetSpeakerMode(system: *mut FMOD_SYSTEM, speaker_mode: ::SpeakerMode) -> ::Status;
     pub fn FMOD_System_GetSpeakerMode(system: *mut FMOD_SYSTEM, speaker_mode: *mut ::SpeakerMode) -> ::Status;
-    // I'll bind it a little later
     pub fn FMOD_System_SetCallback(system: *mut FMOD_SYSTEM, call_back: FMOD_SYSTEM_CALLBACK) -> ::Status;
+    pub fn FMOD_System_SetUserData(system: *mut FMOD_SYSTEM, user_data: *mut c_void) -> ::Status;
+    pub fn FMOD_System_GetUserData(system: *mut FMOD_SYSTEM, user_data: *mut *mut c_void) -> ::Status;
     /* plug-in part functions */
     pub fn FMOD_System_SetPluginPath(system: *mut FMOD_SYSTEM, path: *const c_char) -> ::Status;
     pub fn FMOD_System_LoadPlugin(system: *mut FMOD_SYSTEM, filename: *const c_char, handle: *mut c_uint, priority: c_uint) -> ::Status;
@@ -331,8 +344,7 @@ extern "C" {
     pub fn FMOD_Channel_GetLowPassGain(channel: *mut FMOD_CHANNEL, gain: *mut c_float) -> ::Status;
     pub fn FMOD_Channel_SetChannelGroup(channel: *mut FMOD_CHANNEL, channelgroup: *mut FMOD_CHANNELGROUP) -> ::Status;
     pub fn FMOD_Channel_GetChannelGroup(channel: *mut FMOD_CHANNEL, channelgroup: *mut *mut FMOD_CHANNELGROUP) -> ::Status;
-    /* I'll bind it later */
-    //pub fn FMOD_Channel_SetCallback(channel: *mut FMOD_CHANNEL, callback: *mut FMOD_Channel_CALLBACK) -> ::Status;
+    pub fn FMOD_Channel_SetCallback(channel: *mut FMOD_CHANNEL, callback: FMOD_CHANNEL_CALLBACK) -> ::Status;
     /* 3D functionality */
     pub fn FMOD_Channel_Set3DAttributes(channel: *mut FMOD_CHANNEL, position: *mut FMOD_VECTOR, velociy: *mut FMOD_VECTOR) -> ::Status;
     pub fn FMOD_Channel_Get3DAttributes(channel: *mut FMOD_CHANNEL, position: *mut FMOD_VECTOR, velociy: *mut FMOD_VECTOR) -> ::Status;
@@ -386,6 +398,7 @@ extern "C" {
     
     /* channel_group functions */
     pub fn FMOD_ChannelGroup_Release(channel_group: *mut FMOD_CHANNELGROUP) -> ::Status;
+    pub fn FMOD_ChannelGroup_GetSystemObject(channel_group: *mut FMOD_CHANNELGROUP, system: *mut *mut FMOD_SYSTEM) -> ::Status;
     /* Channelgroup scale values. (changes attributes relative to the channels, doesn't overwrite them)*/
     pub fn FMOD_ChannelGroup_SetVolume(channel_group: *mut FMOD_CHANNELGROUP, volume: c_float) -> ::Status;
     pub fn FMOD_ChannelGroup_GetVolume(channel_group: *mut FMOD_CHANNELGROUP, volume: *mut c_float) -> ::Status;
@@ -555,6 +568,7 @@ extern "C" {
         memory_used_details: *mut FMOD_MEMORY_USAGE_DETAILS) -> ::Status;
 }
 
+#[repr(C)]
 pub struct FMOD_ASYNCREADINFO {
     pub handle     : *mut c_void,   /* [r] The file handle that was filled out in the open callback. */
     pub offset     : c_uint,        /* [r] Seek position, make sure you read from this file offset. */
@@ -649,6 +663,7 @@ pub struct FMOD_ADVANCEDSETTINGS {
     pub stackSizeMixer             : c_uint              /* [r/w] Optional. Specify 0 to ignore. Specify the stack size for the FMOD mixer thread. Useful for custom dsps that use excess stack. Default 49,152 (48kb) */
 }
 
+#[repr(C)]
 pub struct FMOD_CODEC_DESCRIPTION {
     pub name           : *mut c_char,                   /* [in] Name of the codec. */
     pub version        : c_uint,                        /* [in] Plugin writer's version number. */
@@ -664,6 +679,7 @@ pub struct FMOD_CODEC_DESCRIPTION {
     pub getwaveformat  : FMOD_CODEC_GETWAVEFORMAT       /* [in] Callback to tell FMOD about the waveformat of a particular subsound. This is to save memory, rather than saving 1000 FMOD_CODEC_WAVEFORMAT structures in the codec, the codec might have a more optimal way of storing this information. */
 }
 
+#[repr(C)]
 pub struct FMOD_CODEC_WAVEFORMAT
 {
     pub name       : [c_char; 256],    /* [in] Name of sound.*/
@@ -679,10 +695,11 @@ pub struct FMOD_CODEC_WAVEFORMAT
     pub channelmask: c_uint            /* [in] Microsoft speaker channel mask, as defined for WAVEFORMATEXTENSIBLE and is found in ksmedia.h. Leave at 0 to play in natural speaker order. */
 }
 
+#[repr(C)]
 pub struct FMOD_CODEC_STATE
 {
     pub numsubsounds: c_int,                      /* [in] Number of 'subsounds' in this sound. Anything other than 0 makes it a 'container' format (ie CDDA/DLS/FSB etc which contain 1 or more su bsounds). For most normal, single sound codec such as WAV/AIFF/MP3, this should be 0 as they are not a container for subsounds, they are the sound by itself. */
-    pub waveformat  : FMOD_CODEC_WAVEFORMAT,      /* [in] Pointer to an array of format structures containing information about each sample. Can be 0 or NULL if FMOD_CODEC_GETWAVEFORMAT callback is preferred. The number of entries here must equal the number of subsounds defined in the subsound parameter. If numsubsounds = 0 then there should be 1 instance of this structure. */
+    pub waveformat  : *mut FMOD_CODEC_WAVEFORMAT, /* [in] Pointer to an array of format structures containing information about each sample. Can be 0 or NULL if FMOD_CODEC_GETWAVEFORMAT callback is preferred. The number of entries here must equal the number of subsounds defined in the subsound parameter. If numsubsounds = 0 then there should be 1 instance of this structure. */
     pub plugindata  : *mut c_void,                /* [in] Plugin writer created data the codec author wants to attach to this object. */
                                                
     pub filehandle  : *mut c_void,                /* [out] This will return an internal FMOD file handle to use with the callbacks provided. */
@@ -716,6 +733,7 @@ pub struct FMOD_REVERB_PROPERTIES
     pub Flags           : c_uint       /* [r/w] *mut FMOD_REVERB_FLAGS - modifies the behavior of above properties                                (SUPPORTED:WII) */
 }
 
+#[repr(C)]
 pub struct FMOD_TAG
 {
     pub _type   : ::TagType,      /* [r] The type of this tag. */
@@ -826,7 +844,12 @@ pub struct SoundData {
     pub non_block: SoundNonBlockCallback,
     pub pcm_read: SoundPcmReadCallback,
     pub pcm_set_pos: SoundPcmSetPosCallback,
-    pub user_data: *mut c_void
+    pub format: ::SoundFormat,
+    pub user_data: Option<::UserData>,
+    /* set for sounds created with Sys::create_sound_async */
+    pub load: Option<::std::sync::Arc<::sound::LoadState>>,
+    /* allocated by Sound::set_user_data, freed when the sound is released */
+    pub allocated: bool
 }
 
 impl SoundData {
@@ -835,7 +858,10 @@ impl SoundData {
             non_block: None,
             pcm_read: None,
             pcm_set_pos: None,
-            user_data: ::std::ptr::null_mut()
+            format: ::SoundFormat::None,
+            user_data: None,
+            load: None,
+            allocated: false
         }
     }
 }
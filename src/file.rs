@@ -27,11 +27,18 @@ use libc::{SEEK_SET, SEEK_CUR, SEEK_END};
 use libc::FILE;
 use libc::stat;
 use std::mem::zeroed;
+use std::io::{Read, Seek};
 use libc::fstat;
 use libc::fileno;
 use libc::{c_void, c_char, c_long};
 use std::ffi::CString;
 
+/// File opened by the factory given to
+/// [`Sys::set_file_system`](struct.Sys.html#method.set_file_system).
+pub trait ReadSeek: Read + Seek + Send {}
+
+impl<T: Read + Seek + Send> ReadSeek for T {}
+
 #[doc(hidden)]
 pub fn get_ffi(file: &FmodFile) -> *mut FILE {
     file.fd
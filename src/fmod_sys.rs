@@ -22,9 +22,10 @@
 * 3. This notice may not be removed or altered from any source distribution.
 */
 
-use libc::{c_void, c_uint, c_int, c_char, c_short};
+use libc::{c_void, c_uint, c_int, c_char};
 use ffi;
 use types::*;
+use position::Position;
 use sound;
 use sound::Sound;
 use sound_group;
@@ -38,152 +39,186 @@ use reverb_properties;
 use geometry;
 use reverb;
 use dsp_connection;
+use codec;
+use decoder;
+use async_read_info;
+use async_read_info::{AsyncReader, SharedFile};
 use std::default::Default;
 use callbacks::*;
 use std;
-use file;
-use libc::FILE;
-use c_vec::CVec;
-use std::ffi::CString;
-
-fn get_saved_sys_callback<'r>() -> &'r mut SysCallback {
-    static mut callback : SysCallback = SysCallback {
-            file_open: None,
-            file_close: None,
-            file_read: None,
-            file_seek: None
-        };
-
-    unsafe { &mut callback }
+use file::ReadSeek;
+use std::path::Path;
+use std::io::Seek;
+use std::slice;
+use std::ffi::{CStr, CString};
+use std::sync::{Arc, Mutex, Weak};
+use std::time::Duration;
+use std::io;
+use std::fmt;
+use std::fs::File;
+use byteorder::{WriteBytesExt, LittleEndian};
+use borrowed;
+use borrowed::Borrowed;
+
+/* FMOD Ex gives no user data to the file open callback, which therefore doesn't know which
+   system it is called for. Every system with a file system gets its own open callback, finding
+   the system in its slot, which is freed when the file system is removed or the system is
+   released. Few programs use more than a couple of systems at once */
+const FILE_SYSTEM_SLOTS: usize = 16;
+
+static FILE_SYSTEMS: Mutex<[usize; FILE_SYSTEM_SLOTS]> = Mutex::new([0; FILE_SYSTEM_SLOTS]);
+
+/* file system set with Sys::set_file_system or Sys::set_async_file_system */
+struct FileSystem {
+    factory: FileSystemFactory,
+    reader: Option<Arc<dyn AsyncReader>>
 }
 
-struct SysCallback {
-    file_open: FileOpenCallback,
-    file_close: FileCloseCallback,
-    file_read: FileReadCallback,
-    file_seek: FileSeekCallback
+/* handle given to FMOD for every opened file */
+struct FileHandle {
+    file: Arc<SharedFile>,
+    reader: Option<Arc<dyn AsyncReader>>
 }
 
-impl SysCallback {
-    fn new() -> SysCallback {
-        SysCallback {
-            file_open: None,
-            file_close: None,
-            file_read: None,
-            file_seek: None
-        }
+fn get_file_system(slot: usize) -> Option<Arc<FileSystem>> {
+    let system = match FILE_SYSTEMS.lock() {
+        Ok(systems) if systems[slot] != 0 => systems[slot] as *mut ffi::FMOD_SYSTEM,
+        _ => return None
+    };
+    let data = match get_sys_data(system) {
+        Ok(data) if !data.is_null() => data,
+        _ => return None
+    };
+
+    match unsafe { (*data).file_system.lock() } {
+        Ok(f) => f.clone(),
+        Err(_) => None
     }
 }
 
-extern "C" fn file_open_callback(name: *mut c_char, unicode: c_int, file_size: *mut c_uint,
-                                 handle: *mut *mut c_void,
-                                 user_data: *mut *mut c_void) -> ::Status {
-    let tmp = get_saved_sys_callback();
+extern "C" fn file_open_callback<const SLOT: usize>(name: *mut c_char, unicode: c_int,
+                                                    file_size: *mut c_uint,
+                                                    handle: *mut *mut c_void,
+                                                    user_data: *mut *mut c_void) -> ::Status {
+    let file_system = match get_file_system(SLOT) {
+        Some(f) => f,
+        None => return ::Status::FileNotFound
+    };
+    /* unicode names are wide strings, which paths can't be built from portably */
+    if name.is_null() || unicode != 0 {
+        return ::Status::FileNotFound;
+    }
+    let name = unsafe { CStr::from_ptr(name) }.to_string_lossy().into_owned();
+    let mut file = match (file_system.factory)(Path::new(&name)) {
+        Ok(f) => f,
+        Err(ref e) if e.kind() == io::ErrorKind::NotFound => return ::Status::FileNotFound,
+        Err(_) => return ::Status::FileBad
+    };
+    let size = match file.seek(io::SeekFrom::End(0)).and_then(|s| {
+        file.seek(io::SeekFrom::Start(0)).map(|_| s)
+    }) {
+        Ok(s) => s,
+        Err(_) => return ::Status::FileCouldNotSeek
+    };
 
-    match tmp.file_open {
-        Some(s) => {
-            let t_name = if name.is_null() {
-                String::new()
-            } else {
-                let l = ffi::strlen(name);
+    unsafe {
+        *file_size = size as c_uint;
+        *handle = Box::into_raw(Box::new(FileHandle {
+            file: Arc::new(SharedFile::new(file)),
+            reader: file_system.reader.clone()
+        })) as *mut c_void;
+        *user_data = ::std::ptr::null_mut();
+    }
+    ::Status::Ok
+}
 
-                unsafe { String::from_raw_parts(name as *mut u8, l, l) }
-            };
-            match s(t_name.as_ref(), unicode) {
-                Some((f, s)) => {
-                    unsafe {
-                        *file_size = f.get_file_size() as u32;
-                        *handle = file::get_ffi(&f) as *mut c_void;
-                        *user_data = match s {
-                            Some(mut d) => std::mem::transmute(&mut d),
-                            None => ::std::ptr::null_mut()
-                        };
-                    }
-                   ::Status::Ok
-                }
-                None => {
-                    unsafe {
-                        *file_size = 0u32;
-                        *handle = std::ptr::null_mut();
-                        *user_data = std::ptr::null_mut();
-                    }
-                    ::Status::FileNotFound
-                }
-            }
-        },
-        None => {
-            unsafe {
-                *file_size = 0u32;
-                *handle = std::ptr::null_mut();
-                *user_data = std::ptr::null_mut();
+static FILE_OPEN_CALLBACKS: [ffi::FMOD_FILE_OPENCALLBACK; FILE_SYSTEM_SLOTS] = [
+    Some(file_open_callback::<0>), Some(file_open_callback::<1>),
+    Some(file_open_callback::<2>), Some(file_open_callback::<3>),
+    Some(file_open_callback::<4>), Some(file_open_callback::<5>),
+    Some(file_open_callback::<6>), Some(file_open_callback::<7>),
+    Some(file_open_callback::<8>), Some(file_open_callback::<9>),
+    Some(file_open_callback::<10>), Some(file_open_callback::<11>),
+    Some(file_open_callback::<12>), Some(file_open_callback::<13>),
+    Some(file_open_callback::<14>), Some(file_open_callback::<15>)
+];
+
+extern "C" fn file_close_callback(handle: *mut c_void, _: *mut c_void) -> ::Status {
+    if !handle.is_null() {
+        unsafe { drop(Box::from_raw(handle as *mut FileHandle)) };
+    }
+    ::Status::Ok
+}
+
+extern "C" fn file_read_callback(handle: *mut c_void, buffer: *mut c_void, size_bytes: c_uint,
+                                 bytes_read: *mut c_uint, _: *mut c_void) -> ::Status {
+    if handle.is_null() {
+        return ::Status::InvalidHandle;
+    }
+    let handle = unsafe { &*(handle as *mut FileHandle) };
+    let buffer = unsafe { slice::from_raw_parts_mut(buffer as *mut u8, size_bytes as usize) };
+    let mut file = match handle.file.file.lock() {
+        Ok(f) => f,
+        Err(_) => return ::Status::FileBad
+    };
+
+    match async_read_info::read_full(&mut **file, buffer) {
+        Ok(read) => {
+            unsafe { *bytes_read = read as c_uint };
+            if read < buffer.len() {
+                ::Status::FileEOF
+            } else {
+                ::Status::Ok
             }
-            ::Status::Ok
+        }
+        Err(e) => {
+            unsafe { *bytes_read = 0 };
+            e
         }
     }
 }
 
-extern "C" fn file_close_callback(handle: *mut c_void, user_data: *mut c_void) -> ::Status {
-    let tmp = get_saved_sys_callback();
+extern "C" fn file_seek_callback(handle: *mut c_void, pos: c_uint, _: *mut c_void) -> ::Status {
+    if handle.is_null() {
+        return ::Status::InvalidHandle;
+    }
+    let handle = unsafe { &*(handle as *mut FileHandle) };
+    let mut file = match handle.file.file.lock() {
+        Ok(f) => f,
+        Err(_) => return ::Status::FileCouldNotSeek
+    };
 
-    match tmp.file_close {
-        Some(s) => {
-            unsafe {
-                s(&mut file::from_ffi(handle as *mut FILE), if user_data.is_null() {
-                    None
-                } else {
-                    Some(std::mem::transmute(user_data))
-                });
-            }
-            ::Status::Ok
-        }
-        None => ::Status::Ok
+    match file.seek(io::SeekFrom::Start(pos as u64)) {
+        Ok(_) => ::Status::Ok,
+        Err(_) => ::Status::FileCouldNotSeek
     }
 }
 
-extern "C" fn file_read_callback(handle: *mut c_void, buffer: *mut c_void, size_bytes: c_uint,
-                                 bytes_read: *mut c_uint, user_data: *mut c_void) -> ::Status {
-    let tmp = get_saved_sys_callback();
-
-    match tmp.file_read {
-        Some(s) => {
-            unsafe {
-                let mut data_vec : CVec<u8> = CVec::new(buffer as *mut u8, size_bytes as usize);
+extern "C" fn file_async_read_callback(info: *mut ffi::FMOD_ASYNCREADINFO,
+                                       _: *mut c_void) -> ::Status {
+    if info.is_null() || unsafe { (*info).handle.is_null() } {
+        return ::Status::InvalidHandle;
+    }
+    let handle = unsafe { &*((*info).handle as *mut FileHandle) };
 
-                let read_bytes = s(&mut file::from_ffi(handle as *mut FILE), data_vec.as_mut(),
-                                   size_bytes, if user_data.is_null() {
-                    None
-                } else {
-                    Some(std::mem::transmute(user_data))
-                });
-                *bytes_read = read_bytes as u32;
-                if read_bytes < size_bytes as usize {
-                    ::Status::FileEOF
-                } else {
-                   ::Status::Ok
-                }
+    match handle.reader {
+        Some(ref reader) => match async_read_info::from_ffi(info, handle.file.clone()) {
+            Ok(info) => {
+                reader.read(info);
+                ::Status::Ok
             }
-        }
-        None => ::Status::Ok
+            Err(e) => e
+        },
+        None => ::Status::Unsupported
     }
 }
 
-extern "C" fn file_seek_callback(handle: *mut c_void, pos: c_uint,
-                                 user_data: *mut c_void) -> ::Status {
-    let tmp = get_saved_sys_callback();
-
-    match tmp.file_seek {
-        Some(s) => {
-            unsafe {
-                s(&mut file::from_ffi(handle as *mut FILE), pos, if user_data.is_null() {
-                    None
-                } else {
-                    Some(std::mem::transmute(user_data))
-                });
-            }
-            ::Status::Ok
-        }
-        None => ::Status::Ok
+extern "C" fn file_async_cancel_callback(handle: *mut c_void, _: *mut c_void) -> ::Status {
+    if handle.is_null() {
+        return ::Status::InvalidHandle;
     }
+    /* the pending reads of the file are freed by FMOD once this returns */
+    unsafe { &*(handle as *mut FileHandle) }.file.cancel()
 }
 
 extern "C" fn pcm_read_callback(sound: *mut ffi::FMOD_SOUND, data: *mut c_void,
@@ -197,12 +232,10 @@ extern "C" fn pcm_read_callback(sound: *mut ffi::FMOD_SOUND, data: *mut c_void,
                 let callbacks : &mut ffi::SoundData = std::mem::transmute(tmp);
 
                 match callbacks.pcm_read {
-                    Some(p) => {
-                        let max = data_len as isize >> 2;
-                        let mut data_vec = CVec::new(data as *mut c_short, max as usize * 2);
+                    Some(ref mut p) => {
+                        let buffer = sound::PcmBuffer::from_raw(data, data_len, callbacks.format);
 
-                        let ret = p(&ffi::FFI::wrap(sound), data_vec.as_mut());
-                        ret
+                        p(&ffi::FFI::wrap(sound), buffer)
                     },
                     None => ::Status::Ok
                 }
@@ -224,6 +257,9 @@ extern "C" fn non_block_callback(sound: *mut ffi::FMOD_SOUND, result: ::Status)
             if !tmp.is_null() {
                 let callbacks : &mut ffi::SoundData = ::std::mem::transmute(tmp);
 
+                if let Some(ref load) = callbacks.load {
+                    load.complete(result);
+                }
                 match callbacks.non_block {
                     Some(p) => p(&ffi::FFI::wrap(sound), result),
                     None => ::Status::Ok
@@ -248,7 +284,8 @@ extern "C" fn pcm_set_pos_callback(sound: *mut ffi::FMOD_SOUND, sub_sound: c_int
                 let callbacks : &mut ffi::SoundData = ::std::mem::transmute(tmp);
 
                 match callbacks.pcm_set_pos {
-                    Some(p) => p(&ffi::FFI::wrap(sound), sub_sound, position, TimeUnit(postype)),
+                    Some(ref mut p) => p(&ffi::FFI::wrap(sound), sub_sound, position,
+                                         TimeUnit(postype)),
                     None => ::Status::Ok
                 }
             } else {
@@ -285,35 +322,8 @@ impl Default for Guid {
     }
 }
 
-/// Structure used to store user data for file callback
-pub struct UserData {
-    user_data: *mut c_void
-}
-
-impl UserData {
-    pub fn set_user_data<'r, T>(&'r mut self, user_data: &'r mut T) {
-        unsafe { self.user_data = std::mem::transmute(user_data) }
-    }
-
-    pub fn get_user_data<'r, T>(&self) -> Option<&'r mut T> {
-        if self.user_data.is_null() {
-            None
-        } else {
-            Some(unsafe { std::mem::transmute(self.user_data) })
-        }
-    }
-}
-
-impl Default for UserData {
-    fn default() -> UserData {
-        UserData {
-            user_data: ::std::ptr::null_mut()
-        }
-    }
-}
-
 /// Wrapper for arguments of
-/// [`Sys::set_software_format`](struct.Sys.html#method.set_software_format) and
+/// [`SysBuilder::set_software_format`](struct.SysBuilder.html#method.set_software_format) and
 /// [`Sys::get_software_format`](struct.Sys.html#method.get_software_format).
 pub struct SoftwareFormat
 {
@@ -375,7 +385,7 @@ pub struct AdvancedSettings {
     /// defined by ASIONumChannels) with ASIO channel names.
     pub ASIO_channel_list             : Vec<String>,
     /// [r/w] Optional. Specify 0 to ignore. Pointer to a list of speakers that the ASIO channels
-    /// map to. This can be called after [`Sys::init`](doc/rfmod/struct.Sys.html#method.init) to
+    /// map to. This can be called after [`SysBuilder::init`](doc/rfmod/struct.SysBuilder.html#method.init) to
     /// remap ASIO output.
     pub ASIO_speaker_list             : Vec<::Speaker>,
     /// [r/w] Optional. Specify 0 to ignore. The max number of 3d reverb DSP's in the system. (NOTE:
@@ -420,7 +430,7 @@ pub struct AdvancedSettings {
     /// occlusion changes.
     pub geometry_max_fade_time        : u32,
     /// [r/w] Optional. Specify 0 to ignore. Tells
-    /// [`Sys::init`](doc/rfmod/struct.Sys.html#method.init) to allocate a pool of wavedata/spectrum
+    /// [`SysBuilder::init`](doc/rfmod/struct.SysBuilder.html#method.init) to allocate a pool of wavedata/spectrum
     /// buffers to prevent memory fragmentation, any additional buffers will be allocated normally.
     pub max_spectrum_wave_data_buffers: u32,
     /// [r/w] Optional. Specify 0 to ignore. The delay the music system should allow for loading a
@@ -530,14 +540,16 @@ pub struct CreateSoundexInfo {
     /// desirable to specify only a subset of sounds to be loaded out of the whole file. This is an
     /// array of subsound indices to load into memory when created.
     pub inclusion_list         : Vec<i32>,
-    /// [w] Optional. Specify 0 to ignore. Callback to 'piggyback' on FMOD's read functions and
+    /// [w] Optional. Specify None to ignore. Callback to 'piggyback' on FMOD's read functions and
     /// accept or even write PCM data while FMOD is opening the sound. Used for user sounds created
-    /// with FMOD_OPENUSER or for capturing decoded data as FMOD reads it.
+    /// with FMOD_OPENUSER or for capturing decoded data as FMOD reads it. The buffer given to the
+    /// callback is typed after `format`. The callback is moved into the sound when it is created.
     pub pcm_read_callback      : SoundPcmReadCallback,
     /// [w] Optional. Specify 0 to ignore. Callback for when the user calls a seeking function such
     /// as [`Channel::set_time`](doc/rfmod/struct.Channel.html#method.set_time) or
     /// [`Channel::set_position`](doc/rfmod/struct.Channel.html#method.set_position) within a
-    /// multi-sample sound, and for when it is opened.
+    /// multi-sample sound, and for when it is opened. The callback is moved into the sound when it
+    /// is created.
     pub pcm_set_pos_callback   : SoundPcmSetPosCallback,
     /// [w] Optional. Specify 0 to ignore. Callback for successful completion, or error while
     /// loading a sound that used the FMOD_NONBLOCKING flag. Also called duing seeking, when
@@ -557,11 +569,6 @@ pub struct CreateSoundexInfo {
     /// such as .MID and .IT, this specifies the maximum voice count allowed while playing. .IT
     /// defaults to 64. .MID defaults to 32.
     pub max_polyphony          : i32,
-    /// [w] Optional. Specify 0 to ignore. This is user data to be attached to the sound during
-    /// creation. Access via
-    /// [`Sound::get_user_data`](doc/rfmod/struct.Sound.html#method.get_user_data). Note: This is
-    /// not passed to FMOD_FILE_OPENCALLBACK, that is a different userdata that is file specific.
-    user_data                  : Box<ffi::SoundData>,
     /// [w] Optional. Specify 0 or SoundTypeUnknown to ignore. Instead of scanning all codec types,
     /// use this to speed up loading by making it jump straight to this codec.
     pub suggested_sound_type   : ::SoundType,
@@ -629,7 +636,6 @@ impl Default for CreateSoundexInfo {
             dls_name: String::new(),
             encryption_key: String::new(),
             max_polyphony: 0i32,
-            user_data: Box::new(ffi::SoundData::new()),
             suggested_sound_type: ::SoundType::Unknown,
             user_open: None,
             user_close: None,
@@ -651,7 +657,7 @@ impl Default for CreateSoundexInfo {
 }
 
 impl CreateSoundexInfo {
-    fn convert_to_c(&mut self) -> ffi::FMOD_CREATESOUNDEXINFO {
+    fn convert_to_c(&mut self, user_data: &mut ffi::SoundData) -> ffi::FMOD_CREATESOUNDEXINFO {
         let tmp_dls_name = CString::new(self.dls_name.clone()).unwrap();
         let tmp_encryption_key = CString::new(self.encryption_key.clone()).unwrap();
 
@@ -667,11 +673,11 @@ impl CreateSoundexInfo {
             numsubsounds: self.num_subsounds,
             inclusionlist: self.inclusion_list.as_mut_ptr(),
             inclusionlistnum: self.inclusion_list.len() as i32,
-            pcmreadcallback: match self.pcm_read_callback {
+            pcmreadcallback: match user_data.pcm_read {
                 Some(_) => Some(pcm_read_callback as extern "C" fn(*mut _, *mut _, _) -> _),
                 None => None
             },
-            pcmsetposcallback: match self.pcm_set_pos_callback {
+            pcmsetposcallback: match user_data.pcm_set_pos {
                 Some(_) => Some(pcm_set_pos_callback as extern "C" fn(*mut _, _, _, _) -> _),
                 None => None
             },
@@ -682,12 +688,7 @@ impl CreateSoundexInfo {
             dlsname: tmp_dls_name.as_ptr() as *mut c_char,
             encryptionkey: tmp_encryption_key.as_ptr() as *mut c_char,
             maxpolyphony: self.max_polyphony,
-            userdata: {
-                self.user_data.non_block = self.non_block_callback;
-                self.user_data.pcm_read = self.pcm_read_callback;
-                self.user_data.pcm_set_pos = self.pcm_set_pos_callback;
-                unsafe { ::std::mem::transmute::<&mut ffi::SoundData, *mut c_void>(&mut *self.user_data) }
-            },
+            userdata: user_data as *mut ffi::SoundData as *mut c_void,
             suggestedsoundtype: self.suggested_sound_type,
             useropen: self.user_open,
             userclose: self.user_close,
@@ -709,6 +710,17 @@ impl CreateSoundexInfo {
             nonblockthreadid: self.non_block_thread_id,
         }
     }
+
+    /* the callbacks are moved into the sound's user data, which outlives this structure */
+    fn convert_to_c_with_callbacks(&mut self, sound: &mut Sound) -> ffi::FMOD_CREATESOUNDEXINFO {
+        let user_data = sound::get_user_data(sound);
+
+        user_data.non_block = self.non_block_callback;
+        user_data.pcm_read = self.pcm_read_callback.take();
+        user_data.pcm_set_pos = self.pcm_set_pos_callback.take();
+        user_data.format = self.format;
+        self.convert_to_c(user_data)
+    }
 }
 
 /// When creating a codec, declare one of these and provide the relevant callbacks and name for FMOD
@@ -1047,15 +1059,220 @@ pub fn from_memory_usage_details_ptr(details: ffi::FMOD_MEMORY_USAGE_DETAILS) ->
     }
 }
 
+/// Mix returned by [`Sys::render_offline`](struct.Sys.html#method.render_offline).
+#[derive(Clone, Debug, PartialEq)]
+pub struct RenderedMix {
+    /// Interleaved samples, `channels` per frame.
+    pub samples: Vec<f32>,
+    pub channels: i32,
+    /// Sample rate in hz.
+    pub rate: i32
+}
+
+/* DSP unit appending the mix to a buffer, used to render offline */
+struct MixCapture {
+    /* samples and number of channels of the mix */
+    mix: Arc<Mutex<(Vec<f32>, i32)>>
+}
+
+impl dsp::DspProcessor for MixCapture {
+    fn process(&mut self, input: &[f32], output: &mut [f32], length: u32, in_channels: i32,
+               out_channels: i32) -> ::Status {
+        let (in_channels, out_channels) = (in_channels as usize, out_channels as usize);
+
+        for frame in 0..length as usize {
+            for channel in 0..out_channels {
+                output[frame * out_channels + channel] = if channel < in_channels {
+                    input[frame * in_channels + channel]
+                } else {
+                    0f32
+                };
+            }
+        }
+        match self.mix.lock() {
+            Ok(mut mix) => {
+                mix.0.extend_from_slice(output);
+                mix.1 = out_channels as i32;
+                ::Status::Ok
+            }
+            Err(_) => ::Status::Internal
+        }
+    }
+}
+
+/// Event received by the closure given to
+/// [`Sys::set_callback`](struct.Sys.html#method.set_callback).
+pub enum SystemEvent<'a> {
+    /// The enumerated list of devices has changed.
+    DeviceListChanged,
+    /// An output device has been lost due to control panel parameter changes and FMOD cannot
+    /// automatically recover.
+    DeviceLost,
+    /// A thread has been created, the value is its name.
+    ThreadCreated(&'a str),
+    /// A thread has been destroyed, the value is its name.
+    ThreadDestroyed(&'a str),
+    /// A bad connection was made with [`Dsp::add_input`](struct.Dsp.html#method.add_input).
+    BadDSPConnection {
+        target: Dsp,
+        source: Dsp
+    },
+    /// Too many effects were added, exceeding the maximum tree depth of 128.
+    BadDSPLevel
+}
+
+/* stored as FMOD user data of the system, released with the system */
+struct SysData {
+    /* events can be sent from the mixer and other FMOD threads. The number is incremented each
+       time the callback is set or removed, so that a callback replacing itself isn't restored */
+    callback: Mutex<(SystemCallback, u32)>,
+    /* files are opened from the stream and non blocking threads */
+    file_system: Mutex<Option<Arc<FileSystem>>>,
+    /* lets the Sys objects made from a raw pointer keep the system alive too */
+    owner: Weak<SysHandle>,
+    /* codecs registered by the system, their slots are freed once it is released */
+    codecs: Mutex<Vec<Box<codec::CodecDescription>>>
+}
+
+fn release_file_system_slot(system: *mut ffi::FMOD_SYSTEM) {
+    if let Ok(mut systems) = FILE_SYSTEMS.lock() {
+        for s in systems.iter_mut() {
+            if *s == system as usize {
+                *s = 0;
+            }
+        }
+    }
+}
+
+fn get_sys_data(system: *mut ffi::FMOD_SYSTEM) -> Result<*mut SysData, ::Error> {
+    let mut data = ::std::ptr::null_mut();
+
+    match unsafe { ffi::FMOD_System_GetUserData(system, &mut data) } {
+        ::Status::Ok => Ok(data as *mut SysData),
+        e => Err(::Error::new(e, "FMOD_System_GetUserData"))
+    }
+}
+
+extern "C" fn system_callback(system: *mut ffi::FMOD_SYSTEM, _type: ::SystemCallbackType,
+                              command_data1: *mut c_void,
+                              command_data2: *mut c_void) -> ::Status {
+    /* memory allocation failures are sent with a null system, there is no closure to call */
+    let data = match get_sys_data(system) {
+        Ok(data) if !data.is_null() => data,
+        _ => return ::Status::Ok
+    };
+    let thread_name = |name: *mut c_void| {
+        if name.is_null() {
+            String::new()
+        } else {
+            unsafe { CStr::from_ptr(name as *const c_char) }.to_string_lossy().into_owned()
+        }
+    };
+    let name = match _type {
+        ::SystemCallbackType::ThreadCreated | ::SystemCallbackType::ThreadDestroyed => {
+            thread_name(command_data2)
+        }
+        _ => String::new()
+    };
+    let event = match _type {
+        ::SystemCallbackType::DeviceListChanged => SystemEvent::DeviceListChanged,
+        ::SystemCallbackType::DeviceLost => SystemEvent::DeviceLost,
+        ::SystemCallbackType::ThreadCreated => SystemEvent::ThreadCreated(&name),
+        ::SystemCallbackType::ThreadDestroyed => SystemEvent::ThreadDestroyed(&name),
+        ::SystemCallbackType::BadDSPConnection => SystemEvent::BadDSPConnection {
+            target: ffi::FFI::wrap(command_data1 as *mut ffi::FMOD_DSP),
+            source: ffi::FFI::wrap(command_data2 as *mut ffi::FMOD_DSP)
+        },
+        ::SystemCallbackType::BadDSPLevel => SystemEvent::BadDSPLevel,
+        _ => return ::Status::Ok
+    };
+    /* taken out of the lock so the closure can call Sys::set_callback or remove_callback */
+    let (callback, generation) = match unsafe { (*data).callback.lock() } {
+        Ok(mut slot) => (slot.0.take(), slot.1),
+        Err(_) => return ::Status::Ok
+    };
+    let mut callback = match callback {
+        Some(callback) => callback,
+        None => return ::Status::Ok
+    };
+    let ret = callback(&ffi::FFI::wrap(system), event);
+
+    if let Ok(mut slot) = unsafe { (*data).callback.lock() } {
+        if slot.1 == generation {
+            slot.0 = Some(callback);
+        }
+    }
+    ret
+}
+
+/* the FMOD system, closed and released once the last handle keeping it alive is dropped */
+pub struct SysHandle {
+    system: *mut ffi::FMOD_SYSTEM
+}
+
+/* only used to release the system, from the thread dropping the last reference */
+unsafe impl Send for SysHandle {}
+unsafe impl Sync for SysHandle {}
+
+impl SysHandle {
+    fn release(&mut self) -> Result<(), ::Error> {
+        if self.system.is_null() {
+            return Ok(());
+        }
+        let data = get_sys_data(self.system)?;
+
+        unsafe {
+            match ffi::FMOD_System_Close(self.system) {
+                ::Status::Ok => {}
+                e => return Err(::Error::new(e, "FMOD_System_Close"))
+            }
+            match ffi::FMOD_System_Release(self.system) {
+                ::Status::Ok => {
+                    release_file_system_slot(self.system);
+                    self.system = ::std::ptr::null_mut();
+                    if !data.is_null() {
+                        drop(Box::from_raw(data));
+                    }
+                    Ok(())
+                }
+                e => Err(::Error::new(e, "FMOD_System_Release"))
+            }
+        }
+    }
+}
+
+impl Drop for SysHandle {
+    fn drop(&mut self) {
+        let _ = self.release();
+    }
+}
+
+/* reference keeping the system alive, given to the objects created from it */
+pub fn get_owner(sys: &Sys) -> Option<Arc<SysHandle>> {
+    match sys.owner {
+        Some(ref owner) => Some(owner.clone()),
+        None => match get_sys_data(sys.system) {
+            Ok(data) if !data.is_null() => unsafe { (*data).owner.upgrade() },
+            _ => None
+        }
+    }
+}
+
 /// FMOD System Object
+///
+/// The system is shared by the `Sys` objects returned by
+/// [`SysBuilder::init`](struct.SysBuilder.html#method.init) and their clones, and by the
+/// sounds, channels, DSPs, DSP connections, channel groups, sound groups, reverbs and
+/// geometries obtained from it. It is closed and released when the last of them is dropped, so
+/// none of them can outlive it.
 pub struct Sys {
     system: *mut ffi::FMOD_SYSTEM,
-    is_first: bool
+    owner: Option<Arc<SysHandle>>
 }
 
 impl ffi::FFI<ffi::FMOD_SYSTEM> for Sys {
     fn wrap(system: *mut ffi::FMOD_SYSTEM) -> Sys {
-        Sys {system: system, is_first: false}
+        Sys {system: system, owner: None}
     }
 
     fn unwrap(s: &Sys) -> *mut ffi::FMOD_SYSTEM {
@@ -1063,221 +1280,477 @@ impl ffi::FFI<ffi::FMOD_SYSTEM> for Sys {
     }
 }
 
+/// Returns a new reference to the system, keeping it alive even when cloned from a
+/// [`Borrowed`](struct.Borrowed.html) system.
+impl Clone for Sys {
+    fn clone(&self) -> Sys {
+        Sys {system: self.system, owner: get_owner(self)}
+    }
+}
+
 impl Drop for Sys {
     fn drop(&mut self) {
-        self.release();
+        let _ = self.release();
     }
 }
 
 impl Sys {
-    /* the first one created has to be the last one released */
-    pub fn new() -> Result<Sys, ::Status> {
+    /* the system isn't initialized yet, it is only given to users through SysBuilder */
+    fn new() -> Result<Sys, ::Error> {
         let mut tmp = ::std::ptr::null_mut();
 
         match unsafe { ffi::FMOD_System_Create(&mut tmp) } {
-            ::Status::Ok => Ok(Sys{system: tmp, is_first: true}),
-            err => Err(err)
+            ::Status::Ok => {}
+            err => return Err(::Error::new(err, "FMOD_System_Create"))
+        }
+        let owner = Arc::new(SysHandle { system: tmp });
+        let data = Box::into_raw(Box::new(SysData {
+            callback: Mutex::new((None, 0)),
+            file_system: Mutex::new(None),
+            owner: Arc::downgrade(&owner),
+            codecs: Mutex::new(Vec::new())
+        }));
+
+        match unsafe { ffi::FMOD_System_SetUserData(tmp, data as *mut c_void) } {
+            ::Status::Ok => Ok(Sys{system: tmp, owner: Some(owner)}),
+            e => {
+                unsafe { drop(Box::from_raw(data)) };
+                Err(::Error::new(e, "FMOD_System_SetUserData"))
+            }
         }
     }
 
-    pub fn init(&self) -> ::Status {
-        unsafe { ffi::FMOD_System_Init(self.system, 1, ::INIT_NORMAL, ::std::ptr::null_mut()) }
+    /// Creates a system which can only be configured with the settings FMOD accepts before
+    /// initialization, [`SysBuilder::init`](struct.SysBuilder.html#method.init) then returns the
+    /// initialized system.
+    pub fn builder() -> Result<SysBuilder, ::Error> {
+        Ok(SysBuilder { sys: Sys::new()? })
     }
 
-    pub fn init_with_parameters(&self, max_channels: i32, InitFlag(flag): InitFlag) -> ::Status {
-        unsafe { ffi::FMOD_System_Init(self.system, max_channels, flag, ::std::ptr::null_mut()) }
-    }
+    /// Mixes `duration` of audio as fast as possible and returns the mix. The output has to be
+    /// `OutputType::NoSoundNRT` or `OutputType::WAVWriterNRT`, see
+    /// [`SysBuilder::set_output`](struct.SysBuilder.html#method.set_output).
+    pub fn render_offline(&self, duration: Duration) -> Result<RenderedMix, ::Error> {
+        match self.get_output()? {
+            ::OutputType::NoSoundNRT | ::OutputType::WAVWriterNRT => {}
+            _ => return Err(::Error::new(::Status::Unsupported, "Sys::render_offline"))
+        }
+        let rate = self.get_software_format()?.sample_rate;
+        let frames = (duration.as_secs() * rate as u64 +
+                      duration.subsec_nanos() as u64 * rate as u64 / 1_000_000_000) as usize;
+        let capture = Arc::new(Mutex::new((Vec::new(), 0i32)));
+        let dsp = self.create_DSP_from_processor("rfmod capture", &[],
+                                                 Box::new(MixCapture { mix: capture.clone() }))?;
+
+        self.add_DSP(&dsp)?;
+        let result = self.mix_frames(&capture, frames);
+        let removed = dsp.remove();
 
-    pub fn update(&self) -> ::Status {
-        unsafe { ffi::FMOD_System_Update(self.system) }
+        result?;
+        removed?;
+        let (mut samples, channels) = match capture.lock() {
+            Ok(mut mix) => ::std::mem::take(&mut *mix),
+            Err(_) => return Err(::Error::new(::Status::Internal, "Sys::render_offline"))
+        };
+
+        samples.truncate(frames * channels as usize);
+        Ok(RenderedMix {
+            samples: samples,
+            channels: channels,
+            rate: rate
+        })
     }
 
-    pub fn release(&mut self) -> ::Status {
-        if self.is_first && !self.system.is_null() {
-            unsafe {
-                match match ffi::FMOD_System_Close(self.system) {
-                    ::Status::Ok => ffi::FMOD_System_Release(self.system),
-                    e => e
-                } {
-                    ::Status::Ok => {
-                        self.system = ::std::ptr::null_mut();
-                       ::Status::Ok
+    /// Same as [`Sys::render_offline`](#method.render_offline) but writes the mix to a WAV file.
+    pub fn render_to_wav(&self, file_name: &str, duration: Duration) -> io::Result<()> {
+        let mix = self.render_offline(duration)?;
+        let mut file = File::create(file_name)?;
+
+        sound::write_wav_data(&mut file, ::SoundFormat::PCMFloat, mix.channels, 32, mix.rate,
+                              (mix.samples.len() * 4) as u32, |output| {
+            for &sample in &mix.samples {
+                output.write_f32::<LittleEndian>(sample)?;
+            }
+            Ok(())
+        })
+    }
+
+    /* updates the system until `capture` holds `frames` frames, each update mixing one DSP
+       buffer. Fails if the capture DSP doesn't get mixed, when it is bypassed for example */
+    fn mix_frames(&self, capture: &Mutex<(Vec<f32>, i32)>, frames: usize) -> Result<(), ::Error> {
+        let (buffer_length, _) = self.get_DSP_buffer_size()?;
+        let max_updates = 2 * (frames / ::std::cmp::max(buffer_length as usize, 1) + 1) + 8;
+
+        for _ in 0..max_updates {
+            self.update()?;
+            match capture.lock() {
+                Ok(mix) => {
+                    if mix.1 > 0 && mix.0.len() >= frames * mix.1 as usize {
+                        return Ok(());
                     }
-                    e => e
                 }
-                
+                Err(_) => return Err(::Error::new(::Status::Internal, "Sys::render_offline"))
             }
-        } else {
-            ::Status::Ok
+        }
+        Err(::Error::new(::Status::NotReady, "Sys::render_offline"))
+    }
+
+    pub fn update(&self) -> Result<(), ::Error> {
+        match unsafe { ffi::FMOD_System_Update(self.system) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_System_Update"))
+        }
+    }
+
+    /// Drops the reference of this object to the system. The system is closed and released if
+    /// nothing else keeps it alive, otherwise it is once the last object using it is released.
+    pub fn release(&mut self) -> Result<(), ::Error> {
+        let owner = match self.owner.take() {
+            Some(owner) => owner,
+            None => return Ok(())
+        };
+
+        self.system = ::std::ptr::null_mut();
+        match Arc::try_unwrap(owner) {
+            Ok(mut handle) => handle.release(),
+            Err(_) => Ok(())
         }
     }
 
     /// If music is empty, null is sent
     pub fn create_sound(&self, music: &str, options: Option<Mode>,
-                        exinfo: Option<&mut CreateSoundexInfo>) -> Result<Sound, ::Status> {
-        let mut sound = sound::from_ptr_first(::std::ptr::null_mut());
-        let op = match options {
-            Some(Mode(t)) => t,
-            None => ::SOFTWARE | ::LOOP_OFF | ::_2D | ::CREATESTREAM
-        };
-        let ex = match exinfo {
-            Some(e) => {
-                let user_data = sound::get_user_data(&mut sound);
-                user_data.non_block = e.non_block_callback;
-                user_data.pcm_read = e.pcm_read_callback;
-                user_data.pcm_set_pos = e.pcm_set_pos_callback;
-                unsafe {
-                    user_data.user_data =
-                        ::std::mem::transmute::<&mut ffi::SoundData, *mut c_void>(
-                            &mut *e.user_data);
-                }
-                &mut e.convert_to_c() as *mut ffi::FMOD_CREATESOUNDEXINFO
-            },
+                        exinfo: Option<&mut CreateSoundexInfo>) -> Result<Sound, ::Error> {
+        let mut sound = sound::from_ptr_first(::std::ptr::null_mut(), get_owner(self));
+        let op = options.unwrap_or(::SOFTWARE | ::LOOP_OFF | ::_2D | ::CREATESTREAM);
+
+        op.validate()?;
+        let mut c_exinfo = exinfo.map(|e| e.convert_to_c_with_callbacks(&mut sound));
+        let ex = match c_exinfo {
+            Some(ref mut e) => e as *mut ffi::FMOD_CREATESOUNDEXINFO,
             None => ::std::ptr::null_mut()
         };
 
         match if music.len() > 0 {
             let music_cstring = CString::new(music).unwrap();
             unsafe { ffi::FMOD_System_CreateSound(self.system,
-                                                  music_cstring.as_ptr() as *const c_char, op, ex,
-                                                  sound::get_fffi(&mut sound)) }
+                                                  music_cstring.as_ptr() as *const c_char,
+                                                  op.bits(), ex, sound::get_fffi(&mut sound)) }
         } else {
-            unsafe { ffi::FMOD_System_CreateSound(self.system, ::std::ptr::null(), op, ex,
+            unsafe { ffi::FMOD_System_CreateSound(self.system, ::std::ptr::null(), op.bits(), ex,
                                                   sound::get_fffi(&mut sound)) }
         } {
             ::Status::Ok => {
                 Ok(sound)
             },
-            e => Err(e)
+            e => Err(::Error::new(e, "FMOD_System_CreateSound"))
+        }
+    }
+
+    /// Opens the sound with `NONBLOCKING` added to the mode and returns a future completing once
+    /// it is loaded, so it can be awaited from any executor. The `non_block_callback` of
+    /// `exinfo` is still called.
+    pub fn create_sound_async(&self, music: &str, options: Option<Mode>,
+                              exinfo: Option<&mut CreateSoundexInfo>) -> sound::SoundFuture {
+        let state = Arc::new(sound::LoadState::new());
+        let mut sound = sound::from_ptr_first(::std::ptr::null_mut(), get_owner(self));
+        let op = options.unwrap_or(::SOFTWARE | ::LOOP_OFF | ::_2D | ::CREATESTREAM)
+                 | ::NONBLOCKING;
+
+        if let Err(e) = op.validate() {
+            return sound::new_future(Err(e), state);
+        }
+        let mut default_exinfo : CreateSoundexInfo = Default::default();
+        let mut c_exinfo = exinfo.unwrap_or(&mut default_exinfo)
+                                 .convert_to_c_with_callbacks(&mut sound);
+
+        /* the user data is given to the sound by FMOD before the loading thread starts */
+        sound::get_user_data(&mut sound).load = Some(state.clone());
+        c_exinfo.nonblockcallback = Some(non_block_callback as extern "C" fn(*mut _, _) -> _);
+        let music_cstring = match CString::new(music) {
+            Ok(m) => m,
+            Err(_) => {
+                return sound::new_future(Err(::Error::new(::Status::InvalidParam,
+                                                          "Sys::create_sound_async")), state)
+            }
+        };
+
+        match unsafe { ffi::FMOD_System_CreateSound(self.system, music_cstring.as_ptr(),
+                                                    op.bits(), &mut c_exinfo,
+                                                    sound::get_fffi(&mut sound)) } {
+            ::Status::Ok => sound::new_future(Ok(sound), state),
+            e => sound::new_future(Err(::Error::new(e, "FMOD_System_CreateSound")), state)
         }
     }
 
     pub fn create_stream(&self, music: &str, options: Option<Mode>,
-                         exinfo: Option<&mut CreateSoundexInfo>) -> Result<Sound, ::Status> {
-        let mut sound = sound::from_ptr_first(::std::ptr::null_mut());
-        let op = match options {
-            Some(Mode(t)) => t,
-            None => ::SOFTWARE | ::LOOP_OFF | ::_2D | ::CREATESTREAM
-        };
-        let ex = match exinfo {
-            Some(e) => {
-                let user_data = sound::get_user_data(&mut sound);
-                user_data.non_block = e.non_block_callback;
-                user_data.pcm_read = e.pcm_read_callback;
-                user_data.pcm_set_pos = e.pcm_set_pos_callback;
-                unsafe {
-                    user_data.user_data =
-                        ::std::mem::transmute::<&mut ffi::SoundData, *mut c_void>(
-                            &mut *e.user_data);
-                }
-                &mut e.convert_to_c() as *mut ffi::FMOD_CREATESOUNDEXINFO
-            },
+                         exinfo: Option<&mut CreateSoundexInfo>) -> Result<Sound, ::Error> {
+        let mut sound = sound::from_ptr_first(::std::ptr::null_mut(), get_owner(self));
+        let op = options.unwrap_or(::SOFTWARE | ::LOOP_OFF | ::_2D | ::CREATESTREAM);
+
+        op.validate()?;
+        let mut c_exinfo = exinfo.map(|e| e.convert_to_c_with_callbacks(&mut sound));
+        let ex = match c_exinfo {
+            Some(ref mut e) => e as *mut ffi::FMOD_CREATESOUNDEXINFO,
             None => ::std::ptr::null_mut()
         };
 
         match if music.len() > 0 {
             let music_cstring = CString::new(music).unwrap();
             unsafe { ffi::FMOD_System_CreateStream(self.system,
-                                                   music_cstring.as_ptr() as *const c_char, op, ex,
-                                                   sound::get_fffi(&mut sound)) }
+                                                   music_cstring.as_ptr() as *const c_char,
+                                                   op.bits(), ex, sound::get_fffi(&mut sound)) }
         } else {
-            unsafe { ffi::FMOD_System_CreateStream(self.system, ::std::ptr::null(), op, ex,
+            unsafe { ffi::FMOD_System_CreateStream(self.system, ::std::ptr::null(), op.bits(), ex,
                                                    sound::get_fffi(&mut sound)) }
         } {
             ::Status::Ok => Ok(sound),
-            err => Err(err)
+            err => Err(::Error::new(err, "FMOD_System_CreateStream"))
         }
     }
 
+    /// Creates a sound from a copy of `data`, which contains a file in any format FMOD can open.
+    /// `OPENMEMORY` is added to the mode and the length of `exinfo` is set to the one of `data`.
+    pub fn create_sound_from_memory(&self, data: &[u8], options: Option<Mode>,
+                                    exinfo: Option<&mut CreateSoundexInfo>)
+                                    -> Result<Sound, ::Error> {
+        let op = options.unwrap_or(::SOFTWARE | ::LOOP_OFF | ::_2D | ::CREATESTREAM);
+
+        self.create_sound_from_ptr(data, (op & !::OPENMEMORY_POINT) | ::OPENMEMORY, exinfo)
+    }
+
+    /// Same as [`Sys::create_sound_from_memory`](#method.create_sound_from_memory) but uses
+    /// `OPENMEMORY_POINT`: FMOD plays directly from `data` instead of copying it, so `data` has to
+    /// outlive the returned sound.
+    pub fn create_sound_from_memory_point<'a>(&self, data: &'a [u8], options: Option<Mode>,
+                                              exinfo: Option<&mut CreateSoundexInfo>)
+                                              -> Result<sound::MemorySound<'a>, ::Error> {
+        let op = options.unwrap_or(::SOFTWARE | ::LOOP_OFF | ::_2D | ::CREATESTREAM);
+        let sound = self.create_sound_from_ptr(data,
+                                               (op & !::OPENMEMORY) | ::OPENMEMORY_POINT,
+                                               exinfo)?;
+
+        Ok(sound::from_sound_with_data(sound))
+    }
+
+    /// Creates a sound from a copy of interleaved PCM samples, the format of the sound is given
+    /// by the type of the samples. `OPENMEMORY` and `OPENRAW` are added to the mode, which is
+    /// `SOFTWARE | LOOP_OFF | _2D | CREATESAMPLE` by default.
+    pub fn create_sound_from_pcm<T: sound::Sample>(&self, data: &[T], channels: i32,
+                                                   frequency: i32, options: Option<Mode>)
+                                                   -> Result<Sound, ::Error> {
+        let op = options.unwrap_or(::SOFTWARE | ::LOOP_OFF | ::_2D | ::CREATESAMPLE);
+        let mut exinfo : CreateSoundexInfo = Default::default();
+        let bytes = unsafe {
+            ::std::slice::from_raw_parts(data.as_ptr() as *const u8,
+                                         data.len() * mem::size_of::<T>())
+        };
+
+        exinfo.num_channels = channels;
+        exinfo.default_frequency = frequency;
+        exinfo.format = T::format();
+        self.create_sound_from_ptr(bytes, (op & !::OPENMEMORY_POINT) | ::OPENMEMORY | ::OPENRAW,
+                                   Some(&mut exinfo))
+    }
+
+    fn create_sound_from_ptr(&self, data: &[u8], op: Mode,
+                             exinfo: Option<&mut CreateSoundexInfo>) -> Result<Sound, ::Error> {
+        op.validate()?;
+        let mut sound = sound::from_ptr_first(::std::ptr::null_mut(), get_owner(self));
+        let mut default_exinfo : CreateSoundexInfo = Default::default();
+        let exinfo = match exinfo {
+            Some(e) => e,
+            None => &mut default_exinfo
+        };
+
+        exinfo.length = data.len() as u32;
+        let mut c_exinfo = exinfo.convert_to_c_with_callbacks(&mut sound);
+
+        match unsafe { ffi::FMOD_System_CreateSound(self.system, data.as_ptr() as *const c_char,
+                                                    op.bits(), &mut c_exinfo,
+                                                    sound::get_fffi(&mut sound)) } {
+            ::Status::Ok => Ok(sound),
+            e => Err(::Error::new(e, "FMOD_System_CreateSound"))
+        }
+    }
+
+    /// Opens `music` with `OPENONLY` to decode it through a
+    /// [`SoundDecoder`](struct.SoundDecoder.html) instead of playing it.
+    pub fn create_decoder(&self, music: &str, options: Option<Mode>,
+                          exinfo: Option<&mut CreateSoundexInfo>)
+                          -> Result<decoder::SoundDecoder, ::Error> {
+        let op = options.unwrap_or(::SOFTWARE | ::_2D | ::ACCURATETIME);
+        let sound = self.create_sound(music, Some((op & !::CREATESTREAM) | ::OPENONLY),
+                                      exinfo)?;
+
+        decoder::from_sound(sound)
+    }
+
     pub fn create_channel_group(&self, group_name: &str)
-                                -> Result<channel_group::ChannelGroup, ::Status> {
+                                -> Result<channel_group::ChannelGroup, ::Error> {
         let mut channel_group = ::std::ptr::null_mut();
             let tmp_group_name = CString::new(group_name).unwrap();
 
         match unsafe { ffi::FMOD_System_CreateChannelGroup(self.system,
                                                           tmp_group_name.as_ptr() as *const c_char,
                                                           &mut channel_group) } {
-            ::Status::Ok => Ok(ffi::FFI::wrap(channel_group)),
-            e => Err(e)
+            ::Status::Ok => Ok(channel_group::from_ptr_first(channel_group, get_owner(self))),
+            e => Err(::Error::new(e, "FMOD_System_CreateChannelGroup"))
         }
     }
 
     pub fn create_sound_group(&self, group_name: &str)
-                              -> Result<sound_group::SoundGroup, ::Status> {
+                              -> Result<sound_group::SoundGroup, ::Error> {
         let mut sound_group = ::std::ptr::null_mut();
             let tmp_group_name = CString::new(group_name).unwrap();
 
         match unsafe { ffi::FMOD_System_CreateSoundGroup(self.system,
                                                          tmp_group_name.as_ptr() as *const c_char,
                                                          &mut sound_group) } {
-            ::Status::Ok => Ok(ffi::FFI::wrap(sound_group)),
-            e => Err(e)
+            ::Status::Ok => Ok(sound_group::from_ptr_first(sound_group, get_owner(self))),
+            e => Err(::Error::new(e, "FMOD_System_CreateSoundGroup"))
         }
     }
 
-    pub fn create_reverb(&self) -> Result<reverb::Reverb, ::Status>{
+    pub fn create_reverb(&self) -> Result<reverb::Reverb, ::Error>{
         let mut t_reverb = ::std::ptr::null_mut();
 
         match unsafe { ffi::FMOD_System_CreateReverb(self.system, &mut t_reverb) } {
-            ::Status::Ok => Ok(ffi::FFI::wrap(t_reverb)),
-            e => Err(e)
+            ::Status::Ok => Ok(reverb::from_ptr_first(t_reverb, get_owner(self))),
+            e => Err(::Error::new(e, "FMOD_System_CreateReverb"))
         }
     }
 
-    pub fn create_DSP(&self) -> Result<dsp::Dsp, ::Status> {
+    pub fn create_DSP(&self) -> Result<dsp::Dsp, ::Error> {
         let mut t_dsp = ::std::ptr::null_mut();
 
         match unsafe { ffi::FMOD_System_CreateDSP(self.system, ::std::ptr::null_mut(),
                                                   &mut t_dsp) } {
-            ::Status::Ok => Ok(dsp::from_ptr_first(t_dsp)),
-            e => Err(e)
+            ::Status::Ok => Ok(dsp::from_ptr_first(t_dsp, get_owner(self))),
+            e => Err(::Error::new(e, "FMOD_System_CreateDSP"))
         }
     }
 
     pub fn create_DSP_with_description(&self, description: &mut dsp::DspDescription)
-                                       -> Result<dsp::Dsp, ::Status> {
+                                       -> Result<dsp::Dsp, ::Error> {
         let mut t_dsp = ::std::ptr::null_mut();
         let mut t_description = dsp::get_description_ffi(description);
 
         match unsafe { ffi::FMOD_System_CreateDSP(self.system, &mut t_description, &mut t_dsp) } {
-            ::Status::Ok => Ok(dsp::from_ptr_first(t_dsp)),
-            e => Err(e)
+            ::Status::Ok => Ok(dsp::from_ptr_first(t_dsp, get_owner(self))),
+            e => Err(::Error::new(e, "FMOD_System_CreateDSP"))
         }
     }
 
-    pub fn create_DSP_by_type(&self, _type: ::DspType) -> Result<dsp::Dsp, ::Status> {
+    /// Creates a DSP unit running `processor`, which is dropped when the unit is released.
+    /// `parameters` describes the parameters reachable through
+    /// [`Dsp::set_parameter`](struct.Dsp.html#method.set_parameter), in index order.
+    pub fn create_DSP_from_processor(&self, name: &str, parameters: &[dsp::DspParameterDesc],
+                                     processor: Box<dyn dsp::DspProcessor>)
+                                     -> Result<dsp::Dsp, ::Error> {
         let mut t_dsp = ::std::ptr::null_mut();
+        let mut t_description = dsp::get_processor_description_ffi(name, parameters, processor);
 
-        match unsafe { ffi::FMOD_System_CreateDSPByType(self.system, _type, &mut t_dsp) } {
-            ::Status::Ok => Ok(dsp::from_ptr_first(t_dsp)),
-            e => Err(e)
+        match unsafe { ffi::FMOD_System_CreateDSP(self.system, &mut t_description, &mut t_dsp) } {
+            ::Status::Ok => Ok(dsp::from_ptr_first(t_dsp, get_owner(self))),
+            e => {
+                dsp::release_processor_description(&mut t_description);
+                Err(::Error::new(e, "FMOD_System_CreateDSP"))
+            }
         }
     }
 
-    pub fn set_output(&self, output_type: ::OutputType) -> ::Status {
-        unsafe { ffi::FMOD_System_SetOutput(self.system, output_type) }
+    pub fn create_DSP_by_type(&self, _type: ::DspType) -> Result<dsp::Dsp, ::Error> {
+        let mut t_dsp = ::std::ptr::null_mut();
+
+        match unsafe { ffi::FMOD_System_CreateDSPByType(self.system, _type, &mut t_dsp) } {
+            ::Status::Ok => Ok(dsp::from_ptr_first(t_dsp, get_owner(self))),
+            e => Err(::Error::new(e, "FMOD_System_CreateDSPByType"))
+        }
     }
 
-    pub fn get_output(&self) -> Result<::OutputType, ::Status> {
+    pub fn get_output(&self) -> Result<::OutputType, ::Error> {
         let mut output_type = ::OutputType::AutoDetect;
         
         match unsafe { ffi::FMOD_System_GetOutput(self.system, &mut output_type) } {
             ::Status::Ok => Ok(output_type),
-            e => Err(e)
+            e => Err(::Error::new(e, "FMOD_System_GetOutput"))
+        }
+    }
+
+    /// Sets a closure called on the system events, replacing the previous one. The closure can
+    /// replace or remove itself. Events are sent from FMOD threads, and the ones sent by another
+    /// thread while the closure is running are ignored.
+    /// [`SystemCallbackType::MemoryAllocationFailed`](enum.SystemCallbackType.html) isn't
+    /// related to any system by FMOD and therefore isn't received.
+    pub fn set_callback<F>(&self, callback: F) -> Result<(), ::Error>
+        where F: FnMut(&Sys, SystemEvent) -> ::Status + Send + 'static {
+        let data = self.get_or_create_sys_data()?;
+
+        match unsafe { (*data).callback.lock() } {
+            Ok(mut slot) => {
+                slot.0 = Some(Box::new(callback));
+                slot.1 = slot.1.wrapping_add(1);
+            }
+            Err(_) => return Err(::Error::new(::Status::Internal, "Sys::set_callback"))
+        }
+        let callback = system_callback as extern "C" fn(*mut _, _, *mut _, *mut _) -> _;
+
+        match unsafe { ffi::FMOD_System_SetCallback(self.system, Some(callback)) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_System_SetCallback"))
+        }
+    }
+
+    /// Removes the closure set with [`Sys::set_callback`](#method.set_callback).
+    pub fn remove_callback(&self) -> Result<(), ::Error> {
+        match unsafe { ffi::FMOD_System_SetCallback(self.system, None) } {
+            ::Status::Ok => {}
+            e => return Err(::Error::new(e, "FMOD_System_SetCallback"))
+        }
+        let data = get_sys_data(self.system)?;
+
+        if !data.is_null() {
+            if let Ok(mut slot) = unsafe { (*data).callback.lock() } {
+                slot.0 = None;
+                slot.1 = slot.1.wrapping_add(1);
+            }
         }
+        Ok(())
     }
 
-    pub fn get_num_drivers(&self) -> Result<i32, ::Status> {
+    fn get_or_create_sys_data(&self) -> Result<*mut SysData, ::Error> {
+        let data = get_sys_data(self.system)?;
+
+        if !data.is_null() {
+            return Ok(data);
+        }
+        let data = Box::into_raw(Box::new(SysData {
+            callback: Mutex::new((None, 0)),
+            file_system: Mutex::new(None),
+            owner: Weak::new(),
+            codecs: Mutex::new(Vec::new())
+        }));
+
+        match unsafe { ffi::FMOD_System_SetUserData(self.system, data as *mut c_void) } {
+            ::Status::Ok => Ok(data),
+            e => {
+                unsafe { drop(Box::from_raw(data)) };
+                Err(::Error::new(e, "FMOD_System_SetUserData"))
+            }
+        }
+    }
+
+    pub fn get_num_drivers(&self) -> Result<i32, ::Error> {
         let mut num_drivers = 0i32;
 
         match unsafe { ffi::FMOD_System_GetNumDrivers(self.system,
                                                       &mut num_drivers as *mut c_int) } {
             ::Status::Ok => Ok(num_drivers),
-            e => Err(e)
+            e => Err(::Error::new(e, "FMOD_System_GetNumDrivers"))
         }
     }
 
-    pub fn get_driver_info(&self, id: i32, name_len: usize) -> Result<(Guid, String), ::Status> {
+    pub fn get_driver_info(&self, id: i32, name_len: usize) -> Result<(Guid, String), ::Error> {
         let mut c = Vec::with_capacity(name_len + 1);
         let mut guid = ffi::FMOD_GUID {
                            Data1: 0,
@@ -1299,11 +1772,11 @@ impl Sys {
                                     data3: guid.Data3,
                                     data4: guid.Data4,
                                 }, String::from_utf8(c).unwrap())),
-            e => Err(e),
+            e => Err(::Error::new(e, "FMOD_System_GetDriverInfo")),
         }
     }
 
-    pub fn get_driver_caps(&self, id: i32) -> Result<(FmodCaps, i32, ::SpeakerMode), ::Status> {
+    pub fn get_driver_caps(&self, id: i32) -> Result<(FmodCaps, i32, ::SpeakerMode), ::Error> {
         let mut fmod_caps = 0u32;
         let mut speaker_mode = ::SpeakerMode::Raw;
         let mut control_panel_output_rate = 0i32;
@@ -1312,28 +1785,20 @@ impl Sys {
                                                       &mut control_panel_output_rate as *mut c_int,
                                                       &mut speaker_mode) } {
             ::Status::Ok => Ok((FmodCaps(fmod_caps), control_panel_output_rate, speaker_mode)),
-            e => Err(e),
+            e => Err(::Error::new(e, "FMOD_System_GetDriverCaps")),
         }
     }
 
-    pub fn set_driver(&self, driver: i32) -> ::Status {
-        unsafe { ffi::FMOD_System_SetDriver(self.system, driver as c_int) }
-    }
-
-    pub fn get_driver(&self) -> Result<i32, ::Status> {
+    pub fn get_driver(&self) -> Result<i32, ::Error> {
         let mut driver = 0i32;
 
         match unsafe { ffi::FMOD_System_GetDriver(self.system, &mut driver as *mut c_int) } {
             ::Status::Ok => Ok(driver),
-            e => Err(e),
+            e => Err(::Error::new(e, "FMOD_System_GetDriver")),
         }
     }
 
-    pub fn set_hardware_channels(&self, num_hardware_channels: i32) -> ::Status {
-        unsafe { ffi::FMOD_System_SetHardwareChannels(self.system, num_hardware_channels as c_int) }
-    }
-
-    pub fn get_hardware_channels(&self) -> Result<i32, ::Status> {
+    pub fn get_hardware_channels(&self) -> Result<i32, ::Error> {
         let mut num_hardware_channels = 0i32;
 
         match unsafe {
@@ -1341,15 +1806,11 @@ impl Sys {
                                                  &mut num_hardware_channels as *mut c_int)
         } {
             ::Status::Ok => Ok(num_hardware_channels),
-            e => Err(e),
+            e => Err(::Error::new(e, "FMOD_System_GetHardwareChannels")),
         }
     }
 
-    pub fn set_software_channels(&self, num_software_channels: i32) -> ::Status {
-        unsafe { ffi::FMOD_System_SetSoftwareChannels(self.system, num_software_channels as c_int) }
-    }
-
-    pub fn get_software_channels(&self) -> Result<i32, ::Status> {
+    pub fn get_software_channels(&self) -> Result<i32, ::Error> {
         let mut num_software_channels = 0i32;
 
         match unsafe {
@@ -1357,21 +1818,11 @@ impl Sys {
                                                  &mut num_software_channels as *mut c_int)
         } {
             ::Status::Ok => Ok(num_software_channels),
-            e => Err(e),
+            e => Err(::Error::new(e, "FMOD_System_GetSoftwareChannels")),
         }
     }
 
-    pub fn set_software_format(&self, sample_rate: i32, format: ::SoundFormat,
-                               num_output_channels: i32, max_input_channels: i32,
-                               resample_method: ::DspResampler) -> ::Status {
-        unsafe {
-            ffi::FMOD_System_SetSoftwareFormat(self.system, sample_rate as c_int, format,
-                                               num_output_channels as c_int,
-                                               max_input_channels as c_int, resample_method)
-        }
-    }
-
-    pub fn get_software_format(&self) -> Result<SoftwareFormat, ::Status> {
+    pub fn get_software_format(&self) -> Result<SoftwareFormat, ::Error> {
         let mut t = SoftwareFormat {
             sample_rate: 0,
             format: ::SoundFormat::None,
@@ -1390,16 +1841,11 @@ impl Sys {
                                                           &mut t.bits as *mut c_int)
         } {
             ::Status::Ok => Ok(t),
-            e => Err(e),
+            e => Err(::Error::new(e, "FMOD_System_GetSoftwareFormat")),
         }
     }
 
-    pub fn set_DSP_buffer_size(&self, buffer_length: u32, num_buffers: i32) -> ::Status {
-        unsafe { ffi::FMOD_System_SetDSPBufferSize(self.system, buffer_length as c_uint,
-                                                   num_buffers as c_int) }
-    }
-
-    pub fn get_DSP_buffer_size(&self) -> Result<(u32, i32), ::Status> {
+    pub fn get_DSP_buffer_size(&self) -> Result<(u32, i32), ::Error> {
         let mut buffer_length = 0u32;
         let mut num_buffers = 0i32;
 
@@ -1407,50 +1853,11 @@ impl Sys {
                                                          &mut buffer_length as *mut c_uint,
                                                          &mut num_buffers as *mut c_int) } {
             ::Status::Ok => Ok((buffer_length, num_buffers)),
-            e => Err(e),
+            e => Err(::Error::new(e, "FMOD_System_GetDSPBufferSize")),
         }
     }
 
-    pub fn set_advanced_settings(&self, settings: &mut AdvancedSettings) -> ::Status {
-        let mut converted_c_char: Vec<*const c_char> =
-            (0..settings.ASIO_channel_list.len()).map(|pos| {
-            settings.ASIO_channel_list[pos].as_ptr() as *const c_char
-        }).collect();
-        let deb_log_filename = CString::new(settings.debug_log_filename.clone()).unwrap();
-        let mut advanced_settings = ffi::FMOD_ADVANCEDSETTINGS{
-            cbsize: mem::size_of::<ffi::FMOD_ADVANCEDSETTINGS>() as i32,
-            maxMPEGcodecs: settings.max_MPEG_codecs,
-            maxADPCMcodecs: settings.max_ADPCM_codecs,
-            maxXMAcodecs: settings.max_XMA_codecs,
-            maxCELTcodecs: settings.max_CELT_codecs,
-            maxVORBIScodecs: settings.max_VORBIS_codecs,
-            maxAT9Codecs: settings.max_AT9_codecs,
-            maxPCMcodecs: settings.max_PCM_codecs,
-            ASIONumChannels: settings.ASIO_num_channels,
-            ASIOChannelList: converted_c_char.as_mut_ptr() as *mut *mut c_char,
-            ASIOSpeakerList: settings.ASIO_speaker_list.as_mut_ptr(),
-            max3DReverbDSPs: settings.max_3D_reverb_DSPs,
-            HRTFMinAngle: settings.HRTF_min_angle,
-            HRTFMaxAngle: settings.HRTF_max_angle,
-            HRTFFreq: settings.HRTF_freq,
-            vol0virtualvol: settings.vol0_virtual_vol,
-            eventqueuesize: settings.event_queue_size,
-            defaultDecodeBufferSize: settings.default_decode_buffer_size,
-            debugLogFilename: deb_log_filename.as_ptr() as *mut c_char,
-            profileport: settings.profile_port,
-            geometryMaxFadeTime: settings.geometry_max_fade_time,
-            maxSpectrumWaveDataBuffers: settings.max_spectrum_wave_data_buffers,
-            musicSystemCacheDelay: settings.music_system_cache_delay,
-            distanceFilterCenterFreq: settings.distance_filter_center_freq,
-            stackSizeStream: settings.stack_size_stream,
-            stackSizeNonBlocking: settings.stack_size_non_blocking,
-            stackSizeMixer: settings.stack_size_mixer,
-        };
-
-        unsafe { ffi::FMOD_System_SetAdvancedSettings(self.system, &mut advanced_settings) }
-    }
-
-    pub fn get_advanced_settings(&self) -> Result<AdvancedSettings, ::Status> {
+    pub fn get_advanced_settings(&self) -> Result<AdvancedSettings, ::Error> {
         let mut advanced_settings = ffi::FMOD_ADVANCEDSETTINGS{
             cbsize: mem::size_of::<ffi::FMOD_ADVANCEDSETTINGS>() as i32,
             maxMPEGcodecs: 0,
@@ -1554,30 +1961,20 @@ impl Sys {
                     stack_size_mixer: advanced_settings.stackSizeMixer,
                 })
             }
-            e => Err(e),
+            e => Err(::Error::new(e, "FMOD_System_GetAdvancedSettings")),
         }
     }
 
-    pub fn set_speaker_mode(&self, speaker_mode: ::SpeakerMode) -> ::Status {
-        unsafe { ffi::FMOD_System_SetSpeakerMode(self.system, speaker_mode) }
-    }
-
-    pub fn get_speaker_mode(&self) -> Result<::SpeakerMode, ::Status> {
+    pub fn get_speaker_mode(&self) -> Result<::SpeakerMode, ::Error> {
         let mut speaker_mode = ::SpeakerMode::Raw;
 
         match unsafe { ffi::FMOD_System_GetSpeakerMode(self.system, &mut speaker_mode) } {
             ::Status::Ok => Ok(speaker_mode),
-            e => Err(e)
+            e => Err(::Error::new(e, "FMOD_System_GetSpeakerMode"))
         }
     }
 
-    pub fn set_plugin_path(&self, path: &str) -> ::Status {
-        let tmp_path = CString::new(path).unwrap();
-
-        unsafe { ffi::FMOD_System_SetPluginPath(self.system, tmp_path.as_ptr() as *const c_char) }
-    }
-
-    pub fn load_plugin(&self, filename: &str, priority: u32) -> Result<PluginHandle, ::Status> {
+    pub fn load_plugin(&self, filename: &str, priority: u32) -> Result<PluginHandle, ::Error> {
         let mut handle = 0u32;
         let tmp_filename = filename.as_ptr();
 
@@ -1585,37 +1982,65 @@ impl Sys {
                                                    &mut handle as *mut c_uint,
                                                    priority as c_uint) } {
             ::Status::Ok => Ok(PluginHandle(handle)),
-            e => Err(e),
+            e => Err(::Error::new(e, "FMOD_System_LoadPlugin")),
         }
     }
 
-    pub fn unload_plugin(&self, PluginHandle(handle): PluginHandle) -> ::Status {
-        unsafe { ffi::FMOD_System_UnloadPlugin(self.system, handle) }
+    /// Registers a codec written in Rust. FMOD tries the codecs by priority when opening a file,
+    /// 0 being the highest. FMOD gives no user data to codecs, so at most 16 codecs can be
+    /// registered by all the systems of the program at the same time. A codec stays registered
+    /// until the system is released, even if unloaded with
+    /// [`Sys::unload_plugin`](#method.unload_plugin).
+    pub fn register_codec(&self, codec: Box<dyn codec::Codec>,
+                          priority: u32) -> Result<PluginHandle, ::Error> {
+        let data = self.get_or_create_sys_data()?;
+        let mut description = codec::get_codec_description_ffi(codec)?;
+        let mut handle = 0u32;
+
+        match unsafe { ffi::FMOD_System_RegisterCodec(self.system, &mut description.description,
+                                                      &mut handle, priority) } {
+            ::Status::Ok => {
+                /* FMOD keeps the name of the codec */
+                match unsafe { (*data).codecs.lock() } {
+                    Ok(mut codecs) => codecs.push(description),
+                    Err(_) => ::std::mem::forget(description)
+                }
+                Ok(PluginHandle(handle))
+            }
+            e => Err(::Error::new(e, "FMOD_System_RegisterCodec"))
+        }
+    }
+
+    pub fn unload_plugin(&self, PluginHandle(handle): PluginHandle) -> Result<(), ::Error> {
+        match unsafe { ffi::FMOD_System_UnloadPlugin(self.system, handle) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_System_UnloadPlugin"))
+        }
     }
 
-    pub fn get_num_plugins(&self, plugin_type: ::PluginType) -> Result<i32, ::Status> {
+    pub fn get_num_plugins(&self, plugin_type: ::PluginType) -> Result<i32, ::Error> {
         let mut num_plugins = 0i32;
 
         match unsafe { ffi::FMOD_System_GetNumPlugins(self.system, plugin_type,
                                                       &mut num_plugins) } {
             ::Status::Ok => Ok(num_plugins),
-            e => Err(e),
+            e => Err(::Error::new(e, "FMOD_System_GetNumPlugins")),
         }
     }
 
     pub fn get_plugin_handle(&self, plugin_type: ::PluginType,
-                             index: i32) -> Result<PluginHandle, ::Status> {
+                             index: i32) -> Result<PluginHandle, ::Error> {
         let mut handle = 0u32;
 
         match unsafe { ffi::FMOD_System_GetPluginHandle(self.system, plugin_type, index as c_int,
                                                         &mut handle as *mut c_uint) } {
             ::Status::Ok => Ok(PluginHandle(handle)),
-            e => Err(e),
+            e => Err(::Error::new(e, "FMOD_System_GetPluginHandle")),
         }
     }
 
     pub fn get_plugin_info(&self, PluginHandle(handle): PluginHandle,
-                           name_len: usize) -> Result<(String, ::PluginType, u32), ::Status> {
+                           name_len: usize) -> Result<(String, ::PluginType, u32), ::Error> {
         let mut plugin_type = ::PluginType::Output;
         let mut version = 0u32;
         let mut c = Vec::with_capacity(name_len + 1);
@@ -1629,57 +2054,59 @@ impl Sys {
                                                       name_len as c_int,
                                                       &mut version as *mut c_uint) } {
             ::Status::Ok => Ok((String::from_utf8(c).unwrap(), plugin_type, version)),
-            e => Err(e),
+            e => Err(::Error::new(e, "FMOD_System_GetPluginInfo")),
         }
     }
 
-    pub fn set_output_by_plugin(&self, PluginHandle(handle): PluginHandle) -> ::Status {
-        unsafe { ffi::FMOD_System_SetOutputByPlugin(self.system, handle) }
-    }
-
-    pub fn get_output_by_plugin(&self) -> Result<PluginHandle, ::Status> {
+    pub fn get_output_by_plugin(&self) -> Result<PluginHandle, ::Error> {
         let mut handle = 0u32;
 
         match unsafe { ffi::FMOD_System_GetOutputByPlugin(self.system, &mut handle) } {
             ::Status::Ok => Ok(PluginHandle(handle)),
-            e => Err(e),
+            e => Err(::Error::new(e, "FMOD_System_GetOutputByPlugin")),
         }
     }
 
     pub fn create_DSP_by_plugin(&self,
-                                PluginHandle(handle): PluginHandle) -> Result<Dsp, ::Status> {
+                                PluginHandle(handle): PluginHandle) -> Result<Dsp, ::Error> {
         let mut dsp = ::std::ptr::null_mut();
 
         match unsafe { ffi::FMOD_System_CreateDSPByPlugin(self.system, handle, &mut dsp) } {
-            ::Status::Ok => Ok(dsp::from_ptr_first(dsp)),
-            e => Err(e),
+            ::Status::Ok => Ok(dsp::from_ptr_first(dsp, get_owner(self))),
+            e => Err(::Error::new(e, "FMOD_System_CreateDSPByPlugin")),
         }
     }
 
-    pub fn set_3D_num_listeners(&self, num_listeners: i32) -> ::Status {
-        unsafe { ffi::FMOD_System_Set3DNumListeners(self.system, num_listeners as c_int) }
+    pub fn set_3D_num_listeners(&self, num_listeners: i32) -> Result<(), ::Error> {
+        match unsafe { ffi::FMOD_System_Set3DNumListeners(self.system, num_listeners as c_int) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_System_Set3DNumListeners"))
+        }
     }
 
-    pub fn get_3D_num_listeners(&self) -> Result<i32, ::Status> {
+    pub fn get_3D_num_listeners(&self) -> Result<i32, ::Error> {
         let mut num_listeners = 0i32;
 
         match unsafe { ffi::FMOD_System_Get3DNumListeners(self.system,
                                                           &mut num_listeners as *mut c_int) } {
             ::Status::Ok => Ok(num_listeners),
-            e => Err(e),
+            e => Err(::Error::new(e, "FMOD_System_Get3DNumListeners")),
         }
     }
 
     pub fn set_3D_listener_attributes(&self, listener: i32, pos: &vector::Vector,
                                       vel: &vector::Vector, forward: &vector::Vector,
-                                      up: &vector::Vector) -> ::Status {
+                                      up: &vector::Vector) -> Result<(), ::Error> {
         let c_p = vector::get_ffi(pos);
         let c_v = vector::get_ffi(vel);
         let c_f = vector::get_ffi(forward);
         let c_u = vector::get_ffi(up);
 
-        unsafe { ffi::FMOD_System_Set3DListenerAttributes(self.system, listener as c_int, &c_p,
-                                                          &c_v, &c_f, &c_u) }
+        match unsafe { ffi::FMOD_System_Set3DListenerAttributes(self.system, listener as c_int,
+                                                                &c_p, &c_v, &c_f, &c_u) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_System_Set3DListenerAttributes"))
+        }
     }
 
     /// Returns:
@@ -1687,7 +2114,7 @@ impl Sys {
     /// Ok(position, velocity, forward, up)
     pub fn get_3D_listener_attributes(&self, listener: i32)
                                       -> Result<(vector::Vector, vector::Vector, vector::Vector,
-                                                 vector::Vector), ::Status> {
+                                                 vector::Vector), ::Error> {
         let mut pos = vector::get_ffi(&vector::Vector::new());
         let mut vel = vector::get_ffi(&vector::Vector::new());
         let mut forward = vector::get_ffi(&vector::Vector::new());
@@ -1698,24 +2125,28 @@ impl Sys {
                                                                 &mut up) } {
             ::Status::Ok => Ok((vector::from_ptr(pos), vector::from_ptr(vel),
                                 vector::from_ptr(forward), vector::from_ptr(up))),
-            e => Err(e),
+            e => Err(::Error::new(e, "FMOD_System_Get3DListenerAttributes")),
         }
     }
 
     pub fn set_3D_speaker_position(&self, speaker: ::Speaker, x: f32, y: f32,
-                                   active: bool) -> ::Status {
+                                   active: bool) -> Result<(), ::Error> {
         let t_active : c_int = match active {
             true => 1,
             false => 0,
         };
-        unsafe { ffi::FMOD_System_Set3DSpeakerPosition(self.system, speaker, x, y, t_active) }
+        match unsafe { ffi::FMOD_System_Set3DSpeakerPosition(self.system, speaker, x, y,
+                                                             t_active) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_System_Set3DSpeakerPosition"))
+        }
     }
 
     /// Returns:
     ///
     /// Ok(x, y, is_active)
     pub fn get_3D_speaker_position(&self,
-                                   speaker: ::Speaker) -> Result<(f32, f32, bool), ::Status> {
+                                   speaker: ::Speaker) -> Result<(f32, f32, bool), ::Error> {
         let mut x = 0f32;
         let mut y = 0f32;
         let mut active : c_int = 0;
@@ -1726,20 +2157,23 @@ impl Sys {
                 0 => false,
                 _ => true,
             })),
-            e => Err(e),
+            e => Err(::Error::new(e, "FMOD_System_Get3DSpeakerPosition")),
         }
     }
 
     pub fn set_3D_settings(&self, doppler_scale: f32, distance_factor: f32,
-                           roll_off_scale: f32) -> ::Status {
-        unsafe { ffi::FMOD_System_Set3DSettings(self.system, doppler_scale, distance_factor,
-                                                roll_off_scale) }
+                           roll_off_scale: f32) -> Result<(), ::Error> {
+        match unsafe { ffi::FMOD_System_Set3DSettings(self.system, doppler_scale, distance_factor,
+                                                      roll_off_scale) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_System_Set3DSettings"))
+        }
     }
 
     /// Returns:
     ///
     /// Ok(doppler_scale, distance_factor, roll_off_scale)
-    pub fn get_3D_settings(&self) -> Result<(f32, f32, f32), ::Status> {
+    pub fn get_3D_settings(&self) -> Result<(f32, f32, f32), ::Error> {
         let mut doppler_scale = 0f32;
         let mut distance_factor = 0f32;
         let mut roll_off_scale = 0f32;
@@ -1747,61 +2181,66 @@ impl Sys {
         match unsafe { ffi::FMOD_System_Get3DSettings(self.system, &mut doppler_scale,
                                                       &mut distance_factor, &mut roll_off_scale) } {
             ::Status::Ok => Ok((doppler_scale, distance_factor, roll_off_scale)),
-            e => Err(e),
+            e => Err(::Error::new(e, "FMOD_System_Get3DSettings")),
         }
     }
 
-    pub fn set_stream_buffer_size(&self, file_buffer_size: u32,
-                                  TimeUnit(file_buffer_size_type): TimeUnit) -> ::Status {
-        unsafe { ffi::FMOD_System_SetStreamBufferSize(self.system, file_buffer_size as c_uint,
-                                                      file_buffer_size_type) }
+    /// Sets the size of the file buffer of streams, in `Ms`, `Pcm`, `PcmBytes` or `RawBytes`.
+    pub fn set_stream_buffer_size(&self, file_buffer_size: Position) -> Result<(), ::Error> {
+        match unsafe { ffi::FMOD_System_SetStreamBufferSize(self.system,
+                                                            file_buffer_size.value() as c_uint,
+                                                            file_buffer_size.time_unit().bits()) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_System_SetStreamBufferSize"))
+        }
     }
 
-    /// Returns:
-    ///
-    /// Ok(file_buffer_size, distance_factor, time)
-    pub fn get_stream_buffer_size(&self) -> Result<(u32, TimeUnit), ::Status> {
+    pub fn get_stream_buffer_size(&self) -> Result<Position, ::Error> {
         let mut file_buffer_size = 0u32;
         let mut file_buffer_size_type = 0u32;
 
         match unsafe { ffi::FMOD_System_GetStreamBufferSize(self.system, &mut file_buffer_size,
                                                             &mut file_buffer_size_type) } {
-            ::Status::Ok => Ok((file_buffer_size, TimeUnit(file_buffer_size_type))),
-            e => Err(e),
+            ::Status::Ok => match Position::new(file_buffer_size,
+                                                TimeUnit(file_buffer_size_type)) {
+                Some(p) => Ok(p),
+                None => Err(::Error::new(::Status::InvalidParam, "Sys::get_stream_buffer_size"))
+            },
+            e => Err(::Error::new(e, "FMOD_System_GetStreamBufferSize")),
         }
     }
 
-    pub fn get_version(&self) -> Result<u32, ::Status> {
+    pub fn get_version(&self) -> Result<u32, ::Error> {
         let mut version : c_uint = 0;
 
         match unsafe { ffi::FMOD_System_GetVersion(self.system, &mut version) } {
             ::Status::Ok => Ok(version as u32),
-            e => Err(e),
+            e => Err(::Error::new(e, "FMOD_System_GetVersion")),
         }
     }
 
-    pub fn get_output_handle(&self) -> Result<OutputHandle, ::Status> {
+    pub fn get_output_handle(&self) -> Result<OutputHandle, ::Error> {
         let mut output_h = ::std::ptr::null_mut();
 
         match unsafe { ffi::FMOD_System_GetOutputHandle(self.system, &mut output_h) } {
             ::Status::Ok => Ok(OutputHandle{handle: output_h}),
-            e => Err(e),
+            e => Err(::Error::new(e, "FMOD_System_GetOutputHandle")),
         }
     }
 
-    pub fn get_channels_playing(&self) -> Result<i32, ::Status> {
+    pub fn get_channels_playing(&self) -> Result<i32, ::Error> {
         let mut playing_chans : c_int = 0;
 
         match unsafe { ffi::FMOD_System_GetChannelsPlaying(self.system, &mut playing_chans) } {
             ::Status::Ok => Ok(playing_chans as i32),
-            e => Err(e),
+            e => Err(::Error::new(e, "FMOD_System_GetChannelsPlaying")),
         }
     }
 
     /// Returns:
     ///
     /// Ok(dsp, stream, geometry, update, total)
-    pub fn get_CPU_usage(&self) -> Result<(f32, f32, f32, f32, f32), ::Status> {
+    pub fn get_CPU_usage(&self) -> Result<(f32, f32, f32, f32, f32), ::Error> {
         let mut dsp = 0f32;
         let mut stream = 0f32;
         let mut geometry = 0f32;
@@ -1811,14 +2250,14 @@ impl Sys {
         match unsafe { ffi::FMOD_System_GetCPUUsage(self.system, &mut dsp, &mut stream,
                                                     &mut geometry, &mut update, &mut total) } {
             ::Status::Ok => Ok((dsp, stream, geometry, update, total)),
-            e => Err(e),
+            e => Err(::Error::new(e, "FMOD_System_GetCPUUsage")),
         }
     }
 
     /// Returns:
     ///
     /// Ok(current_alloced, max_allocated, total)
-    pub fn get_sound_RAM(&self) -> Result<(i32, i32, i32), ::Status> {
+    pub fn get_sound_RAM(&self) -> Result<(i32, i32, i32), ::Error> {
         let mut current_alloced : c_int = 0;
         let mut max_allocated : c_int = 0;
         let mut total : c_int = 0;
@@ -1826,16 +2265,16 @@ impl Sys {
         match unsafe { ffi::FMOD_System_GetSoundRAM(self.system, &mut current_alloced,
                                                     &mut max_allocated, &mut total) } {
             ::Status::Ok => Ok((current_alloced as i32, max_allocated as i32, total as i32)),
-            e => Err(e),
+            e => Err(::Error::new(e, "FMOD_System_GetSoundRAM")),
         }
     }
 
-    pub fn get_num_CDROM_drives(&self) -> Result<i32, ::Status> {
+    pub fn get_num_CDROM_drives(&self) -> Result<i32, ::Error> {
         let mut num_drives : c_int= 0;
 
         match unsafe { ffi::FMOD_System_GetNumCDROMDrives(self.system, &mut num_drives) } {
             ::Status::Ok => Ok(num_drives as i32),
-            e => Err(e)
+            e => Err(::Error::new(e, "FMOD_System_GetNumCDROMDrives"))
         }
     }
 
@@ -1844,7 +2283,7 @@ impl Sys {
     /// Ok(drive_name, scsi_name, device_name)
     pub fn get_CDROM_drive_name(&self, drive: i32, drive_name_len: usize, scsi_name_len: usize,
                                 device_name_len: usize)
-                                -> Result<(String, String, String), ::Status> {
+                                -> Result<(String, String, String), ::Error> {
         let mut drive_name = Vec::with_capacity(drive_name_len + 1);
         let mut scsi_name = Vec::with_capacity(scsi_name_len + 1);
         let mut device_name = Vec::with_capacity(device_name_len + 1);
@@ -1869,12 +2308,12 @@ impl Sys {
             ::Status::Ok => Ok((String::from_utf8(drive_name).unwrap(),
                                 String::from_utf8(scsi_name).unwrap(),
                                 String::from_utf8(device_name).unwrap())),
-            e => Err(e),
+            e => Err(::Error::new(e, "FMOD_System_GetCDROMDriveName")),
         }
     }
 
     pub fn get_spectrum(&self, spectrum_size: usize, channel_offset: Option<i32>,
-                        window_type: Option<::DspFftWindow>) -> Result<Vec<f32>, ::Status> {
+                        window_type: Option<::DspFftWindow>) -> Result<Vec<f32>, ::Error> {
         let mut ptr : Vec<f32> = ::std::iter::repeat(0f32).take(spectrum_size).collect();
         let c_window_type = match window_type {
             Some(wt) => wt,
@@ -1889,133 +2328,147 @@ impl Sys {
                                                     spectrum_size as c_int, c_channel_offset,
                                                     c_window_type) } {
             ::Status::Ok => Ok(ptr),
-            e => Err(e),
+            e => Err(::Error::new(e, "FMOD_System_GetSpectrum")),
         }
     }
 
     pub fn get_wave_data(&self, wave_size: usize,
-                         channel_offset: i32) -> Result<Vec<f32>, ::Status> {
+                         channel_offset: i32) -> Result<Vec<f32>, ::Error> {
         let mut ptr : Vec<f32> = ::std::iter::repeat(0f32).take(wave_size).collect();
 
         match unsafe { ffi::FMOD_System_GetWaveData(self.system, ptr.as_mut_ptr(),
                                                     wave_size as c_int, channel_offset as c_int) } {
             ::Status::Ok => Ok(ptr),
-            e => Err(e),
+            e => Err(::Error::new(e, "FMOD_System_GetWaveData")),
         }
     }
     
-    pub fn get_channel(&self, channel_id: i32) -> Result<channel::Channel, ::Status> {
+    pub fn get_channel(&self, channel_id: i32) -> Result<channel::Channel, ::Error> {
         let mut channel = ::std::ptr::null_mut();
 
         match unsafe { ffi::FMOD_System_GetChannel(self.system, channel_id as c_int,
                                                    &mut channel) } {
-            ::Status::Ok => Ok(ffi::FFI::wrap(channel)),
-            e => Err(e),
+            ::Status::Ok => Ok(channel::from_ptr(channel, get_owner(self))),
+            e => Err(::Error::new(e, "FMOD_System_GetChannel")),
         }
     }
 
-    pub fn get_master_channel_group(&self) -> Result<channel_group::ChannelGroup, ::Status> {
+    pub fn get_master_channel_group(&self) -> Result<Borrowed<'_, channel_group::ChannelGroup>, ::Error> {
         let mut channel_group = ::std::ptr::null_mut();
 
         match unsafe { ffi::FMOD_System_GetMasterChannelGroup(self.system, &mut channel_group) } {
-            ::Status::Ok => Ok(ffi::FFI::wrap(channel_group)),
-            e => Err(e),
+            ::Status::Ok => Ok(borrowed::wrap(channel_group)),
+            e => Err(::Error::new(e, "FMOD_System_GetMasterChannelGroup")),
         }
     }
 
-    pub fn get_master_sound_group(&self) -> Result<sound_group::SoundGroup, ::Status> {
+    pub fn get_master_sound_group(&self) -> Result<Borrowed<'_, sound_group::SoundGroup>, ::Error> {
         let mut sound_group = ::std::ptr::null_mut();
 
         match unsafe { ffi::FMOD_System_GetMasterSoundGroup(self.system, &mut sound_group) } {
-            ::Status::Ok => Ok(ffi::FFI::wrap(sound_group)),
-            e => Err(e),
+            ::Status::Ok => Ok(borrowed::wrap(sound_group)),
+            e => Err(::Error::new(e, "FMOD_System_GetMasterSoundGroup")),
         }
     }
 
     pub fn set_reverb_properties(&self,
-                                 properties: reverb_properties::ReverbProperties) -> ::Status {
+                                 properties: reverb_properties::ReverbProperties)
+                                 -> Result<(), ::Error> {
         let t_properties = reverb_properties::get_ffi(properties);
 
-        unsafe { ffi::FMOD_System_SetReverbProperties(self.system, &t_properties) }
+        match unsafe { ffi::FMOD_System_SetReverbProperties(self.system, &t_properties) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_System_SetReverbProperties"))
+        }
     }
 
-    pub fn get_reverb_properties(&self) -> Result<reverb_properties::ReverbProperties, ::Status> {
+    pub fn get_reverb_properties(&self) -> Result<reverb_properties::ReverbProperties, ::Error> {
         let mut properties = reverb_properties::get_ffi(Default::default());
 
         match unsafe { ffi::FMOD_System_GetReverbProperties(self.system, &mut properties) } {
             ::Status::Ok => Ok(reverb_properties::from_ptr(properties)),
-            e => Err(e),
+            e => Err(::Error::new(e, "FMOD_System_GetReverbProperties")),
         }
     }
 
     pub fn set_reverb_ambient_properties(&self, properties: reverb_properties::ReverbProperties)
-                                         -> ::Status {
+                                         -> Result<(), ::Error> {
         let mut t_properties = reverb_properties::get_ffi(properties);
 
-        unsafe { ffi::FMOD_System_SetReverbAmbientProperties(self.system, &mut t_properties) }
+        match unsafe { ffi::FMOD_System_SetReverbAmbientProperties(self.system,
+                                                                   &mut t_properties) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_System_SetReverbAmbientProperties"))
+        }
     }
 
     pub fn get_reverb_ambient_properties(&self)
-                                         -> Result<reverb_properties::ReverbProperties, ::Status> {
+                                         -> Result<reverb_properties::ReverbProperties, ::Error> {
         let mut properties = reverb_properties::get_ffi(Default::default());
 
         match unsafe { ffi::FMOD_System_GetReverbAmbientProperties(self.system, &mut properties) } {
             ::Status::Ok => Ok(reverb_properties::from_ptr(properties)),
-            e => Err(e),
+            e => Err(::Error::new(e, "FMOD_System_GetReverbAmbientProperties")),
         }
     }
 
-    pub fn get_DSP_head(&self) -> Result<Dsp, ::Status> {
+    pub fn get_DSP_head(&self) -> Result<Borrowed<'_, Dsp>, ::Error> {
         let mut head = ::std::ptr::null_mut();
 
         match unsafe { ffi::FMOD_System_GetDSPHead(self.system, &mut head) } {
-            ::Status::Ok => Ok(ffi::FFI::wrap(head)),
-            e => Err(e),
+            ::Status::Ok => Ok(borrowed::wrap(head)),
+            e => Err(::Error::new(e, "FMOD_System_GetDSPHead")),
         }
     }
 
-    pub fn add_DSP(&self, dsp: &dsp::Dsp) -> Result<dsp_connection::DspConnection, ::Status> {
+    pub fn add_DSP(&self, dsp: &dsp::Dsp) -> Result<dsp_connection::DspConnection, ::Error> {
         let mut t_connection = ::std::ptr::null_mut();
 
         match unsafe { ffi::FMOD_System_AddDSP(self.system, ffi::FFI::unwrap(dsp),
                                                &mut t_connection) } {
-            ::Status::Ok => Ok(ffi::FFI::wrap(t_connection)),
-            e => Err(e),
+            ::Status::Ok => Ok(dsp_connection::from_ptr(t_connection, get_owner(self))),
+            e => Err(::Error::new(e, "FMOD_System_AddDSP")),
         }
     }
 
-    pub fn lock_DSP(&self) -> ::Status {
-        unsafe { ffi::FMOD_System_LockDSP(self.system) }
+    pub fn lock_DSP(&self) -> Result<(), ::Error> {
+        match unsafe { ffi::FMOD_System_LockDSP(self.system) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_System_LockDSP"))
+        }
     }
 
-    pub fn unlock_DSP(&self) -> ::Status {
-        unsafe { ffi::FMOD_System_UnlockDSP(self.system) }
+    pub fn unlock_DSP(&self) -> Result<(), ::Error> {
+        match unsafe { ffi::FMOD_System_UnlockDSP(self.system) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_System_UnlockDSP"))
+        }
     }
 
     /// Returns:
     ///
     /// Ok(hi, lo)
-    pub fn get_DSP_clock(&self) -> Result<(u32, u32), ::Status> {
+    pub fn get_DSP_clock(&self) -> Result<(u32, u32), ::Error> {
         let mut hi : c_uint = 0;
         let mut lo : c_uint = 0;
 
         match unsafe { ffi::FMOD_System_GetDSPClock(self.system, &mut hi, &mut lo) } {
             ::Status::Ok => Ok((hi as u32, lo as u32)),
-            e => Err(e),
+            e => Err(::Error::new(e, "FMOD_System_GetDSPClock")),
         }
     }
 
-    pub fn get_record_num_drivers(&self) -> Result<i32, ::Status> {
+    pub fn get_record_num_drivers(&self) -> Result<i32, ::Error> {
         let mut num_drivers : c_int = 0;
 
         match unsafe { ffi::FMOD_System_GetRecordNumDrivers(self.system, &mut num_drivers) } {
             ::Status::Ok => Ok(num_drivers as i32),
-            e => Err(e),
+            e => Err(::Error::new(e, "FMOD_System_GetRecordNumDrivers")),
         }
     }
 
     pub fn get_record_driver_info(&self, id: i32,
-                                  name_len: usize) -> Result<(Guid, String), ::Status> {
+                                  name_len: usize) -> Result<(Guid, String), ::Error> {
         let mut guid = ffi::FMOD_GUID{
             Data1: 0,
             Data2: 0,
@@ -2037,14 +2490,14 @@ impl Sys {
                                     data3: guid.Data3,
                                     data4: guid.Data4
                                 }, String::from_utf8(c).unwrap())),
-            e => Err(e),
+            e => Err(::Error::new(e, "FMOD_System_GetRecordDriverInfo")),
         }
     }
 
     /// Returns:
     ///
     /// Ok(caps, min_frequency, max_frequency)
-    pub fn get_record_driver_caps(&self, id: i32) -> Result<(FmodCaps, i32, i32), ::Status> {
+    pub fn get_record_driver_caps(&self, id: i32) -> Result<(FmodCaps, i32, i32), ::Error> {
         let mut fmod_caps : c_uint = 0;
         let mut min_frequency : c_int = 0;
         let mut max_frequency : c_int = 0;
@@ -2053,64 +2506,73 @@ impl Sys {
                                                             &mut fmod_caps, &mut min_frequency,
                                                             &mut max_frequency) } {
             ::Status::Ok => Ok((FmodCaps(fmod_caps), min_frequency as i32, max_frequency as i32)),
-            e => Err(e),
+            e => Err(::Error::new(e, "FMOD_System_GetRecordDriverCaps")),
         }
     }
 
-    pub fn get_record_position(&self, id: i32) -> Result<u32, ::Status> {
+    pub fn get_record_position(&self, id: i32) -> Result<u32, ::Error> {
         let mut position : c_uint = 0;
 
         match unsafe { ffi::FMOD_System_GetRecordPosition(self.system, id as c_int,
                                                           &mut position) } {
             ::Status::Ok => Ok(position as u32),
-            e => Err(e),
+            e => Err(::Error::new(e, "FMOD_System_GetRecordPosition")),
         }
     }
 
-    pub fn start_record(&self, id: i32, sound: &sound::Sound, _loop: bool) -> ::Status {
+    pub fn start_record(&self, id: i32, sound: &sound::Sound, _loop: bool) -> Result<(), ::Error> {
         let t_loop = match _loop {
             true => 1,
             _ => 0,
         };
 
-        unsafe { ffi::FMOD_System_RecordStart(self.system, id as c_int, ffi::FFI::unwrap(sound),
-                                              t_loop) }
+        match unsafe { ffi::FMOD_System_RecordStart(self.system, id as c_int,
+                                                    ffi::FFI::unwrap(sound), t_loop) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_System_RecordStart"))
+        }
     }
 
-    pub fn stop_record(&self, id: i32) -> ::Status {
-        unsafe { ffi::FMOD_System_RecordStop(self.system, id as c_int) }
+    pub fn stop_record(&self, id: i32) -> Result<(), ::Error> {
+        match unsafe { ffi::FMOD_System_RecordStop(self.system, id as c_int) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_System_RecordStop"))
+        }
     }
 
-    pub fn is_recording(&self, id: i32) -> Result<bool, ::Status> {
+    pub fn is_recording(&self, id: i32) -> Result<bool, ::Error> {
         let mut is_recording : c_int = 0;
         
         match unsafe { ffi::FMOD_System_IsRecording(self.system, id as c_int, &mut is_recording) } {
             ::Status::Ok => Ok(is_recording == 1),
-            e => Err(e),
+            e => Err(::Error::new(e, "FMOD_System_IsRecording")),
         }
     }
 
     pub fn create_geometry(&self, max_polygons: i32,
-                           max_vertices: i32) -> Result<geometry::Geometry, ::Status> {
+                           max_vertices: i32) -> Result<geometry::Geometry, ::Error> {
         let mut geometry = ::std::ptr::null_mut();
 
         match unsafe { ffi::FMOD_System_CreateGeometry(self.system, max_polygons as c_int,
                                                        max_vertices as c_int, &mut geometry) } {
-            ::Status::Ok => Ok(ffi::FFI::wrap(geometry)),
-            e => Err(e),
+            ::Status::Ok => Ok(geometry::from_ptr_first(geometry, get_owner(self))),
+            e => Err(::Error::new(e, "FMOD_System_CreateGeometry")),
         }
     }
 
-    pub fn set_geometry_settings(&self, max_world_size: f32) -> ::Status {
-        unsafe { ffi::FMOD_System_SetGeometrySettings(self.system, max_world_size) }
+    pub fn set_geometry_settings(&self, max_world_size: f32) -> Result<(), ::Error> {
+        match unsafe { ffi::FMOD_System_SetGeometrySettings(self.system, max_world_size) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_System_SetGeometrySettings"))
+        }
     }
 
-    pub fn get_geometry_settings(&self) -> Result<f32, ::Status> {
+    pub fn get_geometry_settings(&self) -> Result<f32, ::Error> {
         let mut max_world_size = 0f32;
 
         match unsafe { ffi::FMOD_System_GetGeometrySettings(self.system, &mut max_world_size) } {
             ::Status::Ok => Ok(max_world_size),
-            e => Err(e),
+            e => Err(::Error::new(e, "FMOD_System_GetGeometrySettings")),
         }
     }
 
@@ -2118,7 +2580,7 @@ impl Sys {
     ///
     /// Ok(listener, source, direct, reverb)
     pub fn get_geometry_occlusion(&self)
-                                  -> Result<(vector::Vector, vector::Vector, f32, f32), ::Status> {
+                                  -> Result<(vector::Vector, vector::Vector, f32, f32), ::Error> {
         let listener = vector::get_ffi(&vector::Vector::new());
         let source = vector::get_ffi(&vector::Vector::new());
         let mut direct = 0f32;
@@ -2128,7 +2590,7 @@ impl Sys {
                                                              &mut direct, &mut reverb) } {
             ::Status::Ok => Ok((vector::from_ptr(listener),
                                 vector::from_ptr(source), direct, reverb)),
-            e => Err(e),
+            e => Err(::Error::new(e, "FMOD_System_GetGeometryOcclusion")),
         }
     }
 
@@ -2137,50 +2599,344 @@ impl Sys {
     /// Ok(memory_used, details)
     pub fn get_memory_info(&self, MemoryBits(memory_bits): MemoryBits,
                            EventMemoryBits(event_memory_bits): EventMemoryBits)
-                           -> Result<(u32, MemoryUsageDetails), ::Status> {
+                           -> Result<(u32, MemoryUsageDetails), ::Error> {
         let mut details = get_memory_usage_details_ffi(Default::default());
         let mut memory_used : c_uint = 0;
 
         match unsafe { ffi::FMOD_System_GetMemoryInfo(self.system, memory_bits, event_memory_bits,
                                                       &mut memory_used, &mut details) } {
             ::Status::Ok => Ok((memory_used as u32, from_memory_usage_details_ptr(details))),
-            e => Err(e),
+            e => Err(::Error::new(e, "FMOD_System_GetMemoryInfo")),
         }
     }
 
-    pub fn set_file_system(&self, user_open: FileOpenCallback, user_close: FileCloseCallback,
-                           user_read: FileReadCallback, user_seek: FileSeekCallback,/*
-                           user_async_read: ffi::FMOD_FILE_ASYNCREADCALLBACK,
-                           user_async_cancel: ffi::FMOD_FILE_ASYNCCANCELCALLBACK,*/
-                           block_align: i32) -> ::Status {
-        let tmp = get_saved_sys_callback();
+    /// Makes FMOD open files through `factory`, which is called with the name given to
+    /// [`Sys::create_sound`](#method.create_sound) and similar functions. Files are opened and
+    /// read from FMOD threads.
+    ///
+    /// FMOD doesn't tell the file callbacks which system they are called for, so each system
+    /// uses one of 16 callbacks shared by the program: at most 16 systems can have a file system
+    /// at the same time. The callback is given back by
+    /// [`Sys::remove_file_system`](#method.remove_file_system) or when the system is released.
+    ///
+    /// `block_align` is the minimum size of the reads done by FMOD, 2048 by default, -1 keeps
+    /// the current value.
+    pub fn set_file_system<F>(&self, factory: F, block_align: i32) -> Result<(), ::Error>
+        where F: Fn(&Path) -> io::Result<Box<dyn ReadSeek>> + Send + Sync + 'static {
+        self.set_file_system_with_reader(Box::new(factory), None, block_align,
+                                         "Sys::set_file_system")
+    }
+
+    /// Same as [`Sys::set_file_system`](#method.set_file_system), but the reads of streams are
+    /// done asynchronously: FMOD hands every read request to `reader` as an
+    /// [`AsyncReadInfo`](struct.AsyncReadInfo.html) and keeps playing until the data is ready.
+    /// Requests which are still pending when FMOD closes a file are cancelled.
+    ///
+    /// [`ThreadPoolReader`](struct.ThreadPoolReader.html) does the reads on its own threads, most
+    /// important first.
+    pub fn set_async_file_system<F, R>(&self, factory: F, reader: R,
+                                       block_align: i32) -> Result<(), ::Error>
+        where F: Fn(&Path) -> io::Result<Box<dyn ReadSeek>> + Send + Sync + 'static,
+              R: AsyncReader + 'static {
+        self.set_file_system_with_reader(Box::new(factory), Some(Arc::new(reader)), block_align,
+                                         "Sys::set_async_file_system")
+    }
+
+    fn set_file_system_with_reader(&self, factory: FileSystemFactory,
+                                   reader: Option<Arc<dyn AsyncReader>>, block_align: i32,
+                                   function: &'static str) -> Result<(), ::Error> {
+        let data = self.get_or_create_sys_data()?;
+        /* a system replacing its file system keeps its slot */
+        let (slot, new_slot) = {
+            let mut systems = match FILE_SYSTEMS.lock() {
+                Ok(s) => s,
+                Err(_) => return Err(::Error::new(::Status::Internal, function))
+            };
 
-        tmp.file_open = user_open;
-        tmp.file_read = user_read;
-        tmp.file_close = user_close;
-        tmp.file_seek = user_seek;
-        unsafe { ffi::FMOD_System_SetFileSystem(self.system,
-            match user_open {
-                Some(_) => Some(file_open_callback as extern "C" fn(*mut _, _, *mut _, *mut *mut _,
-                                                                    *mut *mut _) -> _),
-                None => None
-            },
-            match user_close {
-                Some(_) => Some(file_close_callback as extern "C" fn(*mut _, *mut _) -> _),
-                None => None
-            },
-            match user_read {
-                Some(_) => Some(file_read_callback as extern "C" fn(*mut _, *mut _, _, *mut _,
-                                                                    *mut _) -> _),
-                None => None
-            },
-            match user_seek {
-                Some(_) => Some(file_seek_callback as extern "C" fn(*mut _, _, *mut _) -> _),
-                None => None
-            },
-            None,
-            None,
+            match systems.iter().position(|s| *s == self.system as usize) {
+                Some(s) => (s, false),
+                None => match systems.iter().position(|s| *s == 0) {
+                    Some(s) => {
+                        systems[s] = self.system as usize;
+                        (s, true)
+                    }
+                    None => return Err(::Error::new(::Status::PluginInstances, function))
+                }
+            }
+        };
+        let (async_read, async_cancel) : (ffi::FMOD_FILE_ASYNCREADCALLBACK,
+                                          ffi::FMOD_FILE_ASYNCCANCELCALLBACK) = match reader {
+            Some(_) => (Some(file_async_read_callback), Some(file_async_cancel_callback)),
+            None => (None, None)
+        };
+
+        let previous = match unsafe { (*data).file_system.lock() } {
+            Ok(mut f) => ::std::mem::replace(&mut *f, Some(Arc::new(FileSystem {
+                factory: factory,
+                reader: reader
+            }))),
+            Err(_) => {
+                if new_slot {
+                    release_file_system_slot(self.system);
+                }
+                return Err(::Error::new(::Status::Internal, function));
+            }
+        };
+        match unsafe { ffi::FMOD_System_SetFileSystem(self.system,
+            FILE_OPEN_CALLBACKS[slot],
+            Some(file_close_callback as extern "C" fn(*mut _, *mut _) -> _),
+            Some(file_read_callback as extern "C" fn(*mut _, *mut _, _, *mut _, *mut _) -> _),
+            Some(file_seek_callback as extern "C" fn(*mut _, _, *mut _) -> _),
+            async_read,
+            async_cancel,
             block_align)
+        } {
+            ::Status::Ok => Ok(()),
+            e => {
+                /* FMOD keeps using the previous file system, if any */
+                if let Ok(mut f) = unsafe { (*data).file_system.lock() } {
+                    *f = previous;
+                }
+                if new_slot {
+                    release_file_system_slot(self.system);
+                }
+                Err(::Error::new(e, "FMOD_System_SetFileSystem"))
+            }
         }
     }
+
+    /// Makes FMOD open files from the archives mounted in `archives`, see
+    /// [`Sys::set_file_system`](#method.set_file_system). Streams are seekable whatever the
+    /// archive format.
+    #[cfg(feature = "archive")]
+    pub fn set_archive_file_system(&self, archives: ::ArchiveFileSystem,
+                                   block_align: i32) -> Result<(), ::Error> {
+        self.set_file_system_with_reader(Box::new(move |path: &Path| archives.open(path)), None,
+                                         block_align, "Sys::set_archive_file_system")
+    }
+
+    /// Makes FMOD use its own file system again after
+    /// [`Sys::set_file_system`](#method.set_file_system) or
+    /// [`Sys::set_async_file_system`](#method.set_async_file_system).
+    pub fn remove_file_system(&self) -> Result<(), ::Error> {
+        match unsafe { ffi::FMOD_System_SetFileSystem(self.system, None, None, None, None, None,
+                                                      None, -1) } {
+            ::Status::Ok => {}
+            e => return Err(::Error::new(e, "FMOD_System_SetFileSystem"))
+        }
+        /* files already opened keep their handle, the factory is only used to open new ones */
+        let data = get_sys_data(self.system)?;
+
+        if !data.is_null() {
+            if let Ok(mut f) = unsafe { (*data).file_system.lock() } {
+                *f = None;
+            }
+        }
+        release_file_system_slot(self.system);
+        Ok(())
+    }
+}
+
+/// Uninitialized FMOD system returned by [`Sys::builder`](struct.Sys.html#method.builder).
+///
+/// Only the settings which have to be set before initialization are available, the other ones
+/// are set on the [`Sys`](struct.Sys.html) returned by [`init`](#method.init).
+pub struct SysBuilder {
+    sys: Sys
+}
+
+/// Error returned when the initialization of a [`SysBuilder`](struct.SysBuilder.html) fails,
+/// giving the builder back with its settings so it can be changed and initialized again.
+///
+/// It converts into an [`Error`](struct.Error.html), so `?` can be used on the initialization.
+pub struct InitError {
+    pub error: ::Error,
+    pub builder: SysBuilder
+}
+
+impl fmt::Debug for InitError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("InitError").field("error", &self.error).finish()
+    }
+}
+
+impl fmt::Display for InitError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.error, f)
+    }
+}
+
+impl From<InitError> for ::Error {
+    fn from(e: InitError) -> ::Error {
+        e.error
+    }
+}
+
+impl SysBuilder {
+    /// Initializes the system with 1 channel and `INIT_NORMAL`.
+    pub fn init(self) -> Result<Sys, InitError> {
+        self.init_with_parameters(1, ::INIT_NORMAL)
+    }
+
+    pub fn init_with_parameters(self, max_channels: i32,
+                                InitFlag(flag): InitFlag) -> Result<Sys, InitError> {
+        match unsafe { ffi::FMOD_System_Init(self.sys.system, max_channels, flag,
+                                             ::std::ptr::null_mut()) } {
+            ::Status::Ok => Ok(self.sys),
+            e => Err(InitError { error: ::Error::new(e, "FMOD_System_Init"), builder: self })
+        }
+    }
+
+    /// Same as [`init_with_parameters`](#method.init_with_parameters), giving
+    /// `extra_driver_data` to the output. `OutputType::WAVWriter` and `OutputType::WAVWriterNRT`
+    /// use it as the path of the written file.
+    pub fn init_with_driver_data(self, max_channels: i32, InitFlag(flag): InitFlag,
+                                 extra_driver_data: &str) -> Result<Sys, InitError> {
+        let data = match CString::new(extra_driver_data) {
+            Ok(d) => d,
+            Err(_) => return Err(InitError {
+                error: ::Error::new(::Status::InvalidParam, "FMOD_System_Init"),
+                builder: self
+            })
+        };
+
+        match unsafe { ffi::FMOD_System_Init(self.sys.system, max_channels, flag,
+                                             data.as_ptr() as *mut c_void) } {
+            ::Status::Ok => Ok(self.sys),
+            e => Err(InitError { error: ::Error::new(e, "FMOD_System_Init"), builder: self })
+        }
+    }
+
+    pub fn set_output(&self, output_type: ::OutputType) -> Result<(), ::Error> {
+        match unsafe { ffi::FMOD_System_SetOutput(self.sys.system, output_type) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_System_SetOutput"))
+        }
+    }
+
+    pub fn get_output(&self) -> Result<::OutputType, ::Error> {
+        self.sys.get_output()
+    }
+
+    pub fn set_output_by_plugin(&self, PluginHandle(handle): PluginHandle) -> Result<(), ::Error> {
+        match unsafe { ffi::FMOD_System_SetOutputByPlugin(self.sys.system, handle) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_System_SetOutputByPlugin"))
+        }
+    }
+
+    pub fn get_num_drivers(&self) -> Result<i32, ::Error> {
+        self.sys.get_num_drivers()
+    }
+
+    pub fn get_driver_info(&self, id: i32, name_len: usize) -> Result<(Guid, String), ::Error> {
+        self.sys.get_driver_info(id, name_len)
+    }
+
+    pub fn set_driver(&self, driver: i32) -> Result<(), ::Error> {
+        match unsafe { ffi::FMOD_System_SetDriver(self.sys.system, driver as c_int) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_System_SetDriver"))
+        }
+    }
+
+    pub fn set_hardware_channels(&self, num_hardware_channels: i32) -> Result<(), ::Error> {
+        match unsafe { ffi::FMOD_System_SetHardwareChannels(self.sys.system,
+                                                            num_hardware_channels as c_int) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_System_SetHardwareChannels"))
+        }
+    }
+
+    pub fn set_software_channels(&self, num_software_channels: i32) -> Result<(), ::Error> {
+        match unsafe { ffi::FMOD_System_SetSoftwareChannels(self.sys.system,
+                                                            num_software_channels as c_int) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_System_SetSoftwareChannels"))
+        }
+    }
+
+    pub fn set_software_format(&self, sample_rate: i32, format: ::SoundFormat,
+                               num_output_channels: i32, max_input_channels: i32,
+                               resample_method: ::DspResampler) -> Result<(), ::Error> {
+        match unsafe {
+            ffi::FMOD_System_SetSoftwareFormat(self.sys.system, sample_rate as c_int, format,
+                                               num_output_channels as c_int,
+                                               max_input_channels as c_int, resample_method)
+        } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_System_SetSoftwareFormat"))
+        }
+    }
+
+    pub fn set_DSP_buffer_size(&self, buffer_length: u32, num_buffers: i32) -> Result<(), ::Error> {
+        match unsafe { ffi::FMOD_System_SetDSPBufferSize(self.sys.system, buffer_length as c_uint,
+                                                         num_buffers as c_int) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_System_SetDSPBufferSize"))
+        }
+    }
+
+    pub fn set_advanced_settings(&self, settings: &mut AdvancedSettings) -> Result<(), ::Error> {
+        let mut converted_c_char: Vec<*const c_char> =
+            (0..settings.ASIO_channel_list.len()).map(|pos| {
+            settings.ASIO_channel_list[pos].as_ptr() as *const c_char
+        }).collect();
+        let deb_log_filename = CString::new(settings.debug_log_filename.clone()).unwrap();
+        let mut advanced_settings = ffi::FMOD_ADVANCEDSETTINGS{
+            cbsize: mem::size_of::<ffi::FMOD_ADVANCEDSETTINGS>() as i32,
+            maxMPEGcodecs: settings.max_MPEG_codecs,
+            maxADPCMcodecs: settings.max_ADPCM_codecs,
+            maxXMAcodecs: settings.max_XMA_codecs,
+            maxCELTcodecs: settings.max_CELT_codecs,
+            maxVORBIScodecs: settings.max_VORBIS_codecs,
+            maxAT9Codecs: settings.max_AT9_codecs,
+            maxPCMcodecs: settings.max_PCM_codecs,
+            ASIONumChannels: settings.ASIO_num_channels,
+            ASIOChannelList: converted_c_char.as_mut_ptr() as *mut *mut c_char,
+            ASIOSpeakerList: settings.ASIO_speaker_list.as_mut_ptr(),
+            max3DReverbDSPs: settings.max_3D_reverb_DSPs,
+            HRTFMinAngle: settings.HRTF_min_angle,
+            HRTFMaxAngle: settings.HRTF_max_angle,
+            HRTFFreq: settings.HRTF_freq,
+            vol0virtualvol: settings.vol0_virtual_vol,
+            eventqueuesize: settings.event_queue_size,
+            defaultDecodeBufferSize: settings.default_decode_buffer_size,
+            debugLogFilename: deb_log_filename.as_ptr() as *mut c_char,
+            profileport: settings.profile_port,
+            geometryMaxFadeTime: settings.geometry_max_fade_time,
+            maxSpectrumWaveDataBuffers: settings.max_spectrum_wave_data_buffers,
+            musicSystemCacheDelay: settings.music_system_cache_delay,
+            distanceFilterCenterFreq: settings.distance_filter_center_freq,
+            stackSizeStream: settings.stack_size_stream,
+            stackSizeNonBlocking: settings.stack_size_non_blocking,
+            stackSizeMixer: settings.stack_size_mixer,
+        };
+
+        match unsafe { ffi::FMOD_System_SetAdvancedSettings(self.sys.system, &mut advanced_settings) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_System_SetAdvancedSettings"))
+        }
+    }
+
+    pub fn set_speaker_mode(&self, speaker_mode: ::SpeakerMode) -> Result<(), ::Error> {
+        match unsafe { ffi::FMOD_System_SetSpeakerMode(self.sys.system, speaker_mode) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_System_SetSpeakerMode"))
+        }
+    }
+
+    pub fn set_plugin_path(&self, path: &str) -> Result<(), ::Error> {
+        let tmp_path = CString::new(path).unwrap();
+
+        match unsafe { ffi::FMOD_System_SetPluginPath(self.sys.system,
+                                                      tmp_path.as_ptr() as *const c_char) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_System_SetPluginPath"))
+        }
+    }
+
+    pub fn load_plugin(&self, filename: &str, priority: u32) -> Result<PluginHandle, ::Error> {
+        self.sys.load_plugin(filename, priority)
+    }
 }
@@ -23,22 +23,26 @@
 */
 
 use ffi;
+use user_data;
+use std::any::Any;
 use types::*;
 use vector;
 use libc::{c_int, c_void};
 use fmod_sys;
-use fmod_sys::MemoryUsageDetails;
-use std::mem::transmute;
+use fmod_sys::{MemoryUsageDetails, SysHandle};
+use std::sync::Arc;
 use std::default::Default;
 
 /// Geometry object
 pub struct Geometry {
-    geometry: *mut ffi::FMOD_GEOMETRY
+    geometry: *mut ffi::FMOD_GEOMETRY,
+    /* keeps the system alive, only set on the geometries releasing their FMOD geometry */
+    owner: Option<Arc<SysHandle>>
 }
 
 impl ffi::FFI<ffi::FMOD_GEOMETRY> for Geometry {
     fn wrap(g: *mut ffi::FMOD_GEOMETRY) -> Geometry {
-        Geometry {geometry: g}
+        Geometry {geometry: g, owner: None}
     }
 
     fn unwrap(g: &Geometry) -> *mut ffi::FMOD_GEOMETRY {
@@ -46,29 +50,39 @@ impl ffi::FFI<ffi::FMOD_GEOMETRY> for Geometry {
     }
 }
 
+pub fn from_ptr_first(geometry: *mut ffi::FMOD_GEOMETRY,
+                      owner: Option<Arc<SysHandle>>) -> Geometry {
+    Geometry {geometry: geometry, owner: owner}
+}
+
 impl Drop for Geometry {
     fn drop(&mut self) {
-        self.release();
+        let _ = self.release();
     }
 }
 
 impl Geometry {
-    pub fn release(&mut self) -> ::Status {
-        if self.geometry !=::std::ptr::null_mut() {
+    /// Releases the geometry if it was created by this object, borrowed ones are left untouched.
+    pub fn release(&mut self) -> Result<(), ::Error> {
+        if self.owner.is_some() && !self.geometry.is_null() {
+            let data = self.get_user_data_ptr()?;
+
             match unsafe { ffi::FMOD_Geometry_Release(self.geometry) } {
                 ::Status::Ok => {
                     self.geometry = ::std::ptr::null_mut();
-                   ::Status::Ok
+                    self.owner = None;
+                    unsafe { user_data::free(data) };
+                   Ok(())
                 }
-                e => e,
+                e => Err(::Error::new(e, "FMOD_Geometry_Release")),
             }
         } else {
-            ::Status::Ok
+            Ok(())
         }
     }
 
     pub fn add_polygon(&self, direct_occlusion: f32, reverb_occlusion: f32, double_sided: bool,
-                       vertices: Vec<vector::Vector>) -> Result<i32, ::Status> {
+                       vertices: Vec<vector::Vector>) -> Result<i32, ::Error> {
         let t_double_sided = if double_sided == true {
             1
         } else {
@@ -86,74 +100,81 @@ impl Geometry {
                                                      vertices.len() as c_int, t_vertices.as_ptr(),
                                                      &mut index) } {
             ::Status::Ok => Ok(index),
-            e => Err(e),
+            e => Err(::Error::new(e, "FMOD_Geometry_AddPolygon")),
         }
     }
 
-    pub fn get_num_polygons(&self) -> Result<i32, ::Status> {
+    pub fn get_num_polygons(&self) -> Result<i32, ::Error> {
         let mut num = 0i32;
 
         match unsafe { ffi::FMOD_Geometry_GetNumPolygons(self.geometry, &mut num) } {
             ::Status::Ok => Ok(num),
-            e => Err(e)
+            e => Err(::Error::new(e, "FMOD_Geometry_GetNumPolygons"))
         }
     }
 
-    pub fn get_max_polygons(&self) -> Result<(i32, i32), ::Status> {
+    pub fn get_max_polygons(&self) -> Result<(i32, i32), ::Error> {
         let mut max_polygons = 0i32;
         let mut max_vertices = 0i32;
 
         match unsafe { ffi::FMOD_Geometry_GetMaxPolygons(self.geometry, &mut max_polygons,
                                                          &mut max_vertices) } {
             ::Status::Ok => Ok((max_polygons, max_vertices)),
-            e => Err(e),
+            e => Err(::Error::new(e, "FMOD_Geometry_GetMaxPolygons")),
         }
     }
 
-    pub fn get_polygon_num_vertices(&self, index: i32) -> Result<i32, ::Status> {
+    pub fn get_polygon_num_vertices(&self, index: i32) -> Result<i32, ::Error> {
         let mut num = 0i32;
 
         match unsafe { ffi::FMOD_Geometry_GetPolygonNumVertices(self.geometry, index, &mut num) } {
             ::Status::Ok => Ok(num),
-            e => Err(e),
+            e => Err(::Error::new(e, "FMOD_Geometry_GetPolygonNumVertices")),
         }
     }
 
     pub fn set_polygon_vertex(&self, index: i32, vertex_index: i32,
-                              vertex: vector::Vector) -> ::Status {
+                              vertex: vector::Vector) -> Result<(), ::Error> {
         let t_vertex = vector::get_ffi(&vertex);
 
-        unsafe { ffi::FMOD_Geometry_SetPolygonVertex(self.geometry, index, vertex_index,
-                                                     &t_vertex) }
+        match unsafe { ffi::FMOD_Geometry_SetPolygonVertex(self.geometry, index, vertex_index,
+                                                           &t_vertex) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_Geometry_SetPolygonVertex"))
+        }
     }
 
     pub fn get_polygon_vertex(&self, index: i32,
-                              vertex_index: i32) -> Result<vector::Vector, ::Status> {
+                              vertex_index: i32) -> Result<vector::Vector, ::Error> {
         let mut vertex = vector::get_ffi(&vector::Vector::new());
 
         match unsafe { ffi::FMOD_Geometry_GetPolygonVertex(self.geometry, index, vertex_index,
                                                            &mut vertex) } {
             ::Status::Ok => Ok(vector::from_ptr(vertex)),
-            e => Err(e),
+            e => Err(::Error::new(e, "FMOD_Geometry_GetPolygonVertex")),
         }
     }
 
     pub fn set_polygon_attributes(&self, index: i32, direct_occlusion: f32, reverb_occlusion: f32,
-                                  double_sided: bool) -> ::Status {
+                                  double_sided: bool) -> Result<(), ::Error> {
         let t_double_sided = if double_sided == true {
             1
         } else {
             0
         };
 
-        unsafe { ffi::FMOD_Geometry_SetPolygonAttributes(self.geometry, index, direct_occlusion,
-                                                         reverb_occlusion, t_double_sided) }
+        match unsafe { ffi::FMOD_Geometry_SetPolygonAttributes(self.geometry, index,
+                                                               direct_occlusion, reverb_occlusion,
+                                                               t_double_sided) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_Geometry_SetPolygonAttributes"))
+        }
     }
 
     /// Returns:
     ///
     /// Ok(direct_occlusion, reverb_occlusion, double_sided)
-    pub fn get_polygon_attributes(&self, index: i32) -> Result<(f32, f32, bool), ::Status> {
+    pub fn get_polygon_attributes(&self, index: i32) -> Result<(f32, f32, bool), ::Error> {
         let mut direct_occlusion = 0f32;
         let mut reverb_occlusion = 0f32;
         let mut double_sided = 0;
@@ -163,76 +184,88 @@ impl Geometry {
                                                                &mut reverb_occlusion,
                                                                &mut double_sided) } {
             ::Status::Ok => Ok((direct_occlusion, reverb_occlusion, double_sided == 1)),
-            e => Err(e),
+            e => Err(::Error::new(e, "FMOD_Geometry_GetPolygonAttributes")),
         }
     }
 
-    pub fn set_active(&self, active: bool) -> ::Status {
+    pub fn set_active(&self, active: bool) -> Result<(), ::Error> {
         let t_active = if active == true {
             1
         } else {
             0
         };
 
-        unsafe { ffi::FMOD_Geometry_SetActive(self.geometry, t_active) }
+        match unsafe { ffi::FMOD_Geometry_SetActive(self.geometry, t_active) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_Geometry_SetActive"))
+        }
     }
 
-    pub fn get_active(&self) -> Result<bool, ::Status> {
+    pub fn get_active(&self) -> Result<bool, ::Error> {
         let mut active = 0;
 
         match unsafe { ffi::FMOD_Geometry_GetActive(self.geometry, &mut active) } {
             ::Status::Ok => Ok(active == 1),
-            e => Err(e)
+            e => Err(::Error::new(e, "FMOD_Geometry_GetActive"))
         }
     }
 
-    pub fn set_rotation(&self, forward: vector::Vector, up: vector::Vector) -> ::Status {
+    pub fn set_rotation(&self, forward: vector::Vector, up: vector::Vector) -> Result<(), ::Error> {
         let t_forward = vector::get_ffi(&forward);
         let t_up = vector::get_ffi(&up);
 
-        unsafe { ffi::FMOD_Geometry_SetRotation(self.geometry, &t_forward, &t_up) }
+        match unsafe { ffi::FMOD_Geometry_SetRotation(self.geometry, &t_forward, &t_up) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_Geometry_SetRotation"))
+        }
     }
 
     /// Returns:
     ///
     /// Ok(forward, up)
-    pub fn get_rotation(&self) -> Result<(vector::Vector, vector::Vector), ::Status> {
+    pub fn get_rotation(&self) -> Result<(vector::Vector, vector::Vector), ::Error> {
         let mut forward = vector::get_ffi(&vector::Vector::new());
         let mut up = vector::get_ffi(&vector::Vector::new());
 
         match unsafe { ffi::FMOD_Geometry_GetRotation(self.geometry, &mut forward, &mut up) } {
             ::Status::Ok => Ok((vector::from_ptr(forward), vector::from_ptr(up))),
-            e => Err(e)
+            e => Err(::Error::new(e, "FMOD_Geometry_GetRotation"))
         }
     }
 
-    pub fn set_position(&self, position: vector::Vector) -> ::Status {
+    pub fn set_position(&self, position: vector::Vector) -> Result<(), ::Error> {
         let t_position = vector::get_ffi(&position);
 
-        unsafe { ffi::FMOD_Geometry_SetPosition(self.geometry, &t_position) }
+        match unsafe { ffi::FMOD_Geometry_SetPosition(self.geometry, &t_position) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_Geometry_SetPosition"))
+        }
     }
 
-    pub fn get_position(&self) -> Result<vector::Vector, ::Status> {
+    pub fn get_position(&self) -> Result<vector::Vector, ::Error> {
         let mut position = vector::get_ffi(&vector::Vector::new());
 
         match unsafe { ffi::FMOD_Geometry_GetPosition(self.geometry, &mut position) } {
             ::Status::Ok => Ok(vector::from_ptr(position)),
-            e => Err(e)
+            e => Err(::Error::new(e, "FMOD_Geometry_GetPosition"))
         }
     }
 
-    pub fn set_scale(&self, scale: vector::Vector) -> ::Status {
+    pub fn set_scale(&self, scale: vector::Vector) -> Result<(), ::Error> {
         let t_scale = vector::get_ffi(&scale);
 
-        unsafe { ffi::FMOD_Geometry_SetScale(self.geometry, &t_scale) }
+        match unsafe { ffi::FMOD_Geometry_SetScale(self.geometry, &t_scale) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_Geometry_SetScale"))
+        }
     }
 
-    pub fn get_scale(&self) -> Result<vector::Vector, ::Status> {
+    pub fn get_scale(&self) -> Result<vector::Vector, ::Error> {
         let mut scale = vector::get_ffi(&vector::Vector::new());
 
         match unsafe { ffi::FMOD_Geometry_GetScale(self.geometry, &mut scale) } {
             ::Status::Ok => Ok(vector::from_ptr(scale)),
-            e => Err(e)
+            e => Err(::Error::new(e, "FMOD_Geometry_GetScale"))
         }
     }
 
@@ -241,32 +274,47 @@ impl Geometry {
     /// Ok(memory_used, details)
     pub fn get_memory_info(&self, MemoryBits(memory_bits): MemoryBits,
                            EventMemoryBits(event_memory_bits): EventMemoryBits)
-                           -> Result<(u32, MemoryUsageDetails), ::Status> {
+                           -> Result<(u32, MemoryUsageDetails), ::Error> {
         let mut details = fmod_sys::get_memory_usage_details_ffi(Default::default());
         let mut memory_used = 0u32;
 
-        match unsafe { ffi::FMOD_Geometry_GetMemoryInfo(self.geometry, memory_bits, event_memory_bits, &mut memory_used, &mut details) } {
+        match unsafe { ffi::FMOD_Geometry_GetMemoryInfo(self.geometry, memory_bits,
+                                                        event_memory_bits, &mut memory_used,
+                                                        &mut details) } {
             ::Status::Ok => Ok((memory_used, fmod_sys::from_memory_usage_details_ptr(details))),
-            e => Err(e)
+            e => Err(::Error::new(e, "FMOD_Geometry_GetMemoryInfo"))
         }
     }
 
-    pub fn set_user_data<'r, T>(&'r self, user_data: &'r mut T) -> ::Status {
-        unsafe { ffi::FMOD_Geometry_SetUserData(self.geometry, transmute(user_data)) }
+    /// Stores `user_data` in the geometry, replacing and freeing the previous one. It is freed
+    /// when the geometry is released.
+    pub fn set_user_data<T: Any + Send>(&mut self, user_data: T) -> Result<(), ::Error> {
+        let old = self.get_user_data_ptr()?;
+        let data = user_data::into_ptr(user_data);
+
+        match unsafe { ffi::FMOD_Geometry_SetUserData(self.geometry, data) } {
+            ::Status::Ok => {
+                unsafe { user_data::free(old) };
+                Ok(())
+            }
+            e => {
+                unsafe { user_data::free(data) };
+                Err(::Error::new(e, "FMOD_Geometry_SetUserData"))
+            }
+        }
     }
 
-    pub fn get_user_data<'r, T>(&'r self) -> Result<&'r mut T, ::Status> {
-        unsafe {
-            let mut user_data : *mut c_void = ::std::ptr::null_mut();
+    /// Returns the user data if it is a `T`.
+    pub fn get_user_data<T: Any>(&self) -> Option<&T> {
+        self.get_user_data_ptr().ok().and_then(|data| unsafe { user_data::downcast(data) })
+    }
 
-            match ffi::FMOD_Geometry_GetUserData(self.geometry, &mut user_data) {
-                ::Status::Ok => {
-                    let tmp : &mut T = transmute::<*mut c_void, &mut T>(user_data);
-                    
-                    Ok(tmp)
-                },
-                e => Err(e)
-            }
+    fn get_user_data_ptr(&self) -> Result<*mut c_void, ::Error> {
+        let mut user_data = ::std::ptr::null_mut();
+
+        match unsafe { ffi::FMOD_Geometry_GetUserData(self.geometry, &mut user_data) } {
+            ::Status::Ok => Ok(user_data),
+            e => Err(::Error::new(e, "FMOD_Geometry_GetUserData"))
         }
     }
 }
@@ -0,0 +1,327 @@
+/*
+* Rust-FMOD - Copyright (c) 2014 Gomez Guillaume.
+*
+* The Original software, FMOD library, is provided by FIRELIGHT TECHNOLOGIES.
+*
+* This software is provided 'as-is', without any express or implied warranty.
+* In no event will the authors be held liable for any damages arising from
+* the use of this software.
+*
+* Permission is granted to anyone to use this software for any purpose,
+* including commercial applications, and to alter it and redistribute it
+* freely, subject to the following restrictions:
+*
+* 1. The origin of this software must not be misrepresented; you must not claim
+*    that you wrote the original software. If you use this software in a product,
+*    an acknowledgment in the product documentation would be appreciated but is
+*    not required.
+*
+* 2. Altered source versions must be plainly marked as such, and must not be
+*    misrepresented as being the original software.
+*
+* 3. This notice may not be removed or altered from any source distribution.
+*/
+
+use sound::FmodTag;
+
+/// Value of a [`FmodTag`](struct.FmodTag.html), decoded according to its
+/// [`TagDataType`](enum.TagDataType.html).
+#[derive(Clone, Debug, PartialEq)]
+pub enum TagValue {
+    /// Data which isn't text nor a number, like pictures.
+    Binary(Vec<u8>),
+    Int(i64),
+    Float(f64),
+    /// 8 bit string, decoded as ISO-8859-1.
+    String(String),
+    Utf8(String),
+    /// UTF-16 string, little endian unless it starts with a big endian byte order mark.
+    Utf16(String),
+    Utf16BE(String),
+    /// CD table of contents.
+    Cdtoc(Vec<u8>)
+}
+
+impl TagValue {
+    /// Returns the text of string values.
+    pub fn as_str(&self) -> Option<&str> {
+        match *self {
+            TagValue::String(ref s) | TagValue::Utf8(ref s) | TagValue::Utf16(ref s)
+            | TagValue::Utf16BE(ref s) => Some(s),
+            _ => None
+        }
+    }
+
+    /// Returns the raw data of binary values.
+    pub fn as_bytes(&self) -> Option<&[u8]> {
+        match *self {
+            TagValue::Binary(ref b) | TagValue::Cdtoc(ref b) => Some(b),
+            _ => None
+        }
+    }
+}
+
+/* strings given by FMOD can include their terminating null characters */
+fn trim_nul(s: String) -> String {
+    s.trim_end_matches('\0').to_owned()
+}
+
+fn decode_utf16(data: &[u8], big_endian: bool) -> String {
+    let mut big_endian = big_endian;
+    let mut data = data;
+
+    if data.len() >= 2 {
+        match (data[0], data[1]) {
+            (0xFF, 0xFE) => { big_endian = false; data = &data[2..]; }
+            (0xFE, 0xFF) => { big_endian = true; data = &data[2..]; }
+            _ => {}
+        }
+    }
+    let units : Vec<u16> = data.chunks(2).filter(|c| c.len() == 2).map(|c| {
+        if big_endian {
+            (c[0] as u16) << 8 | c[1] as u16
+        } else {
+            (c[1] as u16) << 8 | c[0] as u16
+        }
+    }).collect();
+
+    trim_nul(String::from_utf16_lossy(&units))
+}
+
+pub fn decode_value(data_type: ::TagDataType, data: &[u8]) -> TagValue {
+    match data_type {
+        ::TagDataType::Int => {
+            let mut bytes = [0u8; 8];
+            let len = ::std::cmp::min(data.len(), 8);
+
+            bytes[..len].copy_from_slice(&data[..len]);
+            /* sign extension of the smaller integers */
+            let shift = 64 - 8 * len as u32;
+            let value = i64::from_ne_bytes(bytes);
+
+            TagValue::Int(if len == 0 { 0 } else { value.wrapping_shl(shift) >> shift })
+        }
+        ::TagDataType::Float if data.len() == 4 => {
+            let mut bytes = [0u8; 4];
+
+            bytes.copy_from_slice(data);
+            TagValue::Float(f32::from_ne_bytes(bytes) as f64)
+        }
+        ::TagDataType::Float if data.len() == 8 => {
+            let mut bytes = [0u8; 8];
+
+            bytes.copy_from_slice(data);
+            TagValue::Float(f64::from_ne_bytes(bytes))
+        }
+        ::TagDataType::String => {
+            TagValue::String(trim_nul(data.iter().map(|&b| b as char).collect()))
+        }
+        ::TagDataType::StringUTF8 => {
+            TagValue::Utf8(trim_nul(String::from_utf8_lossy(data).into_owned()))
+        }
+        ::TagDataType::StringUTF16 => TagValue::Utf16(decode_utf16(data, false)),
+        ::TagDataType::StringUTF16BE => TagValue::Utf16BE(decode_utf16(data, true)),
+        ::TagDataType::CDTOC => TagValue::Cdtoc(data.to_vec()),
+        _ => TagValue::Binary(data.to_vec())
+    }
+}
+
+/// Picture embedded in the tags of a sound.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CoverArt {
+    /// MIME type of the picture, like "image/jpeg".
+    pub mime_type: String,
+    pub data: Vec<u8>
+}
+
+/// Common information read from the ID3v1, ID3v2, Vorbis comment and ASF tags of a sound,
+/// returned by [`Sound::metadata`](struct.Sound.html#method.metadata).
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Metadata {
+    pub title: Option<String>,
+    pub artist: Option<String>,
+    pub album: Option<String>,
+    /// Track number on the album.
+    pub track: Option<u32>,
+    pub year: Option<u32>,
+    pub genre: Option<String>,
+    /// Front cover, or the first picture if none is marked as the front cover.
+    pub cover_art: Option<CoverArt>
+}
+
+/* tag names by format: ID3v1 and Vorbis comments, ID3v2.3/2.4, ID3v2.2 and ASF */
+const TITLE: &[&str] = &["TITLE", "TIT2", "TT2"];
+const ARTIST: &[&str] = &["ARTIST", "TPE1", "TP1", "AUTHOR"];
+const ALBUM: &[&str] = &["ALBUM", "TALB", "TAL", "WM/ALBUMTITLE"];
+const TRACK: &[&str] = &["TRACK", "TRACKNUMBER", "TRCK", "TRK", "WM/TRACKNUMBER", "WM/TRACK"];
+const YEAR: &[&str] = &["YEAR", "DATE", "TYER", "TDRC", "TYE", "WM/YEAR"];
+const GENRE: &[&str] = &["GENRE", "TCON", "TCO", "WM/GENRE"];
+const PICTURE: &[&str] = &["APIC", "PIC", "METADATA_BLOCK_PICTURE", "WM/PICTURE"];
+
+/* picture type of the front cover in ID3v2, FLAC and ASF pictures */
+const FRONT_COVER: u32 = 3;
+
+/* leading number of texts like "3/12" or "2016-05-01" */
+fn parse_number(text: &str) -> Option<u32> {
+    let digits : String = text.trim().chars().take_while(|c| c.is_ascii_digit()).collect();
+
+    digits.parse().ok()
+}
+
+fn number_value(value: &TagValue) -> Option<u32> {
+    match *value {
+        TagValue::Int(i) if i >= 0 && i <= u32::MAX as i64 => Some(i as u32),
+        _ => value.as_str().and_then(parse_number)
+    }
+}
+
+/* skips a null terminated string, two bytes wide for UTF-16 encodings */
+fn skip_string(data: &[u8], wide: bool) -> Option<&[u8]> {
+    if wide {
+        let mut i = 0;
+
+        while i + 1 < data.len() {
+            if data[i] == 0 && data[i + 1] == 0 {
+                return Some(&data[i + 2..]);
+            }
+            i += 2;
+        }
+        None
+    } else {
+        data.iter().position(|&b| b == 0).map(|i| &data[i + 1..])
+    }
+}
+
+/* ID3v2.3/2.4 APIC frame: encoding, MIME type, picture type, description, data */
+fn parse_apic(data: &[u8]) -> Option<(u32, CoverArt)> {
+    let (&encoding, data) = data.split_first()?;
+    let mime_end = data.iter().position(|&b| b == 0)?;
+    let mime_type = String::from_utf8_lossy(&data[..mime_end]).into_owned();
+    let (&picture_type, data) = data[mime_end + 1..].split_first()?;
+    let data = skip_string(data, encoding == 1 || encoding == 2)?;
+
+    Some((picture_type as u32, CoverArt { mime_type: mime_type, data: data.to_vec() }))
+}
+
+/* ID3v2.2 PIC frame: encoding, 3 letters image format, picture type, description, data */
+fn parse_pic(data: &[u8]) -> Option<(u32, CoverArt)> {
+    if data.len() < 5 {
+        return None;
+    }
+    let mime_type = match &data[1..4] {
+        b"PNG" | b"png" => "image/png".to_owned(),
+        b"JPG" | b"jpg" => "image/jpeg".to_owned(),
+        format => format!("image/{}", String::from_utf8_lossy(format).to_lowercase())
+    };
+    let data_start = skip_string(&data[5..], data[0] == 1)?;
+
+    Some((data[4] as u32, CoverArt { mime_type: mime_type, data: data_start.to_vec() }))
+}
+
+fn read_u32_be(data: &[u8], offset: usize) -> Option<u32> {
+    if data.len() < offset + 4 {
+        return None;
+    }
+    Some((data[offset] as u32) << 24 | (data[offset + 1] as u32) << 16
+         | (data[offset + 2] as u32) << 8 | data[offset + 3] as u32)
+}
+
+/* FLAC picture block: type, MIME type, description, size and colors, data, all lengths being
+   32 bit big endian */
+fn parse_flac_picture(data: &[u8]) -> Option<(u32, CoverArt)> {
+    let picture_type = read_u32_be(data, 0)?;
+    let mime_len = read_u32_be(data, 4)? as usize;
+    let mime_type = String::from_utf8_lossy(data.get(8..8 + mime_len)?).into_owned();
+    let description_len = read_u32_be(data, 8 + mime_len)? as usize;
+    let data_len_offset = 12 + mime_len + description_len + 16;
+    let data_len = read_u32_be(data, data_len_offset)? as usize;
+    let picture = data.get(data_len_offset + 4..data_len_offset + 4 + data_len)?;
+
+    Some((picture_type, CoverArt { mime_type: mime_type, data: picture.to_vec() }))
+}
+
+fn decode_base64(text: &str) -> Option<Vec<u8>> {
+    let mut out = Vec::with_capacity(text.len() * 3 / 4);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+
+    for c in text.bytes() {
+        let value = match c {
+            b'A'..=b'Z' => c - b'A',
+            b'a'..=b'z' => c - b'a' + 26,
+            b'0'..=b'9' => c - b'0' + 52,
+            b'+' => 62,
+            b'/' => 63,
+            b'=' | b'\r' | b'\n' => continue,
+            _ => return None
+        };
+        buffer = buffer << 6 | value as u32;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((buffer >> bits) as u8);
+        }
+    }
+    Some(out)
+}
+
+/* ASF WM/Picture: type, data size as 32 bit little endian, MIME type and description as null
+   terminated UTF-16, data */
+fn parse_asf_picture(data: &[u8]) -> Option<(u32, CoverArt)> {
+    if data.len() < 5 {
+        return None;
+    }
+    let picture_type = data[0] as u32;
+    let data_len = (data[1] as usize) | (data[2] as usize) << 8 | (data[3] as usize) << 16
+                   | (data[4] as usize) << 24;
+    let after_mime = skip_string(&data[5..], true)?;
+    let mime_type = decode_utf16(&data[5..data.len() - after_mime.len()], false);
+    let picture = skip_string(after_mime, true)?.get(..data_len)?;
+
+    Some((picture_type, CoverArt { mime_type: mime_type, data: picture.to_vec() }))
+}
+
+fn parse_picture(name: &str, value: &TagValue) -> Option<(u32, CoverArt)> {
+    match name {
+        "APIC" => parse_apic(value.as_bytes()?),
+        "PIC" => parse_pic(value.as_bytes()?),
+        "METADATA_BLOCK_PICTURE" => match *value {
+            TagValue::Binary(ref b) => parse_flac_picture(b),
+            _ => parse_flac_picture(&decode_base64(value.as_str()?)?)
+        },
+        _ => parse_asf_picture(value.as_bytes()?)
+    }
+}
+
+pub fn from_tags<I: Iterator<Item = FmodTag>>(tags: I) -> Metadata {
+    let mut metadata : Metadata = Default::default();
+    let mut cover_type = None;
+
+    for tag in tags {
+        let name = tag.name.to_uppercase();
+        let name = &name[..];
+        let text = || tag.value.as_str().map(|s| s.trim().to_owned()).filter(|s| !s.is_empty());
+
+        if TITLE.contains(&name) && metadata.title.is_none() {
+            metadata.title = text();
+        } else if ARTIST.contains(&name) && metadata.artist.is_none() {
+            metadata.artist = text();
+        } else if ALBUM.contains(&name) && metadata.album.is_none() {
+            metadata.album = text();
+        } else if GENRE.contains(&name) && metadata.genre.is_none() {
+            metadata.genre = text();
+        } else if TRACK.contains(&name) && metadata.track.is_none() {
+            metadata.track = number_value(&tag.value);
+        } else if YEAR.contains(&name) && metadata.year.is_none() {
+            metadata.year = number_value(&tag.value);
+        } else if PICTURE.contains(&name) && cover_type != Some(FRONT_COVER) {
+            if let Some((picture_type, cover)) = parse_picture(name, &tag.value) {
+                if cover_type.is_none() || picture_type == FRONT_COVER {
+                    cover_type = Some(picture_type);
+                    metadata.cover_art = Some(cover);
+                }
+            }
+        }
+    }
+    metadata
+}
@@ -0,0 +1,160 @@
+/*
+* Rust-FMOD - Copyright (c) 2014 Gomez Guillaume.
+*
+* The Original software, FMOD library, is provided by FIRELIGHT TECHNOLOGIES.
+*
+* This software is provided 'as-is', without any express or implied warranty.
+* In no event will the authors be held liable for any damages arising from
+* the use of this software.
+*
+* Permission is granted to anyone to use this software for any purpose,
+* including commercial applications, and to alter it and redistribute it
+* freely, subject to the following restrictions:
+*
+* 1. The origin of this software must not be misrepresented; you must not claim
+*    that you wrote the original software. If you use this software in a product,
+*    an acknowledgment in the product documentation would be appreciated but is
+*    not required.
+*
+* 2. Altered source versions must be plainly marked as such, and must not be
+*    misrepresented as being the original software.
+*
+* 3. This notice may not be removed or altered from any source distribution.
+*/
+
+use std::time::Duration;
+use types::TimeUnit;
+
+/// Position, length or size in one of the units used by FMOD, each variant matching one of the
+/// `TIMEUNIT_*` constants.
+///
+/// A `Duration` converts to a position in milliseconds, so seeking a channel to 1.5 seconds is
+/// `channel.set_position(Duration::from_millis(1500).into())`.
+/// [`Sound::convert_position`](struct.Sound.html#method.convert_position) converts between
+/// milliseconds, PCM samples and PCM bytes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Position {
+    /// Milliseconds.
+    Ms(u32),
+    /// PCM samples, related to milliseconds * samplerate / 1000.
+    Pcm(u32),
+    /// Bytes, related to PCM samples * channels * datawidth (ie 16bit = 2 bytes).
+    PcmBytes(u32),
+    /// Raw file bytes of (compressed) sound data (does not include headers).
+    RawBytes(u32),
+    /// Fractions of 1 PCM sample, used for sub-sample granularity for DSP purposes.
+    PcmFraction(u32),
+    /// MOD/S3M/XM/IT. Order in a sequenced module format.
+    ModOrder(u32),
+    /// MOD/S3M/XM/IT. Row in the current pattern of a sequenced module format.
+    ModRow(u32),
+    /// MOD/S3M/XM/IT. Pattern in a sequenced module format.
+    ModPattern(u32),
+    /// Time in milliseconds in the currently playing subsound of a sentence.
+    SentenceMs(u32),
+    /// Time in PCM samples in the currently playing subsound of a sentence.
+    SentencePcm(u32),
+    /// Time in bytes in the currently playing subsound of a sentence.
+    SentencePcmBytes(u32),
+    /// Index of the currently playing sentence.
+    Sentence(u32),
+    /// Index of the currently playing subsound in a sentence.
+    SentenceSubsound(u32)
+}
+
+impl Position {
+    /// Returns the position of `value` in `unit`, or `None` if `unit` isn't exactly one of the
+    /// `TIMEUNIT_*` constants matching a variant.
+    pub fn new(value: u32, unit: TimeUnit) -> Option<Position> {
+        Some(match unit {
+            ::TIMEUNIT_MS => Position::Ms(value),
+            ::TIMEUNIT_PCM => Position::Pcm(value),
+            ::TIMEUNIT_PCMBYTES => Position::PcmBytes(value),
+            ::TIMEUNIT_RAWBYTES => Position::RawBytes(value),
+            ::TIMEUNIT_PCMFRACTION => Position::PcmFraction(value),
+            ::TIMEUNIT_MODORDER => Position::ModOrder(value),
+            ::TIMEUNIT_MODROW => Position::ModRow(value),
+            ::TIMEUNIT_MODPATTERN => Position::ModPattern(value),
+            ::TIMEUNIT_SENTENCE_MS => Position::SentenceMs(value),
+            ::TIMEUNIT_SENTENCE_PCM => Position::SentencePcm(value),
+            ::TIMEUNIT_SENTENCE_PCMBYTES => Position::SentencePcmBytes(value),
+            ::TIMEUNIT_SENTENCE => Position::Sentence(value),
+            ::TIMEUNIT_SENTENCE_SUBSOUND => Position::SentenceSubsound(value),
+            _ => return None
+        })
+    }
+
+    /// Value of the position, in its unit.
+    pub fn value(&self) -> u32 {
+        match *self {
+            Position::Ms(v) | Position::Pcm(v) | Position::PcmBytes(v) | Position::RawBytes(v)
+            | Position::PcmFraction(v) | Position::ModOrder(v) | Position::ModRow(v)
+            | Position::ModPattern(v) | Position::SentenceMs(v) | Position::SentencePcm(v)
+            | Position::SentencePcmBytes(v) | Position::Sentence(v)
+            | Position::SentenceSubsound(v) => v
+        }
+    }
+
+    /// Unit of the position.
+    pub fn time_unit(&self) -> TimeUnit {
+        match *self {
+            Position::Ms(_) => ::TIMEUNIT_MS,
+            Position::Pcm(_) => ::TIMEUNIT_PCM,
+            Position::PcmBytes(_) => ::TIMEUNIT_PCMBYTES,
+            Position::RawBytes(_) => ::TIMEUNIT_RAWBYTES,
+            Position::PcmFraction(_) => ::TIMEUNIT_PCMFRACTION,
+            Position::ModOrder(_) => ::TIMEUNIT_MODORDER,
+            Position::ModRow(_) => ::TIMEUNIT_MODROW,
+            Position::ModPattern(_) => ::TIMEUNIT_MODPATTERN,
+            Position::SentenceMs(_) => ::TIMEUNIT_SENTENCE_MS,
+            Position::SentencePcm(_) => ::TIMEUNIT_SENTENCE_PCM,
+            Position::SentencePcmBytes(_) => ::TIMEUNIT_SENTENCE_PCMBYTES,
+            Position::Sentence(_) => ::TIMEUNIT_SENTENCE,
+            Position::SentenceSubsound(_) => ::TIMEUNIT_SENTENCE_SUBSOUND
+        }
+    }
+
+    /// Returns the position as a `Duration` if it is in milliseconds.
+    pub fn to_duration(&self) -> Option<Duration> {
+        match *self {
+            Position::Ms(v) | Position::SentenceMs(v) => Some(Duration::from_millis(v as u64)),
+            _ => None
+        }
+    }
+}
+
+impl From<Duration> for Position {
+    fn from(duration: Duration) -> Position {
+        Position::Ms(::std::cmp::min(duration.as_millis(), u32::MAX as u128) as u32)
+    }
+}
+
+/* converts between milliseconds, PCM samples and PCM bytes through the number of samples,
+   keeping its fractional part so that milliseconds are rounded instead of truncated */
+pub fn convert(position: Position, unit: TimeUnit, frequency: f32,
+               frame_size: u32) -> Option<Position> {
+    if frequency <= 0. || frame_size == 0 {
+        return None;
+    }
+    let samples = match position {
+        Position::Ms(v) => v as f64 * frequency as f64 / 1000.,
+        Position::Pcm(v) => v as f64,
+        Position::PcmBytes(v) => v as f64 / frame_size as f64,
+        _ => return None
+    };
+    if position.time_unit() == unit {
+        return Some(position);
+    }
+    let value = match unit {
+        ::TIMEUNIT_MS => (samples * 1000. / frequency as f64).round(),
+        ::TIMEUNIT_PCM => samples.floor(),
+        ::TIMEUNIT_PCMBYTES => samples.floor() * frame_size as f64,
+        _ => return None
+    };
+
+    if value > u32::MAX as f64 {
+        None
+    } else {
+        Position::new(value as u32, unit)
+    }
+}
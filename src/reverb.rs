@@ -23,29 +23,33 @@
 */
 
 use ffi;
+use user_data;
+use std::any::Any;
 use types::*;
 use vector;
 use reverb_properties;
 use fmod_sys;
-use fmod_sys::MemoryUsageDetails;
-use std::mem::transmute;
+use fmod_sys::{MemoryUsageDetails, SysHandle};
+use std::sync::Arc;
 use libc::{c_void};
 use std::default::Default;
 
 /// Reverb object
 pub struct Reverb {
     reverb: *mut ffi::FMOD_REVERB,
+    /* keeps the system alive, only set on the reverbs releasing their FMOD reverb */
+    owner: Option<Arc<SysHandle>>
 }
 
 impl Drop for Reverb {
     fn drop(&mut self) {
-        self.release();
+        let _ = self.release();
     }
 }
 
 impl ffi::FFI<ffi::FMOD_REVERB> for Reverb {
     fn wrap(r: *mut ffi::FMOD_REVERB) -> Reverb {
-        Reverb {reverb: r}
+        Reverb {reverb: r, owner: None}
     }
 
     fn unwrap(r: &Reverb) -> *mut ffi::FMOD_REVERB {
@@ -53,30 +57,42 @@ impl ffi::FFI<ffi::FMOD_REVERB> for Reverb {
     }
 }
 
+pub fn from_ptr_first(reverb: *mut ffi::FMOD_REVERB, owner: Option<Arc<SysHandle>>) -> Reverb {
+    Reverb {reverb: reverb, owner: owner}
+}
+
 impl Reverb {
-    pub fn release(&mut self) -> ::Status {
-        if self.reverb !=::std::ptr::null_mut() {
+    /// Releases the reverb if it was created by this object, borrowed ones are left untouched.
+    pub fn release(&mut self) -> Result<(), ::Error> {
+        if self.owner.is_some() && !self.reverb.is_null() {
+            let data = self.get_user_data_ptr()?;
+
             match unsafe { ffi::FMOD_Reverb_Release(self.reverb) } {
                 ::Status::Ok => {
                     self.reverb = ::std::ptr::null_mut();
-                    ::Status::Ok
+                    self.owner = None;
+                    unsafe { user_data::free(data) };
+                    Ok(())
                 }
-                e => e,
+                e => Err(::Error::new(e, "FMOD_Reverb_Release")),
             }
         } else {
-            ::Status::Ok
+            Ok(())
         }
     }
 
     pub fn set_3D_attributes(&self, position: vector::Vector, min_distance: f32,
-                             max_distance: f32) -> ::Status {
+                             max_distance: f32) -> Result<(), ::Error> {
         let t_position = vector::get_ffi(&position);
 
-        unsafe { ffi::FMOD_Reverb_Set3DAttributes(self.reverb, &t_position, min_distance,
-                                                  max_distance) }
+        match unsafe { ffi::FMOD_Reverb_Set3DAttributes(self.reverb, &t_position, min_distance,
+                                                        max_distance) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_Reverb_Set3DAttributes"))
+        }
     }
 
-    pub fn get_3D_attributes(&self) -> Result<(vector::Vector, f32, f32), ::Status> {
+    pub fn get_3D_attributes(&self) -> Result<(vector::Vector, f32, f32), ::Error> {
         let mut position = vector::get_ffi(&vector::Vector::new());
         let mut min_distance = 0f32;
         let mut max_distance = 0f32;
@@ -84,43 +100,50 @@ impl Reverb {
         match unsafe { ffi::FMOD_Reverb_Get3DAttributes(self.reverb, &mut position,
                                                         &mut min_distance, &mut max_distance) } {
             ::Status::Ok => Ok((vector::from_ptr(position), min_distance, max_distance)),
-            e => Err(e),
+            e => Err(::Error::new(e, "FMOD_Reverb_Get3DAttributes")),
         }
     }
 
     pub fn set_properties(&self,
-                          reverb_properties: reverb_properties::ReverbProperties) -> ::Status {
+                          reverb_properties: reverb_properties::ReverbProperties)
+                          -> Result<(), ::Error> {
         let t_reverb_properties = reverb_properties::get_ffi(reverb_properties);
 
-        unsafe { ffi::FMOD_Reverb_SetProperties(self.reverb, &t_reverb_properties) }
+        match unsafe { ffi::FMOD_Reverb_SetProperties(self.reverb, &t_reverb_properties) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_Reverb_SetProperties"))
+        }
     }
 
     pub fn get_properties(&self, reverb_properties: reverb_properties::ReverbProperties)
-                          -> Result<reverb_properties::ReverbProperties, ::Status> {
+                          -> Result<reverb_properties::ReverbProperties, ::Error> {
         let mut t_reverb_properties = reverb_properties::get_ffi(reverb_properties);
 
         match unsafe { ffi::FMOD_Reverb_GetProperties(self.reverb, &mut t_reverb_properties) } {
             ::Status::Ok => Ok(reverb_properties::from_ptr(t_reverb_properties)),
-            e => Err(e),
+            e => Err(::Error::new(e, "FMOD_Reverb_GetProperties")),
         }
     }
 
-    pub fn set_active(&self, active: bool) -> ::Status {
+    pub fn set_active(&self, active: bool) -> Result<(), ::Error> {
         let t_active = if active == true {
             1
         } else {
             0
         };
 
-        unsafe { ffi::FMOD_Reverb_SetActive(self.reverb, t_active) }
+        match unsafe { ffi::FMOD_Reverb_SetActive(self.reverb, t_active) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_Reverb_SetActive"))
+        }
     }
 
-    pub fn get_active(&self) -> Result<bool, ::Status> {
+    pub fn get_active(&self) -> Result<bool, ::Error> {
         let mut active = 0i32;
 
         match unsafe { ffi::FMOD_Reverb_GetActive(self.reverb, &mut active) } {
             ::Status::Ok => Ok(active == 1),
-            e => Err(e)
+            e => Err(::Error::new(e, "FMOD_Reverb_GetActive"))
         }
     }
 
@@ -129,33 +152,46 @@ impl Reverb {
     /// Ok(memory_used, details)
     pub fn get_memory_info(&self, MemoryBits(memory_bits): MemoryBits,
                            EventMemoryBits(event_memory_bits): EventMemoryBits)
-                           -> Result<(u32, MemoryUsageDetails), ::Status> {
+                           -> Result<(u32, MemoryUsageDetails), ::Error> {
         let mut details = fmod_sys::get_memory_usage_details_ffi(Default::default());
         let mut memory_used = 0u32;
 
         match unsafe { ffi::FMOD_Reverb_GetMemoryInfo(self.reverb, memory_bits, event_memory_bits,
                                                       &mut memory_used, &mut details) } {
             ::Status::Ok => Ok((memory_used, fmod_sys::from_memory_usage_details_ptr(details))),
-            e => Err(e)
+            e => Err(::Error::new(e, "FMOD_Reverb_GetMemoryInfo"))
+        }
+    }
+
+    /// Stores `user_data` in the reverb, replacing and freeing the previous one. It is freed
+    /// when the reverb is released.
+    pub fn set_user_data<T: Any + Send>(&mut self, user_data: T) -> Result<(), ::Error> {
+        let old = self.get_user_data_ptr()?;
+        let data = user_data::into_ptr(user_data);
+
+        match unsafe { ffi::FMOD_Reverb_SetUserData(self.reverb, data) } {
+            ::Status::Ok => {
+                unsafe { user_data::free(old) };
+                Ok(())
+            }
+            e => {
+                unsafe { user_data::free(data) };
+                Err(::Error::new(e, "FMOD_Reverb_SetUserData"))
+            }
         }
     }
 
-    pub fn set_user_data<'r, T>(&'r self, user_data: &'r mut T) -> ::Status {
-        unsafe { ffi::FMOD_Reverb_SetUserData(self.reverb, transmute(user_data)) }
+    /// Returns the user data if it is a `T`.
+    pub fn get_user_data<T: Any>(&self) -> Option<&T> {
+        self.get_user_data_ptr().ok().and_then(|data| unsafe { user_data::downcast(data) })
     }
 
-    pub fn get_user_data<'r, T>(&'r self) -> Result<&'r mut T, ::Status> {
-        unsafe {
-            let mut user_data : *mut c_void = ::std::ptr::null_mut();
+    fn get_user_data_ptr(&self) -> Result<*mut c_void, ::Error> {
+        let mut user_data = ::std::ptr::null_mut();
 
-            match ffi::FMOD_Reverb_GetUserData(self.reverb, &mut user_data) {
-                ::Status::Ok => {
-                    let tmp : &mut T = transmute::<*mut c_void, &mut T>(user_data);
-                    
-                    Ok(tmp)
-                },
-                e => Err(e)
-            }
+        match unsafe { ffi::FMOD_Reverb_GetUserData(self.reverb, &mut user_data) } {
+            ::Status::Ok => Ok(user_data),
+            e => Err(::Error::new(e, "FMOD_Reverb_GetUserData"))
         }
     }
 }
@@ -56,41 +56,20 @@ You can build them separatly too.
 Here is a short example on how to create a file and to play it :
 
 ```Rust
-extern crate libc;
 extern crate rfmod;
 
-use std::os;
+fn run() -> Result<(), rfmod::Error> {
+    let fmod = rfmod::Sys::builder()?.init()?;
+    let sound = fmod.create_sound("music.mp3", None, None)?;
+
+    sound.play_to_the_end()
+}
 
 fn main() {
-    let fmod = match rfmod::Sys::new() {
-        Ok(f) => f,
-        Err(e) => {
-            panic!("Error code : {}", e);
-        }
-    };
-
-    match fmod.init() {
-        rfmod::Status::Ok => {}
-        e => {
-            panic!("Sys.init failed : {}", e);
-        }
-    };
-
-    let mut sound = match fmod.create_sound(StrBuf::from_str("music.mp3"), None, None) {
-        Ok(s) => s,
-        Err(err) => {
-            panic!("Error code : {}", err);
-        }
-    };
-
-    match sound.play_to_the_end() {
-        rfmod::Status::Ok => {
-            println!("Ok !");
-        }
-        err => {
-            panic!("Error code : {}", err);
-        }
-    };
+    match run() {
+        Ok(()) => println!("Ok !"),
+        Err(e) => panic!("{}", e),
+    }
 }
 ```
 
@@ -124,45 +103,98 @@ Here is the list of all modules :
 extern crate libc;
 extern crate c_vec;
 extern crate byteorder;
+#[cfg(feature = "archive")]
+extern crate zip;
 
 pub use channel::{
     Channel,
+    ChannelEvent,
     SpeakerMixOptions,
     ReverbChannelProperties
 };
 pub use fmod_sys::{
     Sys,
+    SysBuilder,
+    InitError,
+    RenderedMix,
+    SystemEvent,
     Guid,
     SoftwareFormat,
     AdvancedSettings,
     OutputHandle,
     CreateSoundexInfo,
-    MemoryUsageDetails,
-    UserData
+    MemoryUsageDetails
 };
 pub use sound::{
     Sound,
     FmodTag,
-    FmodSyncPoint
+    FmodSyncPoint,
+    SoundLock,
+    PcmBuffer,
+    MemorySound,
+    Sample,
+    SoundFuture,
+    Tags
 };
 pub use channel_group::{
     ChannelGroup
 };
 pub use sound_group::SoundGroup;
+pub use sound_cache::SoundCache;
+pub use position::Position;
+pub use borrowed::Borrowed;
+pub use user_data::UserData;
+pub use audio_thread::{
+    AudioThread,
+    AudioClient,
+    AudioEvent,
+    SoundId,
+    ChannelId
+};
+pub use metadata::{
+    TagValue,
+    Metadata,
+    CoverArt
+};
 pub use dsp::{
     Dsp,
     DspParameterDesc,
     DspDescription,
-    DspState
+    DspState,
+    DspProcessor
 };
 pub use dsp_connection::DspConnection;
+pub use error::Error;
 pub use reverb::Reverb;
 pub use reverb_properties::ReverbProperties;
 pub use vector::Vector;
 pub use geometry::Geometry;
 pub use file::{
     FmodFile,
-    SeekStyle
+    SeekStyle,
+    ReadSeek
+};
+pub use codec::{
+    Codec,
+    CodecInstance,
+    CodecWaveFormat,
+    CodecFile
+};
+pub use decoder::{
+    SoundDecoder,
+    Frames
+};
+pub use async_read_info::{
+    AsyncReadInfo,
+    AsyncReader,
+    ThreadPoolReader
+};
+#[cfg(feature = "archive")]
+pub use archive::{
+    Archive,
+    ArchiveFileSystem,
+    ZipArchive,
+    PackArchive
 };
 pub use self::enums::{
     Status,
@@ -181,6 +213,7 @@ pub use self::enums::{
     PluginType,
     OpenState,
     SystemCallbackType,
+    ChannelCallbackType,
     SoundGroupBehavior,
     DspType,
     DspOscillator,
@@ -217,6 +250,12 @@ mod sound;
 mod channel;
 mod channel_group;
 mod sound_group;
+mod sound_cache;
+mod position;
+mod borrowed;
+mod user_data;
+mod audio_thread;
+mod metadata;
 mod fmod_sys;
 mod dsp;
 mod dsp_connection;
@@ -225,118 +264,146 @@ mod vector;
 mod reverb;
 mod reverb_properties;
 mod file;
+mod codec;
+mod decoder;
+mod async_read_info;
+#[cfg(feature = "archive")]
+mod archive;
 mod enums;
 pub mod types;
 pub mod callbacks;
 pub mod error;
 
 /// Default for all modes listed below. LOOP_OFF, 2D, HARDWARE
-pub const DEFAULT                : u32 = 0x00000000;
+pub const DEFAULT                : Mode = Mode(0x00000000);
 /// For non looping sounds. (DEFAULT). Overrides LOOP_NORMAL / LOOP_BIDI.
-pub const LOOP_OFF               : u32 = 0x00000001;
+pub const LOOP_OFF               : Mode = Mode(0x00000001);
 /// For forward looping sounds.
-pub const LOOP_NORMAL            : u32 = 0x00000002;
+pub const LOOP_NORMAL            : Mode = Mode(0x00000002);
 /// For bidirectional looping sounds. (only works on software mixed static sounds).
-pub const LOOP_BIDI              : u32 = 0x00000004;
+pub const LOOP_BIDI              : Mode = Mode(0x00000004);
 /// Ignores any 3d processing. (DEFAULT).
-pub const _2D                    : u32 = 0x00000008;
+pub const _2D                    : Mode = Mode(0x00000008);
 /// Makes the sound positionable in 3D. Overrides 2D
-pub const _3D                    : u32 = 0x00000010;
+pub const _3D                    : Mode = Mode(0x00000010);
 /// Attempts to make sounds use hardware acceleration. (DEFAULT). Note on platforms that don't support HARDWARE (only 3DS, PS Vita, PSP, Wii and Wii U support HARDWARE), this will be internally treated as SOFTWARE.
-pub const HARDWARE               : u32 = 0x00000020;
+pub const HARDWARE               : Mode = Mode(0x00000020);
 /// Makes the sound be mixed by the FMOD CPU based software mixer. Overrides HARDWARE. Use this for FFT, DSP, compressed sample support, 2D multi-speaker support and other software related features.
-pub const SOFTWARE               : u32 = 0x00000040;
+pub const SOFTWARE               : Mode = Mode(0x00000040);
 /// Decompress at runtime, streaming from the source provided (ie from disk). Overrides CREATESAMPLE and CREATECOMPRESSEDSAMPLE. Note a stream can only be played once at a time due to a stream only having 1 stream buffer and file handle. Open multiple streams to have them play concurrently.
-pub const CREATESTREAM           : u32 = 0x00000080;
+pub const CREATESTREAM           : Mode = Mode(0x00000080);
 /// Decompress at loadtime, decompressing or decoding whole file into memory as the target sample format (ie PCM). Fastest for SOFTWARE based playback and most flexible.
-pub const CREATESAMPLE           : u32 = 0x00000100;
+pub const CREATESAMPLE           : Mode = Mode(0x00000100);
 /// Load MP2/MP3/IMAADPCM/CELT/Vorbis/AT9 or XMA into memory and leave it compressed. CELT/Vorbis/AT9 encoding only supported in the FSB file format. During playback the FMOD software mixer will decode it in realtime as a 'compressed sample'. Can only be used in combination with SOFTWARE. Overrides CREATESAMPLE. If the sound data is not one of the supported formats, it will behave as if it was created with CREATESAMPLE and decode the sound into PCM.
-pub const CREATECOMPRESSEDSAMPLE : u32 = 0x00000200;
+pub const CREATECOMPRESSEDSAMPLE : Mode = Mode(0x00000200);
 /// Opens a user created static sample or stream. Use CREATESOUNDEXINFO to specify format and/or read callbacks. If a user created 'sample' is created with no read callback, the sample will be empty. Use [`Sound::lock`](../struct.Sound.html#method.lock) and [`Sound::unlock`](../struct.Sound.html#method.unlock) to place sound data into the sound if this is the case.
-pub const OPENUSER               : u32 = 0x00000400;
+pub const OPENUSER               : Mode = Mode(0x00000400);
 /// "name_or_data" will be interpreted as a pointer to memory instead of filename for creating sounds. Use CREATESOUNDEXINFO to specify length. If used with CREATESAMPLE or CREATECOMPRESSEDSAMPLE, FMOD duplicates the memory into its own buffers. Your own buffer can be freed after open. If used with CREATESTREAM, FMOD will stream out of the buffer whose pointer you passed in. In this case, your own buffer should not be freed until you have finished with and released the stream.
-pub const OPENMEMORY             : u32 = 0x00000800;
+pub const OPENMEMORY             : Mode = Mode(0x00000800);
 /// "name_or_data" will be interpreted as a pointer to memory instead of filename for creating sounds. Use CREATESOUNDEXINFO to specify length. This differs to OPENMEMORY in that it uses the memory as is, without duplicating the memory into its own buffers. For Wii/PSP HARDWARE supports this flag for the GCADPCM/VAG formats. On other platforms SOFTWARE must be used, as sound hardware on the other platforms (ie PC) cannot access main ram. Cannot be freed after open, only after [`Sound::release`](../struct.Sound.html#method.release). Will not work if the data is compressed and CREATECOMPRESSEDSAMPLE is not used.
-pub const OPENMEMORY_POINT       : u32 = 0x10000000;
+pub const OPENMEMORY_POINT       : Mode = Mode(0x10000000);
 /// Will ignore file format and treat as raw pcm. Use CREATESOUNDEXINFO to specify format. Requires at least defaultfrequency, numchannels and format to be specified before it will open. Must be little endian data.
-pub const OPENRAW                : u32 = 0x00001000;
+pub const OPENRAW                : Mode = Mode(0x00001000);
 /// Just open the file, dont prebuffer or read. Good for fast opens for info, or when sound::readData is to be used.
-pub const OPENONLY               : u32 = 0x00002000;
+pub const OPENONLY               : Mode = Mode(0x00002000);
 /// For [`Sys::create_sound`](../struct.Sys.html#method.create_sound) - for accurate [`Sound::get_length`](../struct.Sound.html#method.get_length) / [`Channel::set_position`](../struct.Channel.html#method.set_position) on VBR MP3, and MOD/S3M/XM/IT/MIDI files. Scans file first, so takes longer to open. OPENONLY does not affect this.
-pub const ACCURATETIME           : u32 = 0x00004000;
+pub const ACCURATETIME           : Mode = Mode(0x00004000);
 /// For corrupted / bad MP3 files. This will search all the way through the file until it hits a valid MPEG header. Normally only searches for 4k.
-pub const MPEGSEARCH             : u32 = 0x00008000;
+pub const MPEGSEARCH             : Mode = Mode(0x00008000);
 /// For opening sounds and getting streamed subsounds (seeking) asyncronously. Use [`Sound::get_open_state`](../struct.Sound.html#method.get_open_state) to poll the state of the sound as it opens or retrieves the subsound in the background.
-pub const NONBLOCKING            : u32 = 0x00010000;
+pub const NONBLOCKING            : Mode = Mode(0x00010000);
 /// Unique sound, can only be played one at a time
-pub const UNIQUE                 : u32 = 0x00020000;
+pub const UNIQUE                 : Mode = Mode(0x00020000);
 /// Make the sound's position, velocity and orientation relative to the listener.
-pub const _3D_HEADRELATIVE        : u32 = 0x00040000;
+pub const _3D_HEADRELATIVE        : Mode = Mode(0x00040000);
 /// Make the sound's position, velocity and orientation absolute (relative to the world). (DEFAULT)
-pub const _3D_WORLDRELATIVE       : u32 = 0x00080000;
+pub const _3D_WORLDRELATIVE       : Mode = Mode(0x00080000);
 /// This sound will follow the inverse rolloff model where mindistance = full volume, maxdistance = where sound stops attenuating, and rolloff is fixed according to the global rolloff factor. (DEFAULT)
-pub const _3D_INVERSEROLLOFF      : u32 = 0x00100000;
+pub const _3D_INVERSEROLLOFF      : Mode = Mode(0x00100000);
 /// This sound will follow a linear rolloff model where mindistance = full volume, maxdistance = silence. Rolloffscale is ignored.
-pub const _3D_LINEARROLLOFF       : u32 = 0x00200000;
+pub const _3D_LINEARROLLOFF       : Mode = Mode(0x00200000);
 /// This sound will follow a linear-square rolloff model where mindistance = full volume, maxdistance = silence. Rolloffscale is ignored.
-pub const _3D_LINEARSQUAREROLLOFF : u32 = 0x00400000;
+pub const _3D_LINEARSQUAREROLLOFF : Mode = Mode(0x00400000);
 /// This sound will follow a rolloff model defined by [`Sound::set_3D_custom_rolloff`](../struct.Sound.html#method.set_3D_custom_rolloff) / [`Channel::set_3D_custom_rolloff`](../struct.Channel.html#method.set_3D_custom_rolloff).
-pub const _3D_CUSTOMROLLOFF       : u32 = 0x04000000;
+pub const _3D_CUSTOMROLLOFF       : Mode = Mode(0x04000000);
 /// Is not affect by geometry occlusion. If not specified in [`Sound::set_mode`](../struct.Sound.html#method.set_mode), or [`Channel::set_mode`](../struct.Channel.html#method.set_mode), the flag is cleared and it is affected by geometry again.
-pub const _3D_IGNOREGEOMETRY      : u32 = 0x40000000;
+pub const _3D_IGNOREGEOMETRY      : Mode = Mode(0x40000000);
 /// Filename is double-byte unicode.
-pub const UNICODE                : u32 = 0x01000000;
+pub const UNICODE                : Mode = Mode(0x01000000);
 /// Skips id3v2/asf/etc tag checks when opening a sound, to reduce seek/read overhead when opening files (helps with CD performance).
-pub const IGNORETAGS             : u32 = 0x02000000;
+pub const IGNORETAGS             : Mode = Mode(0x02000000);
 /// Removes some features from samples to give a lower memory overhead, like [`Sound::get_name`](../struct.Sound.html#method.get_name). See remarks.
-pub const LOWMEM                 : u32 = 0x08000000;
+pub const LOWMEM                 : Mode = Mode(0x08000000);
 /// Load sound into the secondary RAM of supported platform. On PS3, sounds will be loaded into RSX/VRAM.
-pub const LOADSECONDARYRAM       : u32 = 0x20000000;
+pub const LOADSECONDARYRAM       : Mode = Mode(0x20000000);
 /// For sounds that start virtual (due to being quiet or low importance), instead of swapping back to audible, and playing at the correct offset according to time, this flag makes the sound play from the start.
-pub const VIRTUAL_PLAYFROMSTART  : u32 = 0x80000000;
+pub const VIRTUAL_PLAYFROMSTART  : Mode = Mode(0x80000000);
 
 /// All platforms - Initialize normally
-pub const INIT_NORMAL                    : u32 = 0x00000000;
+pub const INIT_NORMAL                    : InitFlag = InitFlag(0x00000000);
 /// All platforms - No stream thread is created internally. Streams are driven from [`Sys::update`](../struct.Sys.html#method.update). Mainly used with non-realtime outputs.
-pub const INIT_STREAM_FROM_UPDATE        : u32 = 0x00000001;
+pub const INIT_STREAM_FROM_UPDATE        : InitFlag = InitFlag(0x00000001);
 /// All platforms - FMOD will treat +X as right, +Y as up and +Z as backwards (towards you).
-pub const INIT_3D_RIGHTHANDED            : u32 = 0x00000002;
+pub const INIT_3D_RIGHTHANDED            : InitFlag = InitFlag(0x00000002);
 /// All platforms - Disable software mixer to save memory. Anything created with SOFTWARE will fail and DSP will not work.
-pub const INIT_SOFTWARE_DISABLE          : u32 = 0x00000004;
+pub const INIT_SOFTWARE_DISABLE          : InitFlag = InitFlag(0x00000004);
 /// All platforms - All SOFTWARE (and HARDWARE on 3DS and NGP) with 3D based voices will add a software lowpass filter effect into the DSP chain which is automatically used when [`Channel::set_3D_occlusion`](../struct.Channel.html#method.set_3D_occlusion) is used or the geometry API.
-pub const INIT_OCCLUSION_LOWPASS         : u32 = 0x00000008;
-/// All platforms - All SOFTWARE (and HARDWARE on 3DS and NGP) with 3D based voices will add a software lowpass filter effect into the DSP chain which causes sounds to sound duller when the sound goes behind the listener. Use [`Sys::set_advanced_settings`](../struct.Sys.html#method.set_advanced_settings) to adjust Cutoff frequency.
-pub const INIT_HRTF_LOWPASS              : u32 = 0x00000010;
-/// All platforms - All SOFTWARE with 3D based voices will add a software lowpass and highpass filter effect into the DSP chain which will act as a distance-automated bandpass filter. Use [`Sys::set_advanced_settings`](../struct.Sys.html#method.set_advanced_settings) to adjust the center frequency.
-pub const INIT_DISTANCE_FILTERING        : u32 = 0x00000200;
+pub const INIT_OCCLUSION_LOWPASS         : InitFlag = InitFlag(0x00000008);
+/// All platforms - All SOFTWARE (and HARDWARE on 3DS and NGP) with 3D based voices will add a software lowpass filter effect into the DSP chain which causes sounds to sound duller when the sound goes behind the listener. Use [`SysBuilder::set_advanced_settings`](../struct.SysBuilder.html#method.set_advanced_settings) to adjust Cutoff frequency.
+pub const INIT_HRTF_LOWPASS              : InitFlag = InitFlag(0x00000010);
+/// All platforms - All SOFTWARE with 3D based voices will add a software lowpass and highpass filter effect into the DSP chain which will act as a distance-automated bandpass filter. Use [`SysBuilder::set_advanced_settings`](../struct.SysBuilder.html#method.set_advanced_settings) to adjust the center frequency.
+pub const INIT_DISTANCE_FILTERING        : InitFlag = InitFlag(0x00000200);
 /// All platforms - FMOD Software reverb will preallocate enough buffers for reverb per channel, rather than allocating them and freeing them at runtime.
-pub const INIT_REVERB_PREALLOCBUFFERS    : u32 = 0x00000040;
+pub const INIT_REVERB_PREALLOCBUFFERS    : InitFlag = InitFlag(0x00000040);
 /// All platforms - Enable TCP/IP based host which allows FMOD Designer or FMOD Profiler to connect to it, and view memory, CPU and the DSP network graph in real-time.
-pub const INIT_ENABLE_PROFILE            : u32 = 0x00000020;
-/// All platforms - Any sounds that are 0 volume will go virtual and not be processed except for having their positions updated virtually. Use [`Sys::set_advanced_settings`](../struct.Sys.html#method.set_advanced_settings) to adjust what volume besides zero to switch to virtual at.
-pub const INIT_VOL0_BECOMES_VIRTUAL      : u32 = 0x00000080;
+pub const INIT_ENABLE_PROFILE            : InitFlag = InitFlag(0x00000020);
+/// All platforms - Any sounds that are 0 volume will go virtual and not be processed except for having their positions updated virtually. Use [`SysBuilder::set_advanced_settings`](../struct.SysBuilder.html#method.set_advanced_settings) to adjust what volume besides zero to switch to virtual at.
+pub const INIT_VOL0_BECOMES_VIRTUAL      : InitFlag = InitFlag(0x00000080);
 /// Win32 Vista only - for WASAPI output - Enable exclusive access to hardware, lower latency at the expense of excluding other applications from accessing the audio hardware.
-pub const INIT_WASAPI_EXCLUSIVE          : u32 = 0x00000100;
+pub const INIT_WASAPI_EXCLUSIVE          : InitFlag = InitFlag(0x00000100);
 /// PS3 only - Prefer DTS over Dolby Digital if both are supported. Note: 8 and 6 channel LPCM is always preferred over both DTS and Dolby Digital.
-pub const INIT_PS3_PREFERDTS             : u32 = 0x00800000;
+pub const INIT_PS3_PREFERDTS             : InitFlag = InitFlag(0x00800000);
 /// PS3 only - Force PS3 system output mode to 2 channel LPCM.
-pub const INIT_PS3_FORCE2CHLPCM          : u32 = 0x01000000;
+pub const INIT_PS3_FORCE2CHLPCM          : InitFlag = InitFlag(0x01000000);
 /// Wii / 3DS - Disable Dolby Pro Logic surround.  will be set to STEREO even if user has selected surround in the system settings.
-pub const INIT_DISABLEDOLBY              : u32 = 0x00100000;
+pub const INIT_DISABLEDOLBY              : InitFlag = InitFlag(0x00100000);
 /// Xbox 360 / PS3 - The "music" channelgroup which by default pauses when custom 360 dashboard / PS3 BGM music is played, can be changed to mute (therefore continues playing) instead of pausing, by using this flag.
-pub const INIT_SYSTEM_MUSICMUTENOTPAUSE  : u32 = 0x00200000;
+pub const INIT_SYSTEM_MUSICMUTENOTPAUSE  : InitFlag = InitFlag(0x00200000);
 /// Win32/Wii/PS3/Xbox/Xbox 360 - FMOD Mixer thread is woken up to do a mix when [`Sys::update`](../struct.Sys.html#method.update) is called rather than waking periodically on its own timer.
-pub const INIT_SYNCMIXERWITHUPDATE       : u32 = 0x00400000;
+pub const INIT_SYNCMIXERWITHUPDATE       : InitFlag = InitFlag(0x00400000);
 /// All platforms - With the geometry engine, only process the closest polygon rather than accumulating all polygons the sound to listener line intersects.
-pub const INIT_GEOMETRY_USECLOSEST       : u32 = 0x04000000;
+pub const INIT_GEOMETRY_USECLOSEST       : InitFlag = InitFlag(0x04000000);
 /// Win32 - Disables automatic setting of of _STEREO to _MYEARS if the MyEars profile exists on the PC. MyEars is HRTF 7.1 downmixing through headphones.
-pub const INIT_DISABLE_MYEARS_AUTODETECT : u32 = 0x08000000;
+pub const INIT_DISABLE_MYEARS_AUTODETECT : InitFlag = InitFlag(0x08000000);
 /// PS3 only - Disable DTS output mode selection
-pub const INIT_PS3_DISABLEDTS            : u32 = 0x10000000;
+pub const INIT_PS3_DISABLEDTS            : InitFlag = InitFlag(0x10000000);
 /// PS3 only - Disable Dolby Digital output mode selection
-pub const INIT_PS3_DISABLEDOLBYDIGITAL   : u32 = 0x20000000;
+pub const INIT_PS3_DISABLEDOLBYDIGITAL   : InitFlag = InitFlag(0x20000000);
 /// PS3/PS4 only - FMOD uses the WAVEFORMATEX Microsoft 7.1 speaker mapping where the last 2 pairs of speakers are 'rears' then 'sides', but on PS3/PS4 these are mapped to 'surrounds' and 'backs'. Use this flag to swap fmod's last 2 pair of speakers on PS3/PS4 to avoid needing to do a special case for these platforms.
-pub const INIT_7POINT1_DOLBYMAPPING      : u32 = 0x40000000;
+pub const INIT_7POINT1_DOLBYMAPPING      : InitFlag = InitFlag(0x40000000);
+
+/// Device has no special capabilities.
+pub const CAPS_NONE                  : FmodCaps = FmodCaps(0x00000000);
+/// Device supports hardware mixing.
+pub const CAPS_HARDWARE              : FmodCaps = FmodCaps(0x00000001);
+/// User has device set to 'Hardware acceleration = off' in control panel, and now extra 200ms latency is incurred.
+pub const CAPS_HARDWARE_EMULATED     : FmodCaps = FmodCaps(0x00000002);
+/// Device can do multichannel output, ie greater than 2 channels.
+pub const CAPS_OUTPUT_MULTICHANNEL   : FmodCaps = FmodCaps(0x00000004);
+/// Device can output to 8bit integer PCM.
+pub const CAPS_OUTPUT_FORMAT_PCM8    : FmodCaps = FmodCaps(0x00000008);
+/// Device can output to 16bit integer PCM.
+pub const CAPS_OUTPUT_FORMAT_PCM16   : FmodCaps = FmodCaps(0x00000010);
+/// Device can output to 24bit integer PCM.
+pub const CAPS_OUTPUT_FORMAT_PCM24   : FmodCaps = FmodCaps(0x00000020);
+/// Device can output to 32bit integer PCM.
+pub const CAPS_OUTPUT_FORMAT_PCM32   : FmodCaps = FmodCaps(0x00000040);
+/// Device can output to 32bit floating point PCM.
+pub const CAPS_OUTPUT_FORMAT_PCMFLOAT: FmodCaps = FmodCaps(0x00000080);
+/// Device supports some form of limited hardware reverb, maybe parameterless and only selectable by environment.
+pub const CAPS_REVERB_LIMITED        : FmodCaps = FmodCaps(0x00002000);
+/// Device supports loopback recording.
+pub const CAPS_LOOPBACK              : FmodCaps = FmodCaps(0x00004000);
 
 /// Milliseconds.
 pub const TIMEUNIT_MS               : TimeUnit = TimeUnit(0x00000001);
@@ -23,45 +23,241 @@
 */
 
 use types::*;
-use libc::{c_int, c_uint, c_char, c_ushort, c_void};
+use libc::{c_int, c_uint, c_char, c_void};
 use ffi;
 use channel;
 use sound_group;
 use vector;
 use fmod_sys;
-use fmod_sys::{MemoryUsageDetails, Sys};
-use std::mem::transmute;
+use fmod_sys::{MemoryUsageDetails, Sys, SysHandle};
+use borrowed;
+use borrowed::Borrowed;
 use std::fs::File;
 use std::mem;
 use std::slice;
 use std::default::Default;
 use byteorder::{WriteBytesExt, LittleEndian};
-use std::io::Write;
-use std::ffi::CString;
+use std::io::{self, Seek, SeekFrom, Write};
+use std::ffi::{CStr, CString};
+use metadata;
+use metadata::{Metadata, TagValue};
 use std::time::Duration;
+use position;
+use position::Position;
+use std::marker::PhantomData;
+use std::any::Any;
+use std::ops::Deref;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+
+const WAVE_FORMAT_PCM: u16 = 1;
+const WAVE_FORMAT_IEEE_FLOAT: u16 = 3;
+const WAVE_FORMAT_EXTENSIBLE: u16 = 0xFFFE;
+/* KSDATAFORMAT_SUBTYPE_* GUID, without its leading format tag */
+const SUBTYPE_GUID_TAIL: [u8; 14] = [0x00, 0x00, 0x00, 0x00, 0x10, 0x00, 0x80, 0x00, 0x00, 0xAA,
+                                     0x00, 0x38, 0x9B, 0x71];
+
+fn wav_channel_mask(channels: i32) -> u32 {
+    match channels {
+        1 => 0x4,
+        2 => 0x3,
+        4 => 0x33,
+        6 => 0x3F,
+        8 => 0x63F,
+        _ => 0
+    }
+}
+
+fn write_pcm_region<W: Write>(output: &mut W, data: &[u8], format: ::SoundFormat) -> io::Result<()> {
+    match format {
+        // WAV stores 8bit samples unsigned whereas FMOD gives them signed
+        ::SoundFormat::PCM8 => output.write_all(&data.iter().map(|b| b ^ 0x80).collect::<Vec<u8>>()),
+        _ => output.write_all(data)
+    }
+}
+
+fn wav_format_tag(format: ::SoundFormat) -> io::Result<u16> {
+    match format {
+        ::SoundFormat::PCM8 | ::SoundFormat::PCM16 | ::SoundFormat::PCM24
+        | ::SoundFormat::PCM32 => Ok(WAVE_FORMAT_PCM),
+        ::SoundFormat::PCMFloat => Ok(WAVE_FORMAT_IEEE_FLOAT),
+        f => Err(io::Error::new(io::ErrorKind::InvalidInput,
+                                format!("{:?} data cannot be exported to WAV", f)))
+    }
+}
+
+/* writes a RIFF/WAVE stream made of `data_len` bytes of PCM data, written by `write_data` */
+pub fn write_wav_data<W, F>(output: &mut W, format: ::SoundFormat, channels: i32, bits: i32,
+                            rate: i32, data_len: u32, write_data: F) -> io::Result<()>
+    where W: Write + Seek, F: FnOnce(&mut W) -> io::Result<()> {
+    let sub_format = wav_format_tag(format)?;
+    let extensible = channels > 2;
+    let is_float = sub_format == WAVE_FORMAT_IEEE_FLOAT;
+    let block_align = (channels * bits / 8) as u16;
+    let start = output.stream_position()?;
+
+    /* wav header, its size is written once the data is */
+    output.write_all(b"RIFF")?;
+    output.write_u32::<LittleEndian>(0)?;
+    output.write_all(b"WAVE")?;
+
+    /* fmt chunk */
+    output.write_all(b"fmt ")?;
+    output.write_u32::<LittleEndian>(if extensible { 40 } else if is_float { 18 } else { 16 })?;
+    output.write_u16::<LittleEndian>(if extensible { WAVE_FORMAT_EXTENSIBLE } else { sub_format })?;
+    output.write_u16::<LittleEndian>(channels as u16)?;
+    output.write_u32::<LittleEndian>(rate as u32)?;
+    output.write_u32::<LittleEndian>(rate as u32 * block_align as u32)?;
+    output.write_u16::<LittleEndian>(block_align)?;
+    output.write_u16::<LittleEndian>(bits as u16)?;
+    if extensible {
+        output.write_u16::<LittleEndian>(22)?;
+        output.write_u16::<LittleEndian>(bits as u16)?;
+        output.write_u32::<LittleEndian>(wav_channel_mask(channels))?;
+        output.write_u16::<LittleEndian>(sub_format)?;
+        output.write_all(&SUBTYPE_GUID_TAIL)?;
+    } else if is_float {
+        output.write_u16::<LittleEndian>(0)?;
+    }
+
+    /* fact chunk, required by non PCM formats */
+    let fact_pos = if is_float {
+        output.write_all(b"fact")?;
+        output.write_u32::<LittleEndian>(4)?;
+        let pos = output.stream_position()?;
+        output.write_u32::<LittleEndian>(0)?;
+        Some(pos)
+    } else {
+        None
+    };
+
+    /* data chunk */
+    output.write_all(b"data")?;
+    let data_pos = output.stream_position()?;
+    output.write_u32::<LittleEndian>(0)?;
+
+    write_data(output)?;
+
+    if data_len % 2 != 0 {
+        output.write_u8(0)?;
+    }
+    let end = output.stream_position()?;
+
+    output.seek(SeekFrom::Start(start + 4))?;
+    output.write_u32::<LittleEndian>((end - start - 8) as u32)?;
+    if let Some(pos) = fact_pos {
+        output.seek(SeekFrom::Start(pos))?;
+        output.write_u32::<LittleEndian>(data_len / block_align as u32)?;
+    }
+    output.seek(SeekFrom::Start(data_pos))?;
+    output.write_u32::<LittleEndian>(data_len)?;
+    output.seek(SeekFrom::Start(end))?;
+    Ok(())
+}
+
+unsafe fn region<'a, T>(ptr: *mut c_void, len: u32) -> &'a mut [T] {
+    if ptr.is_null() || len == 0 {
+        &mut []
+    } else {
+        slice::from_raw_parts_mut(ptr as *mut T, len as usize / mem::size_of::<T>())
+    }
+}
+
+unsafe fn const_region<'a, T>(ptr: *const c_void, len: u32) -> &'a [T] {
+    if ptr.is_null() || len == 0 {
+        &[]
+    } else {
+        slice::from_raw_parts(ptr as *const T, len as usize / mem::size_of::<T>())
+    }
+}
+
+fn is_aligned<T>(ptr: *mut c_void) -> bool {
+    ptr as usize % mem::align_of::<T>() == 0
+}
+
+/* keeps Sample from being implemented outside of the crate, samples being read from and written
+   to raw sound data */
+mod sealed {
+    pub trait Sealed {}
+
+    impl Sealed for i8 {}
+    impl Sealed for i16 {}
+    impl Sealed for [u8; 3] {}
+    impl Sealed for i32 {}
+    impl Sealed for f32 {}
+}
 
-struct RiffChunk {
-    id: [c_char; 4],
-    size: c_int
+/// Type of a PCM sample, see
+/// [`Sys::create_sound_from_pcm`](struct.Sys.html#method.create_sound_from_pcm).
+///
+/// It is only implemented by the types matching the PCM sound formats: `i8`, `i16`, `[u8; 3]`,
+/// `i32` and `f32`.
+pub trait Sample: Copy + Default + sealed::Sealed {
+    /// Format of a sound made of such samples.
+    fn format() -> ::SoundFormat;
 }
 
-struct FmtChunk {
-    chunk: RiffChunk,
-    w_format_tag: c_ushort,     /* format type  */
-    n_channels: c_ushort,       /* number of channels (i.e. mono, stereo...)  */
-    n_samples_per_sec: c_uint,  /* sample rate  */
-    n_avg_bytes_per_sec: c_uint,/* for buffer estimation  */
-    n_block_align: c_ushort,    /* block size of data  */
-    w_bits_per_sample: c_ushort /* number of bits per sample of mono data */
+impl Sample for i8 {
+    fn format() -> ::SoundFormat {
+        ::SoundFormat::PCM8
+    }
+}
+
+impl Sample for i16 {
+    fn format() -> ::SoundFormat {
+        ::SoundFormat::PCM16
+    }
+}
+
+impl Sample for [u8; 3] {
+    fn format() -> ::SoundFormat {
+        ::SoundFormat::PCM24
+    }
+}
+
+impl Sample for i32 {
+    fn format() -> ::SoundFormat {
+        ::SoundFormat::PCM32
+    }
+}
+
+impl Sample for f32 {
+    fn format() -> ::SoundFormat {
+        ::SoundFormat::PCMFloat
+    }
 }
 
-struct DataChunk {
-    chunk: RiffChunk
+/// Sample data handed to a [`SoundPcmReadCallback`](callbacks/type.SoundPcmReadCallback.html),
+/// typed after the format of the sound.
+pub enum PcmBuffer<'a> {
+    /// 8bit integer samples.
+    PCM8(&'a mut [i8]),
+    /// 16bit integer samples.
+    PCM16(&'a mut [i16]),
+    /// 24bit integer samples, stored as 3 little endian bytes.
+    PCM24(&'a mut [[u8; 3]]),
+    /// 32bit integer samples.
+    PCM32(&'a mut [i32]),
+    /// 32bit floating point samples.
+    PCMFloat(&'a mut [f32]),
+    /// Raw bytes, for any other format.
+    Bytes(&'a mut [u8]),
 }
 
-struct WavHeader {
-    chunk: RiffChunk,
-    riff_type: [c_char; 4]
+impl<'a> PcmBuffer<'a> {
+    #[doc(hidden)]
+    pub unsafe fn from_raw(data: *mut c_void, len: u32, format: ::SoundFormat) -> PcmBuffer<'a> {
+        match format {
+            ::SoundFormat::PCM8 => PcmBuffer::PCM8(region(data, len)),
+            ::SoundFormat::PCM16 => PcmBuffer::PCM16(region(data, len)),
+            ::SoundFormat::PCM24 => PcmBuffer::PCM24(region(data, len)),
+            ::SoundFormat::PCM32 => PcmBuffer::PCM32(region(data, len)),
+            ::SoundFormat::PCMFloat => PcmBuffer::PCMFloat(region(data, len)),
+            _ => PcmBuffer::Bytes(region(data, len))
+        }
+    }
 }
 
 /// Wrapper for SyncPoint object
@@ -76,6 +272,7 @@ impl FmodSyncPoint {
 }
 
 /// Structure describing a piece of tag data.
+#[derive(Clone, Debug, PartialEq)]
 pub struct FmodTag {
     /// [r] The type of this tag.
     pub _type    : ::TagType,
@@ -83,10 +280,8 @@ pub struct FmodTag {
     pub data_type: ::TagDataType,
     /// [r] The name of this tag i.e. "TITLE", "ARTIST" etc.
     pub name     : String,
-    /// [r] Pointer to the tag data - its format is determined by the datatype member
-    data         : *mut c_void,
-    /// [r] Length of the data contained in this tag
-    data_len     : c_uint,
+    /// [r] The tag data, decoded according to its data type
+    pub value    : TagValue,
     /// [r] True if this tag has been updated since last being accessed with
     /// [`Sound::get_tag`](struct.Sound.html#method.get_tag)
     pub updated  : bool,
@@ -98,69 +293,73 @@ impl Default for FmodTag {
             _type: ::TagType::Unknown,
             data_type: ::TagDataType::Binary,
             name: String::new(),
-            data: ::std::ptr::null_mut(),
-            data_len: 0u32,
+            value: TagValue::Binary(Vec::new()),
             updated: false,
         }
     }
 }
 
 impl FmodTag {
+    /* FMOD owns the tag memory, it is copied before the next call can overwrite it */
     fn from_ptr(pointer: ffi::FMOD_TAG) -> FmodTag {
+        let data = if pointer.data.is_null() {
+            &[][..]
+        } else {
+            unsafe { slice::from_raw_parts(pointer.data as *const u8, pointer.datalen as usize) }
+        };
+
         FmodTag{
             _type: pointer._type,
             data_type: pointer.datatype,
             name: {
                 if !pointer.name.is_null() {
-                    let l = ffi::strlen(pointer.name);
-
-                    unsafe { String::from_raw_parts(pointer.name as *mut u8, l, l) }
+                    unsafe { CStr::from_ptr(pointer.name) }.to_string_lossy().into_owned()
                 } else {
                     String::new()
                 }
             },
-            data: pointer.data,
-            data_len: pointer.datalen,
-            updated: {
-                if pointer.updated == 1 {
-                    true
-                } else {
-                    false
-                }
-            },
+            value: metadata::decode_value(pointer.datatype, data),
+            updated: pointer.updated != 0,
         }
     }
+}
 
-    fn convert_to_c(&self) -> ffi::FMOD_TAG {
-        let tmp = CString::new(self.name.clone()).unwrap();
+/// Iterator over all the tags of a sound, returned by [`Sound::tags`](struct.Sound.html#method.tags).
+pub struct Tags<'a> {
+    sound: &'a Sound,
+    index: i32,
+    num_tags: i32
+}
 
-        ffi::FMOD_TAG {
-            _type: self._type,
-            datatype: self.data_type,
-            name: tmp.as_ptr() as *mut c_char,
-            data: self.data,
-            datalen: self.data_len,
-            updated: {
-                if self.updated == true {
-                    1
-                } else {
-                    0
-                }
-            },
+impl<'a> Iterator for Tags<'a> {
+    type Item = Result<FmodTag, ::Error>;
+
+    fn next(&mut self) -> Option<Result<FmodTag, ::Error>> {
+        if self.index >= self.num_tags {
+            return None;
         }
+        self.index += 1;
+        Some(self.sound.get_tag("", self.index - 1))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = (self.num_tags - self.index) as usize;
+
+        (remaining, Some(remaining))
     }
 }
 
 /// Sound object
 pub struct Sound {
     sound: *mut ffi::FMOD_SOUND,
-    can_be_deleted: bool,
-    user_data: ffi::SoundData,
+    /* keeps the system alive, only set on the sounds releasing their FMOD sound */
+    owner: Option<Arc<SysHandle>>,
+    user_data: Box<ffi::SoundData>,
 }
 
 impl ffi::FFI<ffi::FMOD_SOUND> for Sound {
     fn wrap(s: *mut ffi::FMOD_SOUND) -> Sound {
-        Sound {sound: s, can_be_deleted: false, user_data: ffi::SoundData::new()}
+        Sound {sound: s, owner: None, user_data: Box::new(ffi::SoundData::new())}
     }
 
     fn unwrap(s: &Sound) -> *mut ffi::FMOD_SOUND {
@@ -172,99 +371,101 @@ pub fn get_fffi<'r>(sound: &'r mut Sound) -> &'r mut *mut ffi::FMOD_SOUND {
     &mut sound.sound
 }
 
-pub fn from_ptr_first(sound: *mut ffi::FMOD_SOUND) -> Sound {
-    Sound{sound: sound, can_be_deleted: true, user_data: ffi::SoundData::new()}
+pub fn from_ptr_first(sound: *mut ffi::FMOD_SOUND, owner: Option<Arc<SysHandle>>) -> Sound {
+    Sound{sound: sound, owner: owner, user_data: Box::new(ffi::SoundData::new())}
 }
 
 pub fn get_user_data<'r>(sound: &'r mut Sound) -> &'r mut ffi::SoundData {
-    &mut sound.user_data
+    &mut *sound.user_data
 }
 
 impl Drop for Sound {
     fn drop(&mut self) {
-        self.release();
+        let _ = self.release();
     }
 }
 
 impl Sound {
-    pub fn get_system_object(&self) -> Result<Sys, ::Status> {
+    pub fn get_system_object(&self) -> Result<Borrowed<'_, Sys>, ::Error> {
         let mut system = ::std::ptr::null_mut();
 
         match unsafe { ffi::FMOD_Sound_GetSystemObject(self.sound, &mut system) } {
-            ::Status::Ok => Ok(ffi::FFI::wrap(system)),
-            e => Err(e),
+            ::Status::Ok => Ok(borrowed::wrap(system)),
+            e => Err(::Error::new(e, "FMOD_Sound_GetSystemObject")),
         }
     }
 
-    pub fn release(&mut self) -> ::Status {
-        if self.can_be_deleted && !self.sound.is_null() {
+    /// Releases the sound if it was created by this object, borrowed sounds are left untouched.
+    pub fn release(&mut self) -> Result<(), ::Error> {
+        if self.owner.is_some() && !self.sound.is_null() {
+            let allocated = match self.get_sound_data() {
+                Ok(data) if !data.is_null() && unsafe { (*data).allocated } => data,
+                _ => ::std::ptr::null_mut()
+            };
+
             match unsafe { ffi::FMOD_Sound_Release(self.sound) } {
                ::Status::Ok => {
+                    if !allocated.is_null() {
+                        unsafe { drop(Box::from_raw(allocated)) };
+                    }
                     self.sound = ::std::ptr::null_mut();
-                   ::Status::Ok
+                    self.owner = None;
+                   Ok(())
                 }
-                e => e,
+                e => Err(::Error::new(e, "FMOD_Sound_Release")),
             }
         } else {
-            ::Status::Ok
+            Ok(())
         }
     }
 
-    pub fn play(&self) -> Result<channel::Channel, ::Status> {
+    pub fn play(&self) -> Result<channel::Channel, ::Error> {
+        let s = self.get_system_object()?;
         let mut channel = ::std::ptr::null_mut();
 
-        match match self.get_system_object() {
-            Ok(s) => { 
-                unsafe { ffi::FMOD_System_PlaySound(ffi::FFI::unwrap(&s), ::ChannelIndex::Free, self.sound, 0, &mut channel) }
-            }
-            Err(e) => e,
-        } {
-            ::Status::Ok => Ok(ffi::FFI::wrap(channel)),
-            e => Err(e),
-        }
-    }
-
-    pub fn play_with_parameters(&self, paused: bool, channel: &mut channel::Channel) -> ::Status {
-        let mut chan = ffi::FFI::unwrap(channel);
-        
-        match self.get_system_object() {
-            Ok(s) => { 
-                unsafe { ffi::FMOD_System_PlaySound(ffi::FFI::unwrap(&s), ::ChannelIndex::ReUse, self.sound, match paused {
-                    true => 1,
-                    false => 0,
-                }, &mut chan) }
-            }
-            Err(e) => e,
-        }
-    }
-
-    pub fn play_to_the_end(&self) -> ::Status {
-        match self.play() {
-            Ok(mut chan) => {
-                loop {
-                    match chan.is_playing() {
-                        Ok(b) => {
-                            if b == true {
-                                ::std::thread::sleep(Duration::from_millis(30))
-                            } else {
-                                break;
-                            }
-                        },
-                        Err(e) => return e,
-                    }
-                }
-                chan.release();
-                ::Status::Ok
-            }
-            Err(err) => err,
+        match unsafe { ffi::FMOD_System_PlaySound(ffi::FFI::unwrap(&*s), ::ChannelIndex::Free, self.sound, 0, &mut channel) } {
+            ::Status::Ok => Ok(channel::from_ptr(channel, fmod_sys::get_owner(&s))),
+            e => Err(::Error::new(e, "FMOD_System_PlaySound")),
         }
     }
 
-    pub fn set_defaults(&self, frequency: f32, volume: f32, pan: f32, priority: i32) -> ::Status {
-        unsafe { ffi::FMOD_Sound_SetDefaults(self.sound, frequency, volume, pan, priority) }
+    /// Plays the sound on `channel`, stopping what it was playing, or on a free channel if it is
+    /// `None` or no longer valid. Returns the channel the sound is played on.
+    pub fn play_with_parameters(&self, paused: bool, channel: Option<&channel::Channel>)
+                                -> Result<channel::Channel, ::Error> {
+        let s = self.get_system_object()?;
+        let (index, mut chan) = match channel.and_then(|c| c.upgrade()) {
+            Some(c) => (::ChannelIndex::ReUse, ffi::FFI::unwrap(c)),
+            None => (::ChannelIndex::Free, ::std::ptr::null_mut())
+        };
+
+        match unsafe { ffi::FMOD_System_PlaySound(ffi::FFI::unwrap(&*s), index, self.sound, match paused {
+            true => 1,
+            false => 0,
+        }, &mut chan) } {
+            ::Status::Ok => Ok(channel::from_ptr(chan, fmod_sys::get_owner(&s))),
+            e => Err(::Error::new(e, "FMOD_System_PlaySound")),
+        }
+    }
+
+    pub fn play_to_the_end(&self) -> Result<(), ::Error> {
+        let chan = self.play()?;
+
+        while chan.is_playing()? {
+            ::std::thread::sleep(Duration::from_millis(30))
+        }
+        chan.release();
+        Ok(())
+    }
+
+    pub fn set_defaults(&self, frequency: f32, volume: f32, pan: f32, priority: i32) -> Result<(), ::Error> {
+        match unsafe { ffi::FMOD_Sound_SetDefaults(self.sound, frequency, volume, pan, priority) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_Sound_SetDefaults"))
+        }
     }
 
-    pub fn get_defaults(&self) -> Result<(f32, f32, f32, i32), ::Status> {
+    pub fn get_defaults(&self) -> Result<(f32, f32, f32, i32), ::Error> {
         let mut frequency = 0f32;
         let mut volume = 0f32;
         let mut pan = 0f32;
@@ -273,18 +474,21 @@ impl Sound {
         match unsafe { ffi::FMOD_Sound_GetDefaults(self.sound, &mut frequency, &mut volume,
                                                    &mut pan, &mut priority) } {
             ::Status::Ok => Ok((frequency, volume, pan, priority)),
-            e => Err(e),
+            e => Err(::Error::new(e, "FMOD_Sound_GetDefaults")),
         }
     }
 
-    pub fn set_variations(&self, frequency_var: f32, volume_var: f32, pan_var: f32) -> ::Status {
-        unsafe { ffi::FMOD_Sound_SetVariations(self.sound, frequency_var, volume_var, pan_var) }
+    pub fn set_variations(&self, frequency_var: f32, volume_var: f32, pan_var: f32) -> Result<(), ::Error> {
+        match unsafe { ffi::FMOD_Sound_SetVariations(self.sound, frequency_var, volume_var, pan_var) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_Sound_SetVariations"))
+        }
     }
 
     /// Returns:
     ///
     /// Ok(frequency_var, volume_var, pan_var)
-    pub fn get_variations(&self) -> Result<(f32, f32, f32), ::Status> {
+    pub fn get_variations(&self) -> Result<(f32, f32, f32), ::Error> {
         let mut frequency_var = 0f32;
         let mut volume_var = 0f32;
         let mut pan_var = 0f32;
@@ -292,37 +496,43 @@ impl Sound {
         match unsafe { ffi::FMOD_Sound_GetVariations(self.sound, &mut frequency_var,
                                                      &mut volume_var, &mut pan_var) } {
             ::Status::Ok => Ok((frequency_var, volume_var, pan_var)),
-            e => Err(e),
+            e => Err(::Error::new(e, "FMOD_Sound_GetVariations")),
         }
     }
 
-    pub fn set_3D_min_max_distance(&self, min: f32, max: f32) -> ::Status {
-        unsafe { ffi::FMOD_Sound_Set3DMinMaxDistance(self.sound, min, max) }
+    pub fn set_3D_min_max_distance(&self, min: f32, max: f32) -> Result<(), ::Error> {
+        match unsafe { ffi::FMOD_Sound_Set3DMinMaxDistance(self.sound, min, max) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_Sound_Set3DMinMaxDistance"))
+        }
     }
 
     /// Returns:
     ///
     /// Ok(min, max)
-    pub fn get_3D_min_max_distance(&self) -> Result<(f32, f32), ::Status> {
+    pub fn get_3D_min_max_distance(&self) -> Result<(f32, f32), ::Error> {
         let mut max = 0f32;
         let mut min = 0f32;
 
         match unsafe { ffi::FMOD_Sound_Get3DMinMaxDistance(self.sound, &mut min, &mut max) } {
             ::Status::Ok => Ok((min, max)),
-            e => Err(e),
+            e => Err(::Error::new(e, "FMOD_Sound_Get3DMinMaxDistance")),
         }
     }
 
     pub fn set_3D_cone_settings(&self, inside_cone_angle: f32, outside_cone_angle: f32,
-                                outside_volume: f32) -> ::Status {
-        unsafe { ffi::FMOD_Sound_Set3DConeSettings(self.sound, inside_cone_angle,
-                                                   outside_cone_angle, outside_volume) }
+                                outside_volume: f32) -> Result<(), ::Error> {
+        match unsafe { ffi::FMOD_Sound_Set3DConeSettings(self.sound, inside_cone_angle,
+                                                         outside_cone_angle, outside_volume) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_Sound_Set3DConeSettings"))
+        }
     }
 
     /// Returns:
     ///
     /// Ok(inside_cone_angle, outside_cone_angle, outside_volume)
-    pub fn get_3D_cone_settings(&self) -> Result<(f32, f32, f32), ::Status> {
+    pub fn get_3D_cone_settings(&self) -> Result<(f32, f32, f32), ::Error> {
         let mut inside_cone_angle = 0f32;
         let mut outside_cone_angle = 0f32;
         let mut outside_volume = 0f32;
@@ -331,22 +541,25 @@ impl Sound {
                                                          &mut outside_cone_angle,
                                                          &mut outside_volume) } {
             ::Status::Ok => Ok((inside_cone_angle, outside_cone_angle, outside_volume)),
-            e => Err(e),
+            e => Err(::Error::new(e, "FMOD_Sound_Get3DConeSettings")),
         }
     }
 
-    pub fn set_3D_custom_rolloff(&self, points: Vec<vector::Vector>) -> ::Status {
+    pub fn set_3D_custom_rolloff(&self, points: Vec<vector::Vector>) -> Result<(), ::Error> {
         let mut points_vec = Vec::with_capacity(points.len());
 
         for tmp in points.into_iter() {
             points_vec.push(vector::get_ffi(&tmp));
         }
-        unsafe { ffi::FMOD_Sound_Set3DCustomRolloff(self.sound, points_vec.as_mut_ptr(),
-                                                    points_vec.len() as i32) }
+        match unsafe { ffi::FMOD_Sound_Set3DCustomRolloff(self.sound, points_vec.as_mut_ptr(),
+                                                          points_vec.len() as i32) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_Sound_Set3DCustomRolloff"))
+        }
     }
 
     // to test
-    pub fn get_3D_custom_rolloff(&self, num_points: u32) -> Result<Vec<vector::Vector>, ::Status> {
+    pub fn get_3D_custom_rolloff(&self, num_points: u32) -> Result<Vec<vector::Vector>, ::Error> {
         let mut points_vec = Vec::with_capacity(num_points as usize);
         let mut pointer = points_vec.as_mut_ptr();
 
@@ -360,24 +573,28 @@ impl Sound {
                 }
                 Ok(points)
             }
-            e => Err(e),
+            e => Err(::Error::new(e, "FMOD_Sound_Get3DCustomRolloff")),
         }
     }
 
-    pub fn set_sub_sound(&self, index: i32, sub_sound: Sound) -> ::Status {
-        unsafe { ffi::FMOD_Sound_SetSubSound(self.sound, index, sub_sound.sound) }
+    pub fn set_sub_sound(&self, index: i32, sub_sound: &Sound) -> Result<(), ::Error> {
+        match unsafe { ffi::FMOD_Sound_SetSubSound(self.sound, index, sub_sound.sound) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_Sound_SetSubSound"))
+        }
     }
 
-    pub fn get_sub_sound(&self, index: i32) -> Result<Sound, ::Status> {
+    /// Sub-sounds are released with their parent sound.
+    pub fn get_sub_sound(&self, index: i32) -> Result<Borrowed<'_, Sound>, ::Error> {
         let mut sub_sound = ::std::ptr::null_mut();
 
         match unsafe { ffi::FMOD_Sound_GetSubSound(self.sound, index, &mut sub_sound) } {
-            ::Status::Ok => Ok(ffi::FFI::wrap(sub_sound)),
-            e => Err(e),
+            ::Status::Ok => Ok(borrowed::wrap(sub_sound)),
+            e => Err(::Error::new(e, "FMOD_Sound_GetSubSound")),
         }
     }
 
-    pub fn get_name(&self, name_len: usize) -> Result<String, ::Status> {
+    pub fn get_name(&self, name_len: usize) -> Result<String, ::Error> {
         let mut c = Vec::with_capacity(name_len + 1);
 
         for _ in 0..(name_len + 1) {
@@ -387,23 +604,51 @@ impl Sound {
         match unsafe { ffi::FMOD_Sound_GetName(self.sound, c.as_mut_ptr() as *mut c_char,
                                                name_len as i32) } {
             ::Status::Ok => Ok(String::from_utf8(c).unwrap()),
-            e => Err(e),
+            e => Err(::Error::new(e, "FMOD_Sound_GetName")),
         }
     }
 
-    pub fn get_length(&self, TimeUnit(length_type): TimeUnit) -> Result<u32, ::Status> {
+    pub fn get_length(&self, unit: TimeUnit) -> Result<Position, ::Error> {
         let mut length = 0u32;
 
-        match unsafe { ffi::FMOD_Sound_GetLength(self.sound, &mut length, length_type) } {
-            ::Status::Ok => Ok(length),
-            e => Err(e),
+        match unsafe { ffi::FMOD_Sound_GetLength(self.sound, &mut length, unit.bits()) } {
+            ::Status::Ok => Position::new(length, unit).ok_or(::Error::new(::Status::InvalidParam,
+                                                                          "Sound::get_length")),
+            e => Err(::Error::new(e, "FMOD_Sound_GetLength")),
+        }
+    }
+
+    /// Converts `position` to `unit` from the default frequency and the format of the sound.
+    /// Only milliseconds, PCM samples and PCM bytes can be converted, other units fail with
+    /// `Status::InvalidParam`.
+    pub fn convert_position(&self, position: Position,
+                            unit: TimeUnit) -> Result<Position, ::Error> {
+        let (_, _, channels, bits) = self.get_format()?;
+        let (frequency, _, _, _) = self.get_defaults()?;
+
+        match position::convert(position, unit, frequency, (channels * bits / 8) as u32) {
+            Some(p) => Ok(p),
+            None => Err(::Error::new(::Status::InvalidParam, "Sound::convert_position"))
+        }
+    }
+
+    /// Returns the time at which `position` is in the sound, see
+    /// [`Sound::convert_position`](#method.convert_position).
+    pub fn position_to_duration(&self, position: Position) -> Result<Duration, ::Error> {
+        let (frequency, _, _, _) = self.get_defaults()?;
+
+        match self.convert_position(position, ::TIMEUNIT_PCM)? {
+            Position::Pcm(samples) => {
+                Ok(Duration::from_nanos((samples as f64 * 1e9 / frequency as f64) as u64))
+            }
+            _ => Err(::Error::new(::Status::InvalidParam, "Sound::position_to_duration"))
         }
     }
 
     /// Returns:
     ///
     /// Ok(type, format, channels, bits)
-    pub fn get_format(&self) -> Result<(::SoundType, ::SoundFormat, i32, i32), ::Status> {
+    pub fn get_format(&self) -> Result<(::SoundType, ::SoundFormat, i32, i32), ::Error> {
         let mut _type = ::SoundType::Unknown;
         let mut format = ::SoundFormat::None;
         let mut channels = 0i32;
@@ -412,34 +657,43 @@ impl Sound {
         match unsafe { ffi::FMOD_Sound_GetFormat(self.sound, &mut _type, &mut format, &mut channels,
                                                  &mut bits) } {
             ::Status::Ok => Ok((_type, format, channels, bits)),
-            e => Err(e),
+            e => Err(::Error::new(e, "FMOD_Sound_GetFormat")),
         }
     }
 
-    pub fn get_num_sub_sounds(&self) -> Result<i32, ::Status> {
+    pub fn get_num_sub_sounds(&self) -> Result<i32, ::Error> {
         let mut num_sub_sound = 0i32;
 
         match unsafe { ffi::FMOD_Sound_GetNumSubSounds(self.sound, &mut num_sub_sound) } {
             ::Status::Ok => Ok(num_sub_sound),
-            e => Err(e),
+            e => Err(::Error::new(e, "FMOD_Sound_GetNumSubSounds")),
         }
     }
 
     /// Returns:
     ///
     /// Ok(num_tags, num_tags_updated)
-    pub fn get_num_tags(&self) -> Result<(i32, i32), ::Status> {
+    pub fn get_num_tags(&self) -> Result<(i32, i32), ::Error> {
         let mut num_tags = 0i32;
         let mut num_tags_updated = 0i32;
 
         match unsafe { ffi::FMOD_Sound_GetNumTags(self.sound, &mut num_tags, &mut num_tags_updated) } {
             ::Status::Ok => Ok((num_tags, num_tags_updated)),
-            e => Err(e),
+            e => Err(::Error::new(e, "FMOD_Sound_GetNumTags")),
         }
     }
 
-    //to test if tag's data needs to be filled by user
-    pub fn get_tag(&self, name: &str, index: i32) -> Result<FmodTag, ::Status> {
+    /// Retrieves the tag `index` among the ones named `name`, or among all the tags if `name` is
+    /// empty.
+    pub fn get_tag(&self, name: &str, index: i32) -> Result<FmodTag, ::Error> {
+        let c_name = if name.is_empty() {
+            None
+        } else {
+            match CString::new(name) {
+                Ok(c) => Some(c),
+                Err(_) => return Err(::Error::new(::Status::InvalidParam, "FMOD_Sound_GetTag"))
+            }
+        };
         let mut tag = ffi::FMOD_TAG {
             _type: ::TagType::Unknown,
             datatype: ::TagDataType::Binary,
@@ -449,14 +703,35 @@ impl Sound {
             updated: 0,
         };
 
-        match unsafe { ffi::FMOD_Sound_GetTag(self.sound, name.as_ptr() as *const c_char, index,
-                                              &mut tag) } {
+        let name_ptr = c_name.as_ref().map_or(::std::ptr::null(), |c| c.as_ptr());
+
+        match unsafe { ffi::FMOD_Sound_GetTag(self.sound, name_ptr, index, &mut tag) } {
             ::Status::Ok => Ok(FmodTag::from_ptr(tag)),
-            e => Err(e),
+            e => Err(::Error::new(e, "FMOD_Sound_GetTag")),
         }
     }
 
-    pub fn get_open_state(&self) -> Result<(::OpenState, u32, bool, bool), ::Status> {
+    /// Returns an iterator over all the tags of the sound.
+    pub fn tags(&self) -> Result<Tags<'_>, ::Error> {
+        let (num_tags, _) = self.get_num_tags()?;
+
+        Ok(Tags {
+            sound: self,
+            index: 0,
+            num_tags: num_tags
+        })
+    }
+
+    /// Reads the title, artist, album, track number, year, genre and cover art from the ID3v1,
+    /// ID3v2, Vorbis comment and ASF tags of the sound. The first tag found is used when a field
+    /// is set by several of them.
+    pub fn metadata(&self) -> Result<Metadata, ::Error> {
+        let tags = self.tags()?.collect::<Result<Vec<FmodTag>, ::Error>>()?;
+
+        Ok(metadata::from_tags(tags.into_iter()))
+    }
+
+    pub fn get_open_state(&self) -> Result<(::OpenState, u32, bool, bool), ::Error> {
         let mut open_state = ::OpenState::Ready;
         let mut percent_buffered = 0u32;
         let mut starving = 0;
@@ -476,43 +751,46 @@ impl Sound {
                 } else {
                     false
                 })),
-            e => Err(e),
+            e => Err(::Error::new(e, "FMOD_Sound_GetOpenState")),
         }
     }
 
-    pub fn set_sound_group(&self, sound_group: sound_group::SoundGroup) -> ::Status {
-        unsafe { ffi::FMOD_Sound_SetSoundGroup(self.sound, ffi::FFI::unwrap(&sound_group)) }
+    pub fn set_sound_group(&self, sound_group: &sound_group::SoundGroup) -> Result<(), ::Error> {
+        match unsafe { ffi::FMOD_Sound_SetSoundGroup(self.sound, ffi::FFI::unwrap(sound_group)) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_Sound_SetSoundGroup"))
+        }
     }
 
-    pub fn get_sound_group(&self) -> Result<sound_group::SoundGroup, ::Status> {
+    pub fn get_sound_group(&self) -> Result<Borrowed<'_, sound_group::SoundGroup>, ::Error> {
         let mut sound_group = ::std::ptr::null_mut();
 
         match unsafe { ffi::FMOD_Sound_GetSoundGroup(self.sound, &mut sound_group) } {
-            ::Status::Ok => Ok(ffi::FFI::wrap(sound_group)),
-            e => Err(e),
+            ::Status::Ok => Ok(borrowed::wrap(sound_group)),
+            e => Err(::Error::new(e, "FMOD_Sound_GetSoundGroup")),
         }
     }
 
-    pub fn get_num_sync_points(&self) -> Result<i32, ::Status> {
+    pub fn get_num_sync_points(&self) -> Result<i32, ::Error> {
         let mut num_sync_points = 0i32;
 
         match unsafe { ffi::FMOD_Sound_GetNumSyncPoints(self.sound, &mut num_sync_points) } {
             ::Status::Ok => Ok(num_sync_points),
-            e => Err(e),
+            e => Err(::Error::new(e, "FMOD_Sound_GetNumSyncPoints")),
         }
     }
 
-    pub fn get_sync_point(&self, index: i32) -> Result<FmodSyncPoint, ::Status> {
+    pub fn get_sync_point(&self, index: i32) -> Result<FmodSyncPoint, ::Error> {
         let mut sync_point = ::std::ptr::null_mut();
 
         match unsafe { ffi::FMOD_Sound_GetSyncPoint(self.sound, index, &mut sync_point) } {
             ::Status::Ok => Ok(FmodSyncPoint::from_ptr(sync_point)),
-            e => Err(e),
+            e => Err(::Error::new(e, "FMOD_Sound_GetSyncPoint")),
         }
     }
 
     pub fn get_sync_point_info(&self, sync_point: FmodSyncPoint, name_len: usize,
-                               TimeUnit(offset_type): TimeUnit) -> Result<(String, u32), ::Status> {
+                               offset_type: TimeUnit) -> Result<(String, Position), ::Error> {
         let mut offset = 0u32;
         let mut c = Vec::with_capacity(name_len + 1);
 
@@ -523,119 +801,167 @@ impl Sound {
         match unsafe { ffi::FMOD_Sound_GetSyncPointInfo(self.sound, sync_point.sync_point,
                                                         c.as_mut_ptr() as *mut c_char,
                                                         name_len as i32, &mut offset,
-                                                        offset_type) } {
-            ::Status::Ok => Ok((String::from_utf8(c).unwrap(), offset)),
-            e => Err(e),
+                                                        offset_type.bits()) } {
+            ::Status::Ok => match Position::new(offset, offset_type) {
+                Some(offset) => Ok((String::from_utf8(c).unwrap(), offset)),
+                None => Err(::Error::new(::Status::InvalidParam, "Sound::get_sync_point_info"))
+            },
+            e => Err(::Error::new(e, "FMOD_Sound_GetSyncPointInfo")),
         }
     }
 
-    pub fn add_sync_point(&self, offset: u32, TimeUnit(offset_type): TimeUnit,
-                          name: String) -> Result<FmodSyncPoint, ::Status> {
+    pub fn add_sync_point(&self, offset: Position,
+                          name: String) -> Result<FmodSyncPoint, ::Error> {
         let mut sync_point = ::std::ptr::null_mut();
 
-        match unsafe { ffi::FMOD_Sound_AddSyncPoint(self.sound, offset, offset_type,
+        match unsafe { ffi::FMOD_Sound_AddSyncPoint(self.sound, offset.value(),
+                                                    offset.time_unit().bits(),
                                                     name.as_ptr() as *const c_char,
                                                     &mut sync_point) } {
             ::Status::Ok => Ok(FmodSyncPoint::from_ptr(sync_point)),
-            e => Err(e),
+            e => Err(::Error::new(e, "FMOD_Sound_AddSyncPoint")),
         }
     }
 
-    pub fn delete_sync_point(&self, sync_point: FmodSyncPoint) -> ::Status {
-        unsafe { ffi::FMOD_Sound_DeleteSyncPoint(self.sound, sync_point.sync_point) }
+    pub fn delete_sync_point(&self, sync_point: FmodSyncPoint) -> Result<(), ::Error> {
+        match unsafe { ffi::FMOD_Sound_DeleteSyncPoint(self.sound, sync_point.sync_point) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_Sound_DeleteSyncPoint"))
+        }
     }
 
-    pub fn set_mode(&self, Mode(mode): Mode) -> ::Status {
-        unsafe { ffi::FMOD_Sound_SetMode(self.sound, mode) }
+    pub fn set_mode(&self, mode: Mode) -> Result<(), ::Error> {
+        mode.validate()?;
+        match unsafe { ffi::FMOD_Sound_SetMode(self.sound, mode.bits()) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_Sound_SetMode"))
+        }
     }
 
-    pub fn get_mode(&self) -> Result<Mode, ::Status> {
+    pub fn get_mode(&self) -> Result<Mode, ::Error> {
         let mut mode = 0u32;
 
         match unsafe { ffi::FMOD_Sound_GetMode(self.sound, &mut mode) } {
             ::Status::Ok => Ok(Mode(mode)),
-            e => Err(e),
+            e => Err(::Error::new(e, "FMOD_Sound_GetMode")),
         }
     }
 
-    pub fn set_loop_count(&self, loop_count: i32) -> ::Status {
-        unsafe { ffi::FMOD_Sound_SetLoopCount(self.sound, loop_count) }
+    pub fn set_loop_count(&self, loop_count: i32) -> Result<(), ::Error> {
+        match unsafe { ffi::FMOD_Sound_SetLoopCount(self.sound, loop_count) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_Sound_SetLoopCount"))
+        }
     }
 
-    pub fn get_loop_count(&self) -> Result<i32, ::Status> {
+    pub fn get_loop_count(&self) -> Result<i32, ::Error> {
         let mut loop_count = 0i32;
 
         match unsafe { ffi::FMOD_Sound_GetLoopCount(self.sound, &mut loop_count) } {
             ::Status::Ok => Ok(loop_count),
-            e => Err(e),
+            e => Err(::Error::new(e, "FMOD_Sound_GetLoopCount")),
         }
     }
 
-    pub fn set_loop_points(&self, loop_start: u32, TimeUnit(loop_start_type): TimeUnit,
-                           loop_end: u32, TimeUnit(loop_end_type): TimeUnit) -> ::Status {
-        unsafe { ffi::FMOD_Sound_SetLoopPoints(self.sound, loop_start, loop_start_type, loop_end,
-                                               loop_end_type) }
+    pub fn set_loop_points(&self, loop_start: Position,
+                           loop_end: Position) -> Result<(), ::Error> {
+        match unsafe { ffi::FMOD_Sound_SetLoopPoints(self.sound, loop_start.value(),
+                                                     loop_start.time_unit().bits(),
+                                                     loop_end.value(),
+                                                     loop_end.time_unit().bits()) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_Sound_SetLoopPoints"))
+        }
     }
 
     /// Returns:
     ///
     /// Ok(loop_start, loop_end)
-    pub fn get_loop_points(&self, TimeUnit(loop_start_type): TimeUnit,
-                           TimeUnit(loop_end_type): TimeUnit) -> Result<(u32, u32), ::Status> {
+    pub fn get_loop_points(&self, loop_start_type: TimeUnit,
+                           loop_end_type: TimeUnit) -> Result<(Position, Position), ::Error> {
         let mut loop_start = 0u32;
         let mut loop_end = 0u32;
 
-        match unsafe { ffi::FMOD_Sound_GetLoopPoints(self.sound, &mut loop_start, loop_start_type,
-                                                     &mut loop_end, loop_end_type) } {
-            ::Status::Ok => Ok((loop_start, loop_end)),
-            e => Err(e)
+        match unsafe { ffi::FMOD_Sound_GetLoopPoints(self.sound, &mut loop_start,
+                                                     loop_start_type.bits(), &mut loop_end,
+                                                     loop_end_type.bits()) } {
+            ::Status::Ok => match (Position::new(loop_start, loop_start_type),
+                                   Position::new(loop_end, loop_end_type)) {
+                (Some(start), Some(end)) => Ok((start, end)),
+                _ => Err(::Error::new(::Status::InvalidParam, "Sound::get_loop_points"))
+            },
+            e => Err(::Error::new(e, "FMOD_Sound_GetLoopPoints"))
         }
     }
 
-    pub fn get_num_channels(&self) -> Result<i32, ::Status> {
+    pub fn get_num_channels(&self) -> Result<i32, ::Error> {
         let mut num_channels = 0i32;
 
         match unsafe { ffi::FMOD_Sound_GetMusicNumChannels(self.sound, &mut num_channels) } {
             ::Status::Ok => Ok(num_channels),
-            e => Err(e)
+            e => Err(::Error::new(e, "FMOD_Sound_GetMusicNumChannels"))
         }
     }
 
     // TODO: see how to replace i32 channel by Channel struct
-    pub fn set_music_channel_volume(&self, channel: i32, volume: f32) -> ::Status {
-        unsafe { ffi::FMOD_Sound_SetMusicChannelVolume(self.sound, channel, volume) }
+    pub fn set_music_channel_volume(&self, channel: i32, volume: f32) -> Result<(), ::Error> {
+        match unsafe { ffi::FMOD_Sound_SetMusicChannelVolume(self.sound, channel, volume) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_Sound_SetMusicChannelVolume"))
+        }
     }
 
     // TODO: see how to replace i32 channel by Channel struct
-    pub fn get_music_channel_volume(&self, channel: i32) -> Result<f32, ::Status> {
+    pub fn get_music_channel_volume(&self, channel: i32) -> Result<f32, ::Error> {
         let mut volume = 0f32;
 
         match unsafe { ffi::FMOD_Sound_GetMusicChannelVolume(self.sound, channel, &mut volume) } {
             ::Status::Ok => Ok(volume),
-            e => Err(e)
+            e => Err(::Error::new(e, "FMOD_Sound_GetMusicChannelVolume"))
         }
     }
 
-    pub fn set_music_speed(&self, speed: f32) -> ::Status {
-        unsafe { ffi::FMOD_Sound_SetMusicSpeed(self.sound, speed) }
+    pub fn set_music_speed(&self, speed: f32) -> Result<(), ::Error> {
+        match unsafe { ffi::FMOD_Sound_SetMusicSpeed(self.sound, speed) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_Sound_SetMusicSpeed"))
+        }
     }
 
-    pub fn get_music_speed(&self) -> Result<f32, ::Status> {
+    pub fn get_music_speed(&self) -> Result<f32, ::Error> {
         let mut speed = 0f32;
 
         match unsafe { ffi::FMOD_Sound_GetMusicSpeed(self.sound, &mut speed) } {
             ::Status::Ok => Ok(speed),
-            e => Err(e),
+            e => Err(::Error::new(e, "FMOD_Sound_GetMusicSpeed")),
         }
     }
 
-    pub fn set_sub_sound_sentence(&self, sub_sounds: &mut Vec<i32>) -> ::Status {
-        unsafe { ffi::FMOD_Sound_SetSubSoundSentence(self.sound, sub_sounds.as_mut_ptr(),
-                                                     sub_sounds.len() as c_int) }
+    pub fn set_sub_sound_sentence(&self, sub_sounds: &mut Vec<i32>) -> Result<(), ::Error> {
+        match unsafe { ffi::FMOD_Sound_SetSubSoundSentence(self.sound, sub_sounds.as_mut_ptr(),
+                                                           sub_sounds.len() as c_int) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_Sound_SetSubSoundSentence"))
+        }
     }
 
-    pub fn seek_data(&self, pcm: u32) -> ::Status {
-        unsafe { ffi::FMOD_Sound_SeekData(self.sound, pcm) }
+    /// Decodes data of a sound opened with `OPENONLY` into `buffer`, starting from the position
+    /// set with [`Sound::seek_data`](#method.seek_data). Returns the number of bytes read, which
+    /// is 0 once the end of the sound has been reached.
+    pub fn read_data(&self, buffer: &mut [u8]) -> Result<usize, ::Error> {
+        let mut read = 0u32;
+
+        match unsafe { ffi::FMOD_Sound_ReadData(self.sound, buffer.as_mut_ptr() as *mut c_void, buffer.len() as c_uint, &mut read) } {
+            ::Status::Ok | ::Status::FileEOF => Ok(read as usize),
+            e => Err(::Error::new(e, "FMOD_Sound_ReadData"))
+        }
+    }
+
+    pub fn seek_data(&self, pcm: u32) -> Result<(), ::Error> {
+        match unsafe { ffi::FMOD_Sound_SeekData(self.sound, pcm) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_Sound_SeekData"))
+        }
     }
 
     /// Returns:
@@ -643,26 +969,28 @@ impl Sound {
     /// Ok(memory_used, details)
     pub fn get_memory_info(&self, MemoryBits(memory_bits): MemoryBits,
                            EventMemoryBits(event_memory_bits): EventMemoryBits)
-                           -> Result<(u32, MemoryUsageDetails), ::Status> {
+                           -> Result<(u32, MemoryUsageDetails), ::Error> {
         let mut details = fmod_sys::get_memory_usage_details_ffi(Default::default());
         let mut memory_used = 0u32;
 
         match unsafe { ffi::FMOD_Sound_GetMemoryInfo(self.sound, memory_bits, event_memory_bits,
                                                      &mut memory_used, &mut details) } {
             ::Status::Ok => Ok((memory_used, fmod_sys::from_memory_usage_details_ptr(details))),
-            e => Err(e),
+            e => Err(::Error::new(e, "FMOD_Sound_GetMemoryInfo")),
         }
     }
 
-    /// Returns:
+    /// Locks `length` bytes of the sample data, starting at `offset` bytes, for direct reading
+    /// and writing.
     ///
-    /// Ok(ptr1, ptr2)
-    ///
-    /// ptr1: Address of a pointer that will point to the first part of the locked data.
-    ///
-    /// ptr2: Address of a pointer that will point to the second part of the locked data. This will
-    /// be null if the data locked hasn't wrapped at the end of the buffer.
-    pub fn lock(&self, offset: u32, length: u32) -> Result<(Vec<u8>, Vec<u8>), ::Status> {
+    /// The data is unlocked when the returned guard is dropped.
+    pub fn lock<'a>(&'a mut self, offset: u32, length: u32) -> Result<SoundLock<'a>, ::Error> {
+        self.lock_data(offset, length)
+    }
+
+    /* only used while the sound is borrowed, so no guard of Sound::lock can exist meanwhile */
+    fn lock_data<'a>(&'a self, offset: u32, length: u32) -> Result<SoundLock<'a>, ::Error> {
+        let (_, format, _, _) = self.get_format()?;
         let mut len1 = 0u32;
         let mut len2 = 0u32;
         let mut ptr1 = ::std::ptr::null_mut();
@@ -670,161 +998,264 @@ impl Sound {
 
         match unsafe { ffi::FMOD_Sound_Lock(self.sound, offset, length, &mut ptr1, &mut ptr2,
                                             &mut len1, &mut len2) } {
-            ::Status::Ok => {
-                unsafe {
-                    Ok((slice::from_raw_parts(ptr1 as *const u8, len1 as usize).clone().to_vec(),
-                        slice::from_raw_parts(ptr2 as *const u8, len2 as usize).clone().to_vec()))
-                }
+            ::Status::Ok => Ok(SoundLock {
+                sound: self,
+                format: format,
+                ptr1: ptr1,
+                ptr2: ptr2,
+                len1: len1,
+                len2: len2,
+                locked: true,
+            }),
+            e => Err(::Error::new(e, "FMOD_Sound_Lock")),
+        }
+    }
+
+    /// Stores `user_data` in the sound, replacing and freeing the previous one. It is freed
+    /// when the sound is released by the object which created it, and leaked if FMOD releases
+    /// the sound itself, as with sub-sounds.
+    pub fn set_user_data<T: Any + Send>(&mut self, user_data: T) -> Result<(), ::Error> {
+        let data = self.get_sound_data()?;
+
+        if !data.is_null() {
+            unsafe { (*data).user_data = Some(Box::new(user_data)) };
+            return Ok(());
+        }
+
+        let mut data = Box::new(ffi::SoundData::new());
+
+        data.user_data = Some(Box::new(user_data));
+        data.allocated = true;
+        let data = Box::into_raw(data);
+        match unsafe { ffi::FMOD_Sound_SetUserData(self.sound, data as *mut c_void) } {
+            ::Status::Ok => Ok(()),
+            e => {
+                unsafe { drop(Box::from_raw(data)) };
+                Err(::Error::new(e, "FMOD_Sound_SetUserData"))
             }
-            e => Err(e),
         }
     }
 
-    pub fn unlock(&self, v_ptr1: Vec<u8>, v_ptr2: Vec<u8>) -> ::Status {
-        unsafe { ffi::FMOD_Sound_Unlock(self.sound, v_ptr1.as_ptr() as *mut c_void,
-                                        v_ptr2.as_ptr() as *mut c_void, v_ptr1.len() as c_uint,
-                                        v_ptr2.len() as c_uint) }
+    /// Returns the user data if it is a `T`.
+    pub fn get_user_data<T: Any>(&self) -> Option<&T> {
+        match self.get_sound_data() {
+            Ok(data) if !data.is_null() => unsafe {
+                (*data).user_data.as_ref().and_then(|d| d.downcast_ref())
+            },
+            _ => None
+        }
     }
 
-    pub fn set_user_data<'r, T>(&'r mut self, user_data: &'r mut T) -> ::Status {
-        let mut data : *mut c_void = ::std::ptr::null_mut();
+    /* the sound data given to FMOD when the sound was created or by set_user_data, if any */
+    fn get_sound_data(&self) -> Result<*mut ffi::SoundData, ::Error> {
+        let mut data = ::std::ptr::null_mut();
 
-        unsafe {
-            match ffi::FMOD_Sound_GetUserData(self.sound, &mut data) {
-               ::Status::Ok => {
-                    if data.is_null() {
-                        self.user_data.user_data = ::std::ptr::null_mut();
+        match unsafe { ffi::FMOD_Sound_GetUserData(self.sound, &mut data) } {
+            ::Status::Ok => Ok(data as *mut ffi::SoundData),
+            e => Err(::Error::new(e, "FMOD_Sound_GetUserData"))
+        }
+    }
 
-                        ffi::FMOD_Sound_SetUserData(self.sound, transmute(&mut self.user_data))
-                    } else {
-                        let tmp: &mut ffi::SoundData =
-                            transmute::<*mut c_void, &mut ffi::SoundData>(data);
+    /// Exports the sound into a WAV file, see [`write_wav`](#method.write_wav).
+    pub fn save_to_wav(&self, file_name: &str) -> io::Result<()> {
+        let mut file = File::create(file_name)?;
 
-                        tmp.user_data = transmute::<&mut T, *mut c_void>(user_data);
-                        ffi::FMOD_Sound_SetUserData(self.sound, transmute(tmp))
-                    }
-                }
-                _ => {
-                    self.user_data.user_data = transmute::<&mut T, *mut c_void>(user_data);
+        self.write_wav(&mut file)
+    }
 
-                    ffi::FMOD_Sound_SetUserData(self.sound, transmute(&mut self.user_data))
-                }
-            }
+    /// Writes the sound data as a RIFF/WAVE stream into `output`, starting at its current
+    /// position.
+    ///
+    /// Only sounds holding PCM data (PCM8, PCM16, PCM24, PCM32 or PCMFloat) can be exported, so
+    /// streams cannot be written. Sounds with more than 2 channels use the WAVE_FORMAT_EXTENSIBLE
+    /// format tag.
+    pub fn write_wav<W: Write + Seek>(&self, output: &mut W) -> io::Result<()> {
+        let (_, format, channels, bits) = self.get_format()?;
+        let (rate, _, _, _) = self.get_defaults()?;
+        let len_bytes = self.get_length(::TIMEUNIT_PCMBYTES)?.value();
+
+        /* streams can't be locked */
+        wav_format_tag(format)?;
+        let lock = self.lock_data(0, len_bytes)?;
+
+        {
+            let (region1, region2) = lock.data();
+
+            write_wav_data(output, format, channels, bits, rate as i32,
+                           (region1.len() + region2.len()) as u32, |output| {
+                write_pcm_region(output, region1, format)?;
+                write_pcm_region(output, region2, format)
+            })?;
         }
+        lock.unlock()?;
+        Ok(())
+    }
+}
+
+/// Guard returned by [`Sound::lock`](struct.Sound.html#method.lock), giving access to the two
+/// locked regions of the sample data.
+///
+/// The first region starts at the locked offset. The second one is only filled when the lock
+/// wraps around the end of the sample data and is empty otherwise.
+pub struct SoundLock<'a> {
+    sound: &'a Sound,
+    format: ::SoundFormat,
+    ptr1: *mut c_void,
+    ptr2: *mut c_void,
+    len1: u32,
+    len2: u32,
+    locked: bool,
+}
+
+impl<'a> SoundLock<'a> {
+    /// Format of the locked data, as returned by [`Sound::get_format`](struct.Sound.html#method.get_format).
+    pub fn format(&self) -> ::SoundFormat {
+        self.format
     }
 
-    pub fn get_user_data<'r, T>(&'r self) -> Result<&'r mut T, ::Status> {
-        unsafe {
-            let mut user_data : *mut c_void = ::std::ptr::null_mut();
+    pub fn data(&self) -> (&[u8], &[u8]) {
+        unsafe { (const_region(self.ptr1, self.len1), const_region(self.ptr2, self.len2)) }
+    }
 
-            match ffi::FMOD_Sound_GetUserData(self.sound, &mut user_data) {
-               ::Status::Ok => {
-                    if !user_data.is_null() {
-                        let tmp: &mut ffi::SoundData =
-                            transmute::<*mut c_void, &mut ffi::SoundData>(user_data);
-                        let tmp2: &mut T = transmute::<*mut c_void, &mut T>(tmp.user_data);
-                        
-                        Ok(tmp2)
-                    } else {
-                        // ?
-                        Err(::Status::Ok)
-                    }
-                },
-                e => Err(e)
-            }
+    pub fn data_mut(&mut self) -> (&mut [u8], &mut [u8]) {
+        unsafe { (region(self.ptr1, self.len1), region(self.ptr2, self.len2)) }
+    }
+
+    /// Returns the locked regions as 16bit samples, None if the sound format isn't PCM16.
+    pub fn samples_i16_mut(&mut self) -> Option<(&mut [i16], &mut [i16])> {
+        if self.format == ::SoundFormat::PCM16 && is_aligned::<i16>(self.ptr1)
+           && is_aligned::<i16>(self.ptr2) {
+            unsafe { Some((region(self.ptr1, self.len1), region(self.ptr2, self.len2))) }
+        } else {
+            None
         }
     }
 
-    pub fn save_to_wav(&self, file_name: &str) -> Result<bool, String> {
-        unsafe {
-            let mut channels = 0i32;
-            let mut bits = 0i32;
-            let mut rate = 0f32;
-            let len_bytes = match self.get_length(::TIMEUNIT_PCMBYTES) {
-                Ok(l) => l,
-                Err(e) => return Err(format!("{:?}", e))
-            };
-            let mut len1 = 0u32;
-            let mut len2 = 0u32;
-            let mut ptr1: *mut c_void =::std::ptr::null_mut();
-            let mut ptr2: *mut c_void =::std::ptr::null_mut();
-
-            match ffi::FMOD_Sound_GetFormat(self.sound, ::std::ptr::null_mut(),
-                                            ::std::ptr::null_mut(), &mut channels, &mut bits) {
-               ::Status::Ok => match ffi::FMOD_Sound_GetDefaults(self.sound, &mut rate,
-                                                                 ::std::ptr::null_mut(),
-                                                                 ::std::ptr::null_mut(),
-                                                                 ::std::ptr::null_mut()) {
-                   ::Status::Ok => {}
-                    e => return Err(format!("{:?}", e))
-                },
-                e => return Err(format!("{:?}", e))
-            };
-            let fmt_chunk = FmtChunk {
-                chunk: RiffChunk {
-                    id: ['f' as i8, 'm' as i8, 't' as i8, ' ' as i8],
-                    size: mem::size_of::<FmtChunk>() as i32 - mem::size_of::<RiffChunk>() as i32
-                },
-                w_format_tag: 1,
-                n_channels: channels as u16,
-                n_samples_per_sec: rate as u32,
-                n_avg_bytes_per_sec: rate as u32 * channels as u32 * bits as u32 / 8u32,
-                n_block_align: 1u16 * channels as u16 * bits as u16 / 8u16,
-                w_bits_per_sample: bits as u16
-            };
-            let data_chunk = DataChunk {
-                chunk: RiffChunk {
-                    id: ['d' as i8, 'a' as i8, 't' as i8, 'a' as i8],
-                    size: len_bytes as i32
-                }
-            };
-            let wav_header = WavHeader {
-                chunk: RiffChunk {
-                    id: ['R' as i8, 'I' as i8, 'F' as i8, 'F' as i8],
-                    size: mem::size_of::<FmtChunk>() as i32 + mem::size_of::<RiffChunk>() as i32 + len_bytes as i32
-                },
-                riff_type: ['W' as i8, 'A' as i8, 'V' as i8, 'E' as i8]
-            };
+    /// Returns the locked regions as floating point samples, None if the sound format isn't
+    /// PCMFloat.
+    pub fn samples_f32_mut(&mut self) -> Option<(&mut [f32], &mut [f32])> {
+        if self.format == ::SoundFormat::PCMFloat && is_aligned::<f32>(self.ptr1)
+           && is_aligned::<f32>(self.ptr2) {
+            unsafe { Some((region(self.ptr1, self.len1), region(self.ptr2, self.len2))) }
+        } else {
+            None
+        }
+    }
 
-            let mut file = match File::create(file_name) {
-                Ok(f) => f,
-                Err(e) => return Err(format!("{}", e))
-            };
-            let mut wtr = vec![];
+    /// Unlocks the data now, returning the error that dropping the guard would ignore.
+    pub fn unlock(mut self) -> Result<(), ::Error> {
+        self.release()
+    }
 
-            /* wav header */
-            for it in 0usize..4usize {
-                wtr.write_i8(wav_header.chunk.id[it]).unwrap();
-            }
-            wtr.write_i32::<LittleEndian>(wav_header.chunk.size).unwrap();
-            for it in 0usize..4usize {
-                wtr.write_i8(wav_header.riff_type[it]).unwrap();
-            }
+    fn release(&mut self) -> Result<(), ::Error> {
+        if !self.locked {
+            return Ok(());
+        }
+        self.locked = false;
+        match unsafe { ffi::FMOD_Sound_Unlock(self.sound.sound, self.ptr1, self.ptr2, self.len1,
+                                              self.len2) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_Sound_Unlock")),
+        }
+    }
+}
 
-            /* wav chunk */
-            for it in 0usize..4usize {
-                wtr.write_i8(fmt_chunk.chunk.id[it]).unwrap();
-            }
-            wtr.write_i32::<LittleEndian>(fmt_chunk.chunk.size).unwrap();
-            wtr.write_u16::<LittleEndian>(fmt_chunk.w_format_tag).unwrap();
-            wtr.write_u16::<LittleEndian>(fmt_chunk.n_channels).unwrap();
-            wtr.write_u32::<LittleEndian>(fmt_chunk.n_samples_per_sec).unwrap();
-            wtr.write_u32::<LittleEndian>(fmt_chunk.n_avg_bytes_per_sec).unwrap();
-            wtr.write_u16::<LittleEndian>(fmt_chunk.n_block_align).unwrap();
-            wtr.write_u16::<LittleEndian>(fmt_chunk.w_bits_per_sample).unwrap();
-
-            /* wav data chunk */
-            for it in 0usize..4usize {
-                wtr.write_i8(data_chunk.chunk.id[it]).unwrap();
+impl<'a> Drop for SoundLock<'a> {
+    fn drop(&mut self) {
+        let _ = self.release();
+    }
+}
+
+/// Sound returned by
+/// [`Sys::create_sound_from_memory_point`](struct.Sys.html#method.create_sound_from_memory_point),
+/// playing directly from a buffer which has to outlive it.
+pub struct MemorySound<'a> {
+    sound: Sound,
+    data: PhantomData<&'a [u8]>
+}
+
+impl<'a> Deref for MemorySound<'a> {
+    type Target = Sound;
+
+    fn deref(&self) -> &Sound {
+        &self.sound
+    }
+}
+
+pub fn from_sound_with_data<'a>(sound: Sound) -> MemorySound<'a> {
+    MemorySound {
+        sound: sound,
+        data: PhantomData
+    }
+}
+
+/* completion of a non blocking load, set by the non block callback of the sound */
+pub struct LoadState {
+    state: Mutex<(Option<::Status>, Option<Waker>)>
+}
+
+impl LoadState {
+    pub fn new() -> LoadState {
+        LoadState {
+            state: Mutex::new((None, None))
+        }
+    }
+
+    /* the callback is called again when the sound is seeked, only the first call ends the load */
+    pub fn complete(&self, result: ::Status) {
+        if let Ok(mut state) = self.state.lock() {
+            if state.0.is_none() {
+                state.0 = Some(result);
+                if let Some(waker) = state.1.take() {
+                    waker.wake();
+                }
             }
-            wtr.write_i32::<LittleEndian>(data_chunk.chunk.size).unwrap();
+        }
+    }
+}
+
+/// Sound being loaded, returned by
+/// [`Sys::create_sound_async`](struct.Sys.html#method.create_sound_async). It completes when
+/// FMOD has finished opening the sound, the load isn't cancelled by dropping it.
+pub struct SoundFuture {
+    sound: Option<Result<Sound, ::Error>>,
+    state: Arc<LoadState>
+}
+
+pub fn new_future(sound: Result<Sound, ::Error>, state: Arc<LoadState>) -> SoundFuture {
+    SoundFuture {
+        sound: Some(sound),
+        state: state
+    }
+}
 
-            ffi::FMOD_Sound_Lock(self.sound, 0, len_bytes, &mut ptr1, &mut ptr2, &mut len1, &mut len2);
+impl Future for SoundFuture {
+    type Output = Result<Sound, ::Error>;
 
-            file.write_all(&wtr).unwrap();
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<Sound, ::Error>> {
+        let this = self.get_mut();
 
-            ffi::FMOD_Sound_Unlock(self.sound, ptr1, ptr2, len1, len2);
+        if let Some(Err(e)) = this.sound {
+            this.sound = None;
+            return Poll::Ready(Err(e));
+        }
+        let result = match this.state.state.lock() {
+            Ok(mut state) => match state.0 {
+                Some(result) => result,
+                None => {
+                    state.1 = Some(cx.waker().clone());
+                    return Poll::Pending;
+                }
+            },
+            Err(_) => ::Status::Internal
+        };
+
+        match this.sound.take() {
+            Some(Ok(sound)) => match result {
+                ::Status::Ok => Poll::Ready(Ok(sound)),
+                e => Poll::Ready(Err(::Error::new(e, "Sys::create_sound_async")))
+            },
+            _ => panic!("SoundFuture polled after completion")
         }
-        Ok(true)
     }
 }
@@ -0,0 +1,180 @@
+/*
+* Rust-FMOD - Copyright (c) 2014 Gomez Guillaume.
+*
+* The Original software, FMOD library, is provided by FIRELIGHT TECHNOLOGIES.
+*
+* This software is provided 'as-is', without any express or implied warranty.
+* In no event will the authors be held liable for any damages arising from
+* the use of this software.
+*
+* Permission is granted to anyone to use this software for any purpose,
+* including commercial applications, and to alter it and redistribute it
+* freely, subject to the following restrictions:
+*
+* 1. The origin of this software must not be misrepresented; you must not claim
+*    that you wrote the original software. If you use this software in a product,
+*    an acknowledgment in the product documentation would be appreciated but is
+*    not required.
+*
+* 2. Altered source versions must be plainly marked as such, and must not be
+*    misrepresented as being the original software.
+*
+* 3. This notice may not be removed or altered from any source distribution.
+*/
+
+use ffi;
+use fmod_sys::Sys;
+use sound::Sound;
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+use types::{EventMemoryBits, Mode};
+
+struct CacheEntry {
+    sound: Rc<Sound>,
+    memory: u32,
+    last_used: u64
+}
+
+/// Cache of sounds keyed by file name and mode, so a file is only opened once while it is in
+/// use.
+///
+/// The sounds are shared through `Rc` handles. When the memory used by the cached sounds goes
+/// over the budget, the least recently used ones are released, unless a handle to them is still
+/// held outside of the cache or they are playing on a channel.
+pub struct SoundCache {
+    sys: Sys,
+    budget: u32,
+    memory_used: u32,
+    clock: u64,
+    entries: HashMap<(String, u32), CacheEntry>
+}
+
+impl SoundCache {
+    /// Creates an empty cache for the sounds of `sys`, `budget` being the memory in bytes the
+    /// cached sounds should stay under.
+    pub fn new(sys: &Sys, budget: u32) -> SoundCache {
+        SoundCache {
+            sys: sys.clone(),
+            budget: budget,
+            memory_used: 0,
+            clock: 0,
+            entries: HashMap::new()
+        }
+    }
+
+    /// Returns the sound opened from `music` with `mode`, creating it with
+    /// [`Sys::create_sound`](struct.Sys.html#method.create_sound) if it isn't cached yet.
+    pub fn get(&mut self, music: &str, mode: Mode) -> Result<Rc<Sound>, ::Error> {
+        let Mode(m) = mode;
+        let key = (music.to_owned(), m);
+
+        self.clock += 1;
+        if let Some(entry) = self.entries.get_mut(&key) {
+            entry.last_used = self.clock;
+            return Ok(entry.sound.clone());
+        }
+        let sound = Rc::new(self.sys.create_sound(music, Some(mode), None)?);
+        let (memory, _) = sound.get_memory_info(::MEMBITS_ALL,
+                                                EventMemoryBits(::EVENT_MEMBITS_ALL))?;
+
+        self.memory_used += memory;
+        self.entries.insert(key, CacheEntry {
+            sound: sound.clone(),
+            memory: memory,
+            last_used: self.clock
+        });
+        self.trim();
+        Ok(sound)
+    }
+
+    /// Loads all the sounds of `musics` with `mode` ahead of their use.
+    pub fn preload(&mut self, musics: &[&str], mode: Mode) -> Result<(), ::Error> {
+        for music in musics {
+            self.get(music, mode)?;
+        }
+        Ok(())
+    }
+
+    /// Removes the sound opened from `music` with `mode` from the cache. It is released once the
+    /// returned handle and the other ones are dropped.
+    pub fn remove(&mut self, music: &str, mode: Mode) -> Option<Rc<Sound>> {
+        let Mode(m) = mode;
+
+        self.entries.remove(&(music.to_owned(), m)).map(|entry| {
+            self.memory_used -= entry.memory;
+            entry.sound
+        })
+    }
+
+    /// Removes all the sounds from the cache.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.memory_used = 0;
+    }
+
+    /// Releases the least recently used sounds until the cache is under its budget, skipping
+    /// the ones still in use. It is done automatically when a sound is added, but the sounds
+    /// which were playing at that time are only released by a later call.
+    pub fn trim(&mut self) {
+        if self.memory_used <= self.budget {
+            return;
+        }
+        let playing = self.playing_sounds();
+        let mut unused : Vec<((String, u32), u64)> = self.entries.iter().filter(|&(_, entry)| {
+            Rc::strong_count(&entry.sound) == 1 &&
+            !playing.contains(&(ffi::FFI::unwrap(&*entry.sound) as usize))
+        }).map(|(key, entry)| (key.clone(), entry.last_used)).collect();
+
+        unused.sort_by_key(|&(_, last_used)| last_used);
+        for (key, _) in unused {
+            if self.memory_used <= self.budget {
+                break;
+            }
+            if let Some(entry) = self.entries.remove(&key) {
+                self.memory_used -= entry.memory;
+            }
+        }
+    }
+
+    /* FMOD can't tell which channels play a sound, so all of them are checked */
+    fn playing_sounds(&self) -> HashSet<usize> {
+        let mut playing = HashSet::new();
+        let mut index = 0;
+
+        while let Ok(channel) = self.sys.get_channel(index) {
+            if channel.is_playing().unwrap_or(false) {
+                /* the channel can end in the meantime */
+                if let Ok(sound) = channel.get_current_sound() {
+                    playing.insert(ffi::FFI::unwrap(&*sound) as usize);
+                }
+            }
+            index += 1;
+        }
+        playing
+    }
+
+    /// Memory in bytes the cache should stay under.
+    pub fn budget(&self) -> u32 {
+        self.budget
+    }
+
+    /// Changes the budget of the cache, releasing sounds if it is now over it.
+    pub fn set_budget(&mut self, budget: u32) {
+        self.budget = budget;
+        self.trim();
+    }
+
+    /// Memory in bytes used by the cached sounds.
+    pub fn memory_used(&self) -> u32 {
+        self.memory_used
+    }
+
+    /// Number of cached sounds.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
@@ -24,22 +24,28 @@
 
 use types::*;
 use ffi;
+use user_data;
+use std::any::Any;
 use sound;
 use libc::c_void;
 use fmod_sys;
-use fmod_sys::MemoryUsageDetails;
-use std::mem::transmute;
+use fmod_sys::{MemoryUsageDetails, SysHandle};
+use std::sync::Arc;
+use borrowed;
+use borrowed::Borrowed;
 use libc::{c_char};
 use std::default::Default;
 
 /// SoundGroup object
 pub struct SoundGroup {
     sound_group: *mut ffi::FMOD_SOUNDGROUP,
+    /* keeps the system alive, only set on the sound groups releasing their FMOD sound group */
+    owner: Option<Arc<SysHandle>>
 }
 
 impl ffi::FFI<ffi::FMOD_SOUNDGROUP> for SoundGroup {
     fn wrap(s: *mut ffi::FMOD_SOUNDGROUP) -> SoundGroup {
-        SoundGroup {sound_group: s}
+        SoundGroup {sound_group: s, owner: None}
     }
 
     fn unwrap(s: &SoundGroup) -> *mut ffi::FMOD_SOUNDGROUP {
@@ -47,86 +53,112 @@ impl ffi::FFI<ffi::FMOD_SOUNDGROUP> for SoundGroup {
     }
 }
 
+pub fn from_ptr_first(sound_group: *mut ffi::FMOD_SOUNDGROUP,
+                      owner: Option<Arc<SysHandle>>) -> SoundGroup {
+    SoundGroup {sound_group: sound_group, owner: owner}
+}
+
 impl Drop for SoundGroup {
     fn drop(&mut self) {
-        self.release();
+        let _ = self.release();
     }
 }
 
 impl SoundGroup {
-    pub fn release(&mut self) -> ::Status {
-        if !self.sound_group.is_null() {
+    /// Releases the sound group if it was created by this object, borrowed ones are left untouched.
+    pub fn release(&mut self) -> Result<(), ::Error> {
+        if self.owner.is_some() && !self.sound_group.is_null() {
+            let data = self.get_user_data_ptr()?;
+
             match unsafe { ffi::FMOD_SoundGroup_Release(self.sound_group) } {
                ::Status::Ok => {
                     self.sound_group =::std::ptr::null_mut();
-                   ::Status::Ok
+                    self.owner = None;
+                    unsafe { user_data::free(data) };
+                   Ok(())
                 }
-                e => e
+                e => Err(::Error::new(e, "FMOD_SoundGroup_Release"))
             }
         } else {
-           ::Status::Ok
+           Ok(())
         }
     }
 
-    pub fn set_max_audible(&self, max_audible: i32) -> ::Status {
-        unsafe { ffi::FMOD_SoundGroup_SetMaxAudible(self.sound_group, max_audible) }
+    pub fn set_max_audible(&self, max_audible: i32) -> Result<(), ::Error> {
+        match unsafe { ffi::FMOD_SoundGroup_SetMaxAudible(self.sound_group, max_audible) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_SoundGroup_SetMaxAudible"))
+        }
     }
 
-    pub fn get_max_audible(&self) -> Result<i32, ::Status> {
+    pub fn get_max_audible(&self) -> Result<i32, ::Error> {
         let mut max_audible = 0i32;
 
         match unsafe { ffi::FMOD_SoundGroup_GetMaxAudible(self.sound_group, &mut max_audible) } {
             ::Status::Ok => Ok(max_audible),
-            e => Err(e)
+            e => Err(::Error::new(e, "FMOD_SoundGroup_GetMaxAudible"))
         }
     }
 
-    pub fn set_max_audible_behavior(&self, max_audible_behavior: ::SoundGroupBehavior) -> ::Status {
-        unsafe { ffi::FMOD_SoundGroup_SetMaxAudibleBehavior(self.sound_group,
-                                                            max_audible_behavior) }
+    pub fn set_max_audible_behavior(&self, max_audible_behavior: ::SoundGroupBehavior)
+                                    -> Result<(), ::Error> {
+        match unsafe { ffi::FMOD_SoundGroup_SetMaxAudibleBehavior(self.sound_group,
+                                                                  max_audible_behavior) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_SoundGroup_SetMaxAudibleBehavior"))
+        }
     }
 
-    pub fn get_max_audible_behavior(&self) -> Result<::SoundGroupBehavior, ::Status> {
+    pub fn get_max_audible_behavior(&self) -> Result<::SoundGroupBehavior, ::Error> {
         let mut max_audible_behavior = ::SoundGroupBehavior::Fail;
 
         match unsafe { ffi::FMOD_SoundGroup_GetMaxAudibleBehavior(self.sound_group,
                                                                   &mut max_audible_behavior) } {
             ::Status::Ok => Ok(max_audible_behavior),
-            e => Err(e)
+            e => Err(::Error::new(e, "FMOD_SoundGroup_GetMaxAudibleBehavior"))
         }
     }
 
-    pub fn set_mute_fade_speed(&self, speed: f32) -> ::Status {
-        unsafe { ffi::FMOD_SoundGroup_SetMuteFadeSpeed(self.sound_group, speed) }
+    pub fn set_mute_fade_speed(&self, speed: f32) -> Result<(), ::Error> {
+        match unsafe { ffi::FMOD_SoundGroup_SetMuteFadeSpeed(self.sound_group, speed) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_SoundGroup_SetMuteFadeSpeed"))
+        }
     }
 
-    pub fn get_mute_fade_speed(&self) -> Result<f32, ::Status> {
+    pub fn get_mute_fade_speed(&self) -> Result<f32, ::Error> {
         let mut speed = 0f32;
 
         match unsafe { ffi::FMOD_SoundGroup_GetMuteFadeSpeed(self.sound_group, &mut speed) } {
             ::Status::Ok => Ok(speed),
-            e => Err(e)
+            e => Err(::Error::new(e, "FMOD_SoundGroup_GetMuteFadeSpeed"))
         }
     }
 
-    pub fn set_volume(&self, volume: f32) -> ::Status {
-        unsafe { ffi::FMOD_SoundGroup_SetVolume(self.sound_group, volume) }
+    pub fn set_volume(&self, volume: f32) -> Result<(), ::Error> {
+        match unsafe { ffi::FMOD_SoundGroup_SetVolume(self.sound_group, volume) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_SoundGroup_SetVolume"))
+        }
     }
 
-    pub fn get_volume(&self) -> Result<f32, ::Status> {
+    pub fn get_volume(&self) -> Result<f32, ::Error> {
         let mut volume = 0f32;
 
         match unsafe { ffi::FMOD_SoundGroup_GetVolume(self.sound_group, &mut volume) } {
             ::Status::Ok => Ok(volume),
-            e => Err(e)
+            e => Err(::Error::new(e, "FMOD_SoundGroup_GetVolume"))
         }
     }
 
-    pub fn stop(&self) -> ::Status {
-        unsafe { ffi::FMOD_SoundGroup_Stop(self.sound_group) }
+    pub fn stop(&self) -> Result<(), ::Error> {
+        match unsafe { ffi::FMOD_SoundGroup_Stop(self.sound_group) } {
+            ::Status::Ok => Ok(()),
+            e => Err(::Error::new(e, "FMOD_SoundGroup_Stop"))
+        }
     }
 
-    pub fn get_name(&self, name_len: usize) -> Result<String, ::Status> {
+    pub fn get_name(&self, name_len: usize) -> Result<String, ::Error> {
         let mut c = Vec::with_capacity(name_len + 1);
 
         for _ in 0..(name_len + 1) {
@@ -136,34 +168,34 @@ impl SoundGroup {
         match unsafe { ffi::FMOD_SoundGroup_GetName(self.sound_group, c.as_mut_ptr() as *mut c_char,
                                                     name_len as i32) } {
             ::Status::Ok => Ok(String::from_utf8(c).unwrap()),
-            e => Err(e)
+            e => Err(::Error::new(e, "FMOD_SoundGroup_GetName"))
         }
     }
 
-    pub fn get_num_sounds(&self) -> Result<i32, ::Status> {
+    pub fn get_num_sounds(&self) -> Result<i32, ::Error> {
         let mut num_sounds = 0i32;
 
         match unsafe { ffi::FMOD_SoundGroup_GetNumSounds(self.sound_group, &mut num_sounds) } {
             ::Status::Ok => Ok(num_sounds),
-            e => Err(e)
+            e => Err(::Error::new(e, "FMOD_SoundGroup_GetNumSounds"))
         }
     }
 
-    pub fn get_sound(&self, index: i32) -> Result<sound::Sound, ::Status> {
+    pub fn get_sound(&self, index: i32) -> Result<Borrowed<'_, sound::Sound>, ::Error> {
         let mut sound = ::std::ptr::null_mut();
 
         match unsafe { ffi::FMOD_SoundGroup_GetSound(self.sound_group, index, &mut sound) } {
-            ::Status::Ok => Ok(ffi::FFI::wrap(sound)),
-            e => Err(e)
+            ::Status::Ok => Ok(borrowed::wrap(sound)),
+            e => Err(::Error::new(e, "FMOD_SoundGroup_GetSound"))
         }
     }
 
-    pub fn get_num_playing(&self) -> Result<i32, ::Status> {
+    pub fn get_num_playing(&self) -> Result<i32, ::Error> {
         let mut num_playing = 0i32;
 
         match unsafe { ffi::FMOD_SoundGroup_GetNumPlaying(self.sound_group, &mut num_playing) } {
             ::Status::Ok => Ok(num_playing),
-            e => Err(e)
+            e => Err(::Error::new(e, "FMOD_SoundGroup_GetNumPlaying"))
         }
     }
 
@@ -172,32 +204,47 @@ impl SoundGroup {
     /// Ok(memory_used, details)
     pub fn get_memory_info(&self, MemoryBits(memory_bits): MemoryBits,
                            EventMemoryBits(event_memory_bits): EventMemoryBits)
-                           -> Result<(u32, MemoryUsageDetails), ::Status> {
+                           -> Result<(u32, MemoryUsageDetails), ::Error> {
         let mut details = fmod_sys::get_memory_usage_details_ffi(Default::default());
         let mut memory_used = 0u32;
 
-        match unsafe { ffi::FMOD_SoundGroup_GetMemoryInfo(self.sound_group, memory_bits, event_memory_bits, &mut memory_used, &mut details) } {
+        match unsafe { ffi::FMOD_SoundGroup_GetMemoryInfo(self.sound_group, memory_bits,
+                                                          event_memory_bits, &mut memory_used,
+                                                          &mut details) } {
             ::Status::Ok => Ok((memory_used, fmod_sys::from_memory_usage_details_ptr(details))),
-            e => Err(e)
+            e => Err(::Error::new(e, "FMOD_SoundGroup_GetMemoryInfo"))
         }
     }
 
-    pub fn set_user_data<'r, T>(&'r self, user_data: &'r mut T) -> ::Status {
-        unsafe { ffi::FMOD_SoundGroup_SetUserData(self.sound_group, transmute(user_data)) }
+    /// Stores `user_data` in the sound group, replacing and freeing the previous one. It is freed
+    /// when the sound group is released.
+    pub fn set_user_data<T: Any + Send>(&mut self, user_data: T) -> Result<(), ::Error> {
+        let old = self.get_user_data_ptr()?;
+        let data = user_data::into_ptr(user_data);
+
+        match unsafe { ffi::FMOD_SoundGroup_SetUserData(self.sound_group, data) } {
+            ::Status::Ok => {
+                unsafe { user_data::free(old) };
+                Ok(())
+            }
+            e => {
+                unsafe { user_data::free(data) };
+                Err(::Error::new(e, "FMOD_SoundGroup_SetUserData"))
+            }
+        }
     }
 
-    pub fn get_user_data<'r, T>(&'r self) -> Result<&'r mut T, ::Status> {
-        unsafe {
-            let mut user_data : *mut c_void = ::std::ptr::null_mut();
+    /// Returns the user data if it is a `T`.
+    pub fn get_user_data<T: Any>(&self) -> Option<&T> {
+        self.get_user_data_ptr().ok().and_then(|data| unsafe { user_data::downcast(data) })
+    }
 
-            match ffi::FMOD_SoundGroup_GetUserData(self.sound_group, &mut user_data) {
-               ::Status::Ok => {
-                    let tmp : &mut T = transmute::<*mut c_void, &mut T>(user_data);
-                    
-                    Ok(tmp)
-                },
-                e => Err(e)
-            }
+    fn get_user_data_ptr(&self) -> Result<*mut c_void, ::Error> {
+        let mut user_data = ::std::ptr::null_mut();
+
+        match unsafe { ffi::FMOD_SoundGroup_GetUserData(self.sound_group, &mut user_data) } {
+            ::Status::Ok => Ok(user_data),
+            e => Err(::Error::new(e, "FMOD_SoundGroup_GetUserData"))
         }
     }
 }
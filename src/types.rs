@@ -22,17 +22,215 @@
 * 3. This notice may not be removed or altered from any source distribution.
 */
 
-#[derive(Clone, Copy)]
-pub struct Mode(pub u32);
-#[derive(Clone, Copy)]
-pub struct TimeUnit(pub u32);
-#[derive(Clone, Copy)]
-pub struct FmodCaps(pub u32);
+use std::fmt;
+use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, Not};
+
+macro_rules! flags {
+    ($(#[$attr:meta])* pub struct $name:ident { $($flag:ident),* }) => {
+        $(#[$attr])*
+        #[derive(Clone, Copy, PartialEq, Eq, Hash, Default)]
+        pub struct $name(pub u32);
+
+        impl $name {
+            /// Returns a value with no flag set.
+            pub fn empty() -> $name {
+                $name(0)
+            }
+
+            /// Returns the raw value given to FMOD.
+            pub fn bits(&self) -> u32 {
+                self.0
+            }
+
+            pub fn is_empty(&self) -> bool {
+                self.0 == 0
+            }
+
+            /// Returns `true` if all the flags of `other` are set.
+            pub fn contains(&self, other: $name) -> bool {
+                self.0 & other.0 == other.0
+            }
+
+            /// Returns `true` if any of the flags of `other` is set.
+            pub fn intersects(&self, other: $name) -> bool {
+                self.0 & other.0 != 0
+            }
+
+            pub fn insert(&mut self, other: $name) {
+                self.0 |= other.0;
+            }
+
+            pub fn remove(&mut self, other: $name) {
+                self.0 &= !other.0;
+            }
+
+            fn names() -> &'static [(&'static str, $name)] {
+                const NAMES: &[(&str, $name)] = &[$((stringify!($flag), ::$flag)),*];
+
+                NAMES
+            }
+        }
+
+        impl BitOr for $name {
+            type Output = $name;
+
+            fn bitor(self, other: $name) -> $name {
+                $name(self.0 | other.0)
+            }
+        }
+
+        impl BitOrAssign for $name {
+            fn bitor_assign(&mut self, other: $name) {
+                self.0 |= other.0;
+            }
+        }
+
+        impl BitAnd for $name {
+            type Output = $name;
+
+            fn bitand(self, other: $name) -> $name {
+                $name(self.0 & other.0)
+            }
+        }
+
+        impl BitAndAssign for $name {
+            fn bitand_assign(&mut self, other: $name) {
+                self.0 &= other.0;
+            }
+        }
+
+        impl Not for $name {
+            type Output = $name;
+
+            fn not(self) -> $name {
+                $name(!self.0)
+            }
+        }
+
+        impl fmt::Debug for $name {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, concat!(stringify!($name), "("))?;
+                write_flags(f, self.0, $name::names().iter().map(|&(name, $name(v))| (name, v)))?;
+                write!(f, ")")
+            }
+        }
+    }
+}
+
+/* lists the names of the single bit flags set, a value matching a named one exactly (like
+   MEMBITS_ALL or DEFAULT) is written with its name */
+fn write_flags<'a, I>(f: &mut fmt::Formatter, value: u32, names: I) -> fmt::Result
+    where I: Iterator<Item = (&'a str, u32)> + Clone {
+    if let Some((name, _)) = names.clone().find(|&(_, v)| v == value) {
+        return write!(f, "{}", name);
+    }
+    let mut remaining = value;
+    let mut first = true;
+
+    for (name, v) in names.filter(|&(_, v)| v.count_ones() == 1) {
+        if value & v != 0 {
+            write!(f, "{}{}", if first { "" } else { " | " }, name)?;
+            remaining &= !v;
+            first = false;
+        }
+    }
+    if remaining != 0 || first {
+        write!(f, "{}{:#x}", if first { "" } else { " | " }, remaining)?;
+    }
+    Ok(())
+}
+
+flags! {
+    /// Flags describing how a sound is opened and played, see the `DEFAULT`, `LOOP_*`, `_2D`,
+    /// `_3D`... constants.
+    pub struct Mode {
+        DEFAULT, LOOP_OFF, LOOP_NORMAL, LOOP_BIDI, _2D, _3D, HARDWARE, SOFTWARE, CREATESTREAM,
+        CREATESAMPLE, CREATECOMPRESSEDSAMPLE, OPENUSER, OPENMEMORY, OPENMEMORY_POINT, OPENRAW,
+        OPENONLY, ACCURATETIME, MPEGSEARCH, NONBLOCKING, UNIQUE, _3D_HEADRELATIVE,
+        _3D_WORLDRELATIVE, _3D_INVERSEROLLOFF, _3D_LINEARROLLOFF, _3D_LINEARSQUAREROLLOFF,
+        _3D_CUSTOMROLLOFF, _3D_IGNOREGEOMETRY, UNICODE, IGNORETAGS, LOWMEM, LOADSECONDARYRAM,
+        VIRTUAL_PLAYFROMSTART
+    }
+}
+
+flags! {
+    /// Units in which a time or a length is given, see the `TIMEUNIT_*` constants.
+    pub struct TimeUnit {
+        TIMEUNIT_MS, TIMEUNIT_PCM, TIMEUNIT_PCMBYTES, TIMEUNIT_RAWBYTES, TIMEUNIT_PCMFRACTION,
+        TIMEUNIT_MODORDER, TIMEUNIT_MODROW, TIMEUNIT_MODPATTERN, TIMEUNIT_SENTENCE_MS,
+        TIMEUNIT_SENTENCE_PCM, TIMEUNIT_SENTENCE_PCMBYTES, TIMEUNIT_SENTENCE,
+        TIMEUNIT_SENTENCE_SUBSOUND, TIMEUNIT_BUFFERED
+    }
+}
+
+flags! {
+    /// Capabilities of a sound card, see the `CAPS_*` constants.
+    pub struct FmodCaps {
+        CAPS_NONE, CAPS_HARDWARE, CAPS_HARDWARE_EMULATED, CAPS_OUTPUT_MULTICHANNEL,
+        CAPS_OUTPUT_FORMAT_PCM8, CAPS_OUTPUT_FORMAT_PCM16, CAPS_OUTPUT_FORMAT_PCM24,
+        CAPS_OUTPUT_FORMAT_PCM32, CAPS_OUTPUT_FORMAT_PCMFLOAT, CAPS_REVERB_LIMITED,
+        CAPS_LOOPBACK
+    }
+}
+
+flags! {
+    /// Flags given to [`SysBuilder::init_with_parameters`](../struct.SysBuilder.html#method.init_with_parameters), see the `INIT_*`
+    /// constants.
+    pub struct InitFlag {
+        INIT_NORMAL, INIT_STREAM_FROM_UPDATE, INIT_3D_RIGHTHANDED, INIT_SOFTWARE_DISABLE,
+        INIT_OCCLUSION_LOWPASS, INIT_HRTF_LOWPASS, INIT_DISTANCE_FILTERING,
+        INIT_REVERB_PREALLOCBUFFERS, INIT_ENABLE_PROFILE, INIT_VOL0_BECOMES_VIRTUAL,
+        INIT_WASAPI_EXCLUSIVE, INIT_PS3_PREFERDTS, INIT_PS3_FORCE2CHLPCM, INIT_DISABLEDOLBY,
+        INIT_SYSTEM_MUSICMUTENOTPAUSE, INIT_SYNCMIXERWITHUPDATE, INIT_GEOMETRY_USECLOSEST,
+        INIT_DISABLE_MYEARS_AUTODETECT, INIT_PS3_DISABLEDTS, INIT_PS3_DISABLEDOLBYDIGITAL,
+        INIT_7POINT1_DOLBYMAPPING
+    }
+}
+
+flags! {
+    /// Types of memory reported by the `get_memory_info` methods, see the `MEMBITS_*`
+    /// constants.
+    pub struct MemoryBits {
+        MEMBITS_OTHER, MEMBITS_STRING, MEMBITS_SYSTEM, MEMBITS_PLUGINS, MEMBITS_OUTPUT,
+        MEMBITS_CHANNEL, MEMBITS_CHANNELGROUP, MEMBITS_CODEC, MEMBITS_FILE, MEMBITS_SOUND,
+        MEMBITS_SOUND_SECONDARYRAM, MEMBITS_SOUNDGROUP, MEMBITS_STREAMBUFFER,
+        MEMBITS_DSPCONNECTION, MEMBITS_DSP, MEMBITS_DSPCODEC, MEMBITS_PROFILE,
+        MEMBITS_RECORDBUFFER, MEMBITS_REVERB, MEMBITS_REVERBCHANNELPROPS, MEMBITS_GEOMETRY,
+        MEMBITS_SYNCPOINT, MEMBITS_ALL
+    }
+}
+
+/* groups of mode flags of which only one can be set */
+const EXCLUSIVE_MODES: &[(u32, &str)] = &[
+    (::LOOP_OFF.0 | ::LOOP_NORMAL.0 | ::LOOP_BIDI.0,
+     "LOOP_OFF, LOOP_NORMAL and LOOP_BIDI can't be combined"),
+    (::_2D.0 | ::_3D.0, "_2D and _3D can't be combined"),
+    (::HARDWARE.0 | ::SOFTWARE.0, "HARDWARE and SOFTWARE can't be combined"),
+    (::CREATESTREAM.0 | ::CREATESAMPLE.0 | ::CREATECOMPRESSEDSAMPLE.0,
+     "CREATESTREAM, CREATESAMPLE and CREATECOMPRESSEDSAMPLE can't be combined"),
+    (::OPENMEMORY.0 | ::OPENMEMORY_POINT.0, "OPENMEMORY and OPENMEMORY_POINT can't be combined"),
+    (::_3D_HEADRELATIVE.0 | ::_3D_WORLDRELATIVE.0,
+     "_3D_HEADRELATIVE and _3D_WORLDRELATIVE can't be combined"),
+    (::_3D_INVERSEROLLOFF.0 | ::_3D_LINEARROLLOFF.0 | ::_3D_LINEARSQUAREROLLOFF.0 |
+     ::_3D_CUSTOMROLLOFF.0, "only one of the _3D_*ROLLOFF flags can be set")
+];
+
+impl Mode {
+    /// Fails with `Status::InvalidParam` if flags overriding each other are combined, like
+    /// `LOOP_OFF | LOOP_NORMAL` or `_2D | _3D`. It is checked by the functions creating a sound
+    /// before calling FMOD.
+    pub fn validate(&self) -> Result<(), ::Error> {
+        for &(group, detail) in EXCLUSIVE_MODES {
+            if (self.0 & group).count_ones() > 1 {
+                return Err(::Error::with_detail(::Status::InvalidParam, "Mode::validate",
+                                                detail));
+            }
+        }
+        Ok(())
+    }
+}
+
 #[derive(Clone, Copy)]
 pub struct PluginHandle(pub u32);
 #[derive(Clone, Copy)]
-pub struct InitFlag(pub u32);
-#[derive(Clone, Copy)]
-pub struct MemoryBits(pub u32);
-#[derive(Clone, Copy)]
 pub struct EventMemoryBits(pub u32);
@@ -0,0 +1,51 @@
+/*
+* Rust-FMOD - Copyright (c) 2014 Gomez Guillaume.
+*
+* The Original software, FMOD library, is provided by FIRELIGHT TECHNOLOGIES.
+*
+* This software is provided 'as-is', without any express or implied warranty.
+* In no event will the authors be held liable for any damages arising from
+* the use of this software.
+*
+* Permission is granted to anyone to use this software for any purpose,
+* including commercial applications, and to alter it and redistribute it
+* freely, subject to the following restrictions:
+*
+* 1. The origin of this software must not be misrepresented; you must not claim
+*    that you wrote the original software. If you use this software in a product,
+*    an acknowledgment in the product documentation would be appreciated but is
+*    not required.
+*
+* 2. Altered source versions must be plainly marked as such, and must not be
+*    misrepresented as being the original software.
+*
+* 3. This notice may not be removed or altered from any source distribution.
+*/
+
+
+use libc::c_void;
+use std::any::Any;
+
+/// Data attached to an FMOD object with `set_user_data`, read back with a checked downcast by
+/// `get_user_data`.
+pub type UserData = Box<dyn Any + Send>;
+
+/* for the objects whose FMOD user data only holds the user's one, boxed twice since FMOD keeps a
+   thin pointer */
+pub fn into_ptr<T: Any + Send>(user_data: T) -> *mut c_void {
+    Box::into_raw(Box::new(Box::new(user_data) as UserData)) as *mut c_void
+}
+
+pub unsafe fn free(user_data: *mut c_void) {
+    if !user_data.is_null() {
+        drop(Box::from_raw(user_data as *mut UserData));
+    }
+}
+
+pub unsafe fn downcast<'a, T: Any>(user_data: *mut c_void) -> Option<&'a T> {
+    if user_data.is_null() {
+        None
+    } else {
+        (*(user_data as *mut UserData)).downcast_ref()
+    }
+}